@@ -7,10 +7,11 @@
 // except according to those terms.
 
 use autocxx_parser::file_locations::FileLocationStrategy;
+use autocxx_parser::MultiBindings;
 use miette::Diagnostic;
 use thiserror::Error;
 
-use crate::{generate_rs_single, CodegenOptions};
+use crate::{generate_rs_single, CodegenOptions, CppStandard, ParsedFile};
 use crate::{get_cxx_header_bytes, CppCodegenOptions, ParseError, RebuildDependencyRecorder};
 use std::ffi::OsStr;
 use std::ffi::OsString;
@@ -35,8 +36,17 @@ pub enum BuilderError {
     NoIncludeCxxMacrosFound,
     #[error("could not create a directory {1}: {0}")]
     UnableToCreateDirectory(std::io::Error, PathBuf),
+    #[error("libclang is unavailable, and we couldn't read the precompiled bindings at {1}: {0}")]
+    PrecompiledBindingsUnreadable(std::io::Error, PathBuf),
+    #[error("libclang is unavailable, and the precompiled bindings archive {1} couldn't be interpreted: {0}")]
+    PrecompiledBindingsInvalid(serde_json::Error, PathBuf),
+    #[error("libclang is unavailable, and the precompiled bindings archive {0} doesn't contain bindings matching the current contents of the include_cpp! macro for mod {1}. Regenerate the archive using autocxx-gen.")]
+    PrecompiledBindingsOutOfDate(PathBuf, String),
 }
 
+/// The name of the archive of Rust bindings produced by `autocxx-gen --gen-rs-archive`.
+static PRECOMPILED_RS_ARCHIVE: &str = "gen.rs.json";
+
 #[cfg_attr(feature = "nightly", doc(cfg(feature = "build")))]
 pub type BuilderBuild = cc::Build;
 
@@ -80,6 +90,7 @@ pub struct Builder<'a, BuilderContext> {
     extra_clang_args: Vec<String>,
     dependency_recorder: Option<Box<dyn RebuildDependencyRecorder>>,
     custom_gendir: Option<PathBuf>,
+    precompiled_bindings: Option<PathBuf>,
     auto_allowlist: bool,
     codegen_options: CodegenOptions<'a>,
     // This member is to ensure that this type is parameterized
@@ -115,6 +126,7 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
             extra_clang_args: Vec::new(),
             dependency_recorder: CTX::get_dependency_recorder(),
             custom_gendir: None,
+            precompiled_bindings: None,
             auto_allowlist: false,
             codegen_options: CodegenOptions::default(),
             ctx: PhantomData,
@@ -122,8 +134,8 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
     }
 
     /// Specify extra arguments for clang. These are used when parsing
-    /// C++ headers. For example, you might want to provide
-    /// `-std=c++17` to specify C++17.
+    /// C++ headers. To choose a C++ version, use [`Self::cpp_standard`]
+    /// instead.
     pub fn extra_clang_args(mut self, extra_clang_args: &[&str]) -> Self {
         self.extra_clang_args = extra_clang_args.iter().map(|s| s.to_string()).collect();
         self
@@ -135,6 +147,18 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
         self
     }
 
+    /// A directory containing bindings previously generated by running
+    /// `autocxx-gen --gen-rs-archive --gen-cpp` on this same `.rs` file.
+    /// If libclang can't be found at build time, these will be used instead
+    /// of generating bindings afresh, so that downstream crates can be built
+    /// without a clang toolchain. The build will fail if the contents of any
+    /// `include_cpp!` macro have changed since the archive was generated.
+    /// If libclang is available, this has no effect.
+    pub fn precompiled_bindings(mut self, precompiled_bindings: PathBuf) -> Self {
+        self.precompiled_bindings = Some(precompiled_bindings);
+        self
+    }
+
     /// Update C++ code generation options. See [`CppCodegenOptions`] for details.
     pub fn cpp_codegen_options<F>(mut self, modifier: F) -> Self
     where
@@ -183,6 +207,23 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
         self
     }
 
+    /// Split the wrapper functions generated by autocxx across this many
+    /// C++ files, so that they can be compiled in parallel. See
+    /// [`CppCodegenOptions::cpp_file_shards`].
+    pub fn cpp_file_shards(mut self, shards: usize) -> Self {
+        self.codegen_options.cpp_codegen_options.cpp_file_shards = Some(shards);
+        self
+    }
+
+    /// The version of the C++ standard to use. This is used both when
+    /// parsing the C++ headers and when building the generated C++ code
+    /// using the [`cc::Build`] returned from [`Self::build`].
+    /// The default is C++14.
+    pub fn cpp_standard(mut self, cpp_standard: CppStandard) -> Self {
+        self.codegen_options.cpp_codegen_options.cpp_standard = cpp_standard;
+        self
+    }
+
     /// An annotation optionally to include on each C++ function.
     /// For example to export the symbol from a library.
     pub fn cxx_impl_annotations(mut self, cxx_impl_annotations: Option<String>) -> Self {
@@ -208,8 +249,8 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
     /// For example, if you wish to set the C++ version to C++17, you might
     /// be tempted to use [`cc::Build::flag_if_supported`] to add the
     /// `-std=c++17` flag. However, this won't affect the header parsing which
-    /// autocxx does internally (by means of bindgen) so you _additionally_
-    /// should call [`extra_clang_args`] with that same option.
+    /// autocxx does internally (by means of bindgen), so instead you should
+    /// call [`Self::cpp_standard`], which does both.
     pub fn build(self) -> Result<BuilderBuild, BuilderError> {
         self.build_listing_files().map(|r| r.0)
     }
@@ -254,6 +295,24 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
 
         let mut parsed_file = crate::parse_file(self.rs_file, self.auto_allowlist)
             .map_err(BuilderError::ParseError)?;
+        if let Some(precompiled_dir) = self.precompiled_bindings {
+            if !libclang_is_available() {
+                log::warn!(
+                    "libclang is unavailable; using precompiled bindings from {}",
+                    precompiled_dir.display()
+                );
+                return build_from_precompiled(
+                    &parsed_file,
+                    &precompiled_dir,
+                    self.codegen_options.cpp_codegen_options.cpp_standard,
+                    &autocxx_inc,
+                    &incdir,
+                    &cxxdir,
+                    &rsdir,
+                    self.dependency_recorder,
+                );
+            }
+        }
         parsed_file
             .resolve_all(
                 autocxx_inc,
@@ -263,11 +322,7 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
             )
             .map_err(BuilderError::ParseError)?;
         let mut counter = 0;
-        let mut builder = cc::Build::new();
-        builder.cpp(true);
-        if std::env::var_os("AUTOCXX_ASAN").is_some() {
-            builder.flag_if_supported("-fsanitize=address");
-        }
+        let mut builder = make_cc_build(self.codegen_options.cpp_codegen_options.cpp_standard);
         let mut generated_rs = Vec::new();
         let mut generated_cpp = Vec::new();
         builder.includes(parsed_file.include_dirs());
@@ -276,9 +331,9 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
                 .generate_h_and_cxx(&self.codegen_options.cpp_codegen_options)
                 .map_err(BuilderError::InvalidCxx)?;
             for filepair in generated_code.0 {
-                let fname = format!("gen{counter}.cxx");
-                counter += 1;
-                if let Some(implementation) = &filepair.implementation {
+                for implementation in filepair.implementations() {
+                    let fname = format!("gen{counter}.cxx");
+                    counter += 1;
                     let gen_cxx_path = write_to_file(&cxxdir, &fname, implementation)?;
                     builder.file(&gen_cxx_path);
                     generated_cpp.push(gen_cxx_path);
@@ -300,6 +355,118 @@ impl<CTX: BuilderContext> Builder<'_, CTX> {
     }
 }
 
+fn make_cc_build(cpp_standard: CppStandard) -> BuilderBuild {
+    let mut builder = cc::Build::new();
+    builder.cpp(true);
+    builder
+        .flag_if_supported(cpp_standard.clang_arg())
+        .flag_if_supported(cpp_standard.msvc_arg());
+    if std::env::var_os("AUTOCXX_ASAN").is_some() {
+        builder.flag_if_supported("-fsanitize=address");
+    }
+    builder
+}
+
+/// Whether libclang can be found. If it's linked statically, it's always
+/// available.
+fn libclang_is_available() -> bool {
+    #[cfg(feature = "runtime")]
+    {
+        clang_sys::is_loaded() || clang_sys::load().is_ok()
+    }
+    #[cfg(not(feature = "runtime"))]
+    {
+        true
+    }
+}
+
+/// Instead of parsing the C++ headers, lay out the bindings stored in
+/// `precompiled_dir` by an earlier run of `autocxx-gen` such that the
+/// rest of the build proceeds exactly as if we'd generated them.
+#[allow(clippy::too_many_arguments)] // the same state as the normal build path
+fn build_from_precompiled(
+    parsed_file: &ParsedFile,
+    precompiled_dir: &Path,
+    cpp_standard: CppStandard,
+    autocxx_inc: &[PathBuf],
+    incdir: &Path,
+    cxxdir: &Path,
+    rsdir: &Path,
+    dependency_recorder: Option<Box<dyn RebuildDependencyRecorder>>,
+) -> Result<BuilderSuccess, BuilderError> {
+    let record_dependency = |path: &Path| {
+        if let Some(dependency_recorder) = &dependency_recorder {
+            dependency_recorder.record_header_file_dependency(&path.to_string_lossy());
+        }
+    };
+    let archive_path = precompiled_dir.join(PRECOMPILED_RS_ARCHIVE);
+    let archive = std::fs::read(&archive_path)
+        .map_err(|e| BuilderError::PrecompiledBindingsUnreadable(e, archive_path.clone()))?;
+    record_dependency(&archive_path);
+    let archive: MultiBindings = serde_json::from_slice(&archive)
+        .map_err(|e| BuilderError::PrecompiledBindingsInvalid(e, archive_path.clone()))?;
+
+    let mut generated_rs = Vec::new();
+    for include_cpp in parsed_file.get_autocxxes() {
+        // The archive is keyed by a hash of the include_cpp! configuration,
+        // so this also confirms the bindings are still up to date.
+        let rs = include_cpp.get_precompiled_rs(&archive).map_err(|_| {
+            BuilderError::PrecompiledBindingsOutOfDate(
+                archive_path.clone(),
+                include_cpp.get_mod_name(),
+            )
+        })?;
+        generated_rs.push(write_to_file(
+            rsdir,
+            &include_cpp.get_rs_filename(),
+            rs.to_string().as_bytes(),
+        )?);
+    }
+
+    let mut builder = make_cc_build(cpp_standard);
+    builder.includes(autocxx_inc);
+    let mut generated_cpp = Vec::new();
+    let mut counter = 0;
+    let mut precompiled_files = std::fs::read_dir(precompiled_dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()
+        })
+        .map_err(|e| {
+            BuilderError::PrecompiledBindingsUnreadable(e, precompiled_dir.to_path_buf())
+        })?;
+    precompiled_files.sort();
+    for path in precompiled_files {
+        let fname = match path.file_name().and_then(OsStr::to_str) {
+            // We've already written cxx.h.
+            None | Some("cxx.h") => continue,
+            Some(fname) => fname,
+        };
+        let is_implementation = match path.extension().and_then(OsStr::to_str) {
+            Some("h" | "hh" | "hpp") => false,
+            Some("cc" | "cpp" | "cxx") => true,
+            _ => continue,
+        };
+        let content = std::fs::read(&path)
+            .map_err(|e| BuilderError::PrecompiledBindingsUnreadable(e, path.clone()))?;
+        record_dependency(&path);
+        if is_implementation {
+            let gen_cxx_path = write_to_file(cxxdir, fname, &content)?;
+            builder.file(&gen_cxx_path);
+            generated_cpp.push(gen_cxx_path);
+            counter += 1;
+        } else {
+            generated_cpp.push(write_to_file(incdir, fname, &content)?);
+        }
+    }
+    if counter == 0 {
+        Err(BuilderError::NoIncludeCxxMacrosFound)
+    } else {
+        Ok(BuilderSuccess(builder, generated_rs, generated_cpp))
+    }
+}
+
 fn ensure_created(dir: &Path) -> Result<(), BuilderError> {
     std::fs::create_dir_all(dir)
         .map_err(|e| BuilderError::UnableToCreateDirectory(e, dir.to_path_buf()))
@@ -340,3 +507,87 @@ fn rust_version_check() {
         panic!("Rust 1.54 or later is required.")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use autocxx_parser::{IncludeCppConfig, MultiBindings};
+    use proc_macro2::Span;
+    use quote::quote;
+    use syn::parse_quote;
+    use tempfile::tempdir;
+
+    use super::{build_from_precompiled, BuilderError, BuilderSuccess, CppStandard};
+
+    static RS: &str = r#"
+        autocxx::include_cpp! {
+            #include "input.h"
+            safety!(unsafe_ffi)
+            generate!("do_math")
+        }
+    "#;
+
+    fn build(rs: &str, dir: &Path) -> Result<BuilderSuccess, BuilderError> {
+        let rs_file = dir.join("main.rs");
+        std::fs::write(&rs_file, rs).unwrap();
+        let [incdir, cxxdir, rsdir]: [PathBuf; 3] =
+            ["include", "cxx", "rs"].map(|subdir| dir.join("out").join(subdir));
+        for outdir in [&incdir, &cxxdir, &rsdir] {
+            std::fs::create_dir_all(outdir).unwrap();
+        }
+        let parsed_file = crate::parse_file(&rs_file, false).unwrap();
+        build_from_precompiled(
+            &parsed_file,
+            &dir.join("precompiled"),
+            CppStandard::default(),
+            &[],
+            &incdir,
+            &cxxdir,
+            &rsdir,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_precompiled_bindings() {
+        let tmp_dir = tempdir().unwrap();
+        let precompiled = tmp_dir.path().join("precompiled");
+        std::fs::create_dir(&precompiled).unwrap();
+        let hexathorpe = syn::token::Pound(Span::call_site());
+        let config: IncludeCppConfig = parse_quote! {
+            #hexathorpe include "input.h"
+            safety!(unsafe_ffi)
+            generate!("do_math")
+        };
+        let mut archive = MultiBindings::default();
+        archive.insert(&config, quote! { mod ffi {} });
+        std::fs::write(
+            precompiled.join("gen.rs.json"),
+            serde_json::to_string(&archive).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(precompiled.join("gen0.cc"), "// implementation").unwrap();
+        std::fs::write(precompiled.join("autocxxgen_ffi.h"), "// header").unwrap();
+
+        let BuilderSuccess(_, generated_rs, generated_cpp) = build(RS, tmp_dir.path()).unwrap();
+        let outdir = tmp_dir.path().join("out");
+        assert_eq!(
+            generated_rs,
+            vec![outdir.join("rs").join("autocxx-ffi-default-gen.rs")]
+        );
+        assert_eq!(
+            generated_cpp,
+            vec![
+                outdir.join("include").join("autocxxgen_ffi.h"),
+                outdir.join("cxx").join("gen0.cc")
+            ]
+        );
+
+        // Once the include_cpp! contents change, the archive is out of date.
+        assert!(matches!(
+            build(&RS.replace("do_math", "do_other_math"), tmp_dir.path()),
+            Err(BuilderError::PrecompiledBindingsOutOfDate(..))
+        ));
+    }
+}
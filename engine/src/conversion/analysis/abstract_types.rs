@@ -11,8 +11,8 @@ use syn::{punctuated::Punctuated, token::Comma, FnArg};
 
 use super::{
     fun::{
-        FnAnalysis, FnKind, FnPhase, FnPrePhase2, MethodKind, PodAndConstructorAnalysis,
-        TraitMethodKind,
+        function_wrapper::CppFunctionBody, FnAnalysis, FnKind, FnPhase, FnPrePhase2, MethodKind,
+        PodAndConstructorAnalysis, TraitMethodKind,
     },
     pod::PodAnalysis,
 };
@@ -161,7 +161,7 @@ pub(crate) fn mark_types_abstract(apis: ApiVec<FnPrePhase2>) -> ApiVec<FnPrePhas
         .collect();
 
     // We also need to remove any constructors belonging to these
-    // abstract types.
+    // abstract types, and any make_shared functions based on them.
     apis.retain(|api| {
         !matches!(&api,
             Api::Function {
@@ -173,6 +173,9 @@ pub(crate) fn mark_types_abstract(apis: ApiVec<FnPrePhase2>) -> ApiVec<FnPrePhas
                     },
                     ..
             } if abstract_classes.contains(self_ty)
+        ) && !matches!(&api,
+            Api::Function { fun, .. }
+                if matches!(&fun.synthetic_cpp, Some((CppFunctionBody::MakeShared(self_ty), _)) if abstract_classes.contains(self_ty))
         )
     });
 
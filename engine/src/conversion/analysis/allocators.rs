@@ -77,6 +77,7 @@ fn create_alloc_and_free(ty_name: QualifiedName) -> impl Iterator<Item = Api<Pod
                 fun: Box::new(FuncToConvert {
                     ident,
                     doc_attrs: Vec::new(),
+                    lint_attrs: Vec::new(),
                     inputs: minisynize_punctuated(inputs),
                     output: output.into(),
                     vis: parse_quote! { pub },
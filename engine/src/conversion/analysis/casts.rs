@@ -98,6 +98,7 @@ fn create_cast(from: &QualifiedName, to: &QualifiedName, mutable: CastMutability
         fun: Box::new(crate::conversion::api::FuncToConvert {
             ident,
             doc_attrs: Vec::new(),
+            lint_attrs: Vec::new(),
             inputs: [fnarg].into_iter().collect(),
             output: parse_quote! {
                 -> * #return_mutability #to_typ
@@ -6,21 +6,28 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use autocxx_parser::IncludeCppConfig;
 use indexmap::map::IndexMap as HashMap;
+use indexmap::set::IndexSet as HashSet;
+use syn::Type;
 
 use crate::{
     conversion::{
-        api::{Api, ApiName, StructDetails, TypeKind},
+        api::{Api, ApiName, ConcreteContainer, StructDetails, TypeKind},
         apivec::ApiVec,
         convert_error::ConvertErrorWithContext,
         error_reporter::convert_apis,
     },
+    known_types::known_types,
     types::QualifiedName,
 };
 
-use super::fun::{
-    FnAnalysis, FnKind, FnPhase, FnPrePhase2, PodAndConstructorAnalysis, PodAndDepAnalysis,
-    TraitMethodKind,
+use super::{
+    fun::{
+        FnAnalysis, FnKind, FnPhase, FnPrePhase2, PodAndConstructorAnalysis, PodAndDepAnalysis,
+        TraitMethodKind,
+    },
+    iterable::{find_iterables, Iterable},
 };
 
 /// We've now analyzed all functions (including both implicit and explicit
@@ -28,20 +35,141 @@ use super::fun::{
 /// which will later be used as edges in the garbage collection, because
 /// typically any use of a type will require us to call its copy or move
 /// constructor. The same applies to its alloc/free functions.
-pub(crate) fn decorate_types_with_constructor_deps(apis: ApiVec<FnPrePhase2>) -> ApiVec<FnPhase> {
+/// While we're at it, note which types can be iterated over, which maps
+/// we can insert into, and which pairs, tuples and arrays are POD.
+pub(crate) fn decorate_types_with_constructor_deps(
+    apis: ApiVec<FnPrePhase2>,
+    config: &IncludeCppConfig,
+) -> ApiVec<FnPhase> {
     let mut constructors_and_allocators_by_type = find_important_constructors(&apis);
+    let mut iterables = find_iterables(&apis, config);
+    let copy_constructible = find_copy_constructible(&apis);
+    let pod_types = find_pod_types(&apis);
     let mut results = ApiVec::new();
     convert_apis(
         apis,
         &mut results,
         Api::fun_unchanged,
         |name, details, pod| {
-            decorate_struct(name, details, pod, &mut constructors_and_allocators_by_type)
+            decorate_struct(
+                name,
+                details,
+                pod,
+                &mut constructors_and_allocators_by_type,
+                &mut iterables,
+            )
         },
         Api::enum_unchanged,
         Api::typedef_unchanged,
     );
-    results
+    let mut decorated = ApiVec::new();
+    decorated.extend(
+        results
+            .into_iter()
+            .map(|api| decorate_container(api, &copy_constructible, &pod_types)),
+    );
+    decorated
+}
+
+/// Maps copy their keys and values on `insert`, so we can only offer
+/// that if both can be copy constructed. Pairs and tuples can only be
+/// copied into Rust by value, and arrays viewed as slices, if all their
+/// elements are POD.
+fn decorate_container(
+    mut api: Api<FnPhase>,
+    copy_constructible: &HashSet<QualifiedName>,
+    pod_types: &HashSet<QualifiedName>,
+) -> Api<FnPhase> {
+    if let Api::ConcreteType {
+        container: Some(container),
+        ..
+    } = &mut api
+    {
+        match container.as_mut() {
+            ConcreteContainer::Map {
+                key,
+                value,
+                insertable,
+            } => {
+                *insertable = [key, value]
+                    .into_iter()
+                    .all(|ty| is_copy_constructible(ty, copy_constructible))
+            }
+            ConcreteContainer::Pair { first, second, pod } => {
+                *pod = [first, second].into_iter().all(|ty| is_pod(ty, pod_types))
+            }
+            ConcreteContainer::Tuple { elements, pod } => {
+                *pod = elements.iter().all(|ty| is_pod(ty, pod_types))
+            }
+            ConcreteContainer::Array { element, pod, .. } => *pod = is_pod(element, pod_types),
+            _ => {}
+        }
+    }
+    api
+}
+
+fn is_copy_constructible(ty: &Type, copy_constructible: &HashSet<QualifiedName>) -> bool {
+    match ty {
+        Type::Path(typ) => {
+            let tn = QualifiedName::from_type_path(typ);
+            if known_types().is_known_type(&tn) {
+                !known_types().lacks_copy_constructor(&tn)
+            } else {
+                copy_constructible.contains(&tn)
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_pod(ty: &Type, pod_types: &HashSet<QualifiedName>) -> bool {
+    match ty {
+        Type::Path(typ) => {
+            let tn = QualifiedName::from_type_path(typ);
+            known_types().is_pod_safe(&tn) || pod_types.contains(&tn)
+        }
+        _ => false,
+    }
+}
+
+/// Enums, and structs which we've decided are POD.
+fn find_pod_types(apis: &ApiVec<FnPrePhase2>) -> HashSet<QualifiedName> {
+    apis.iter()
+        .filter_map(|api| match api {
+            Api::Enum { name, .. } => Some(name.name.clone()),
+            Api::Struct {
+                name,
+                analysis: PodAndConstructorAnalysis { pod, .. },
+                ..
+            } if matches!(pod.kind, TypeKind::Pod) => Some(name.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Enums, and structs for which we've found a usable copy constructor.
+/// We're conservative about anything else, such as typedefs.
+fn find_copy_constructible(apis: &ApiVec<FnPrePhase2>) -> HashSet<QualifiedName> {
+    apis.iter()
+        .filter_map(|api| match api {
+            Api::Enum { name, .. } => Some(name.name.clone()),
+            Api::Function {
+                analysis:
+                    FnAnalysis {
+                        kind:
+                            FnKind::TraitMethod {
+                                kind: TraitMethodKind::CopyConstructor,
+                                impl_for,
+                                ..
+                            },
+                        ignore_reason: Ok(_),
+                        ..
+                    },
+                ..
+            } => Some(impl_for.clone()),
+            _ => None,
+        })
+        .collect()
 }
 
 fn decorate_struct(
@@ -49,6 +177,7 @@ fn decorate_struct(
     details: Box<StructDetails>,
     fn_struct: PodAndConstructorAnalysis,
     constructors_and_allocators_by_type: &mut HashMap<QualifiedName, Vec<QualifiedName>>,
+    iterables: &mut HashMap<QualifiedName, Iterable>,
 ) -> Result<Box<dyn Iterator<Item = Api<FnPhase>>>, ConvertErrorWithContext> {
     let pod = fn_struct.pod;
     let is_abstract = matches!(pod.kind, TypeKind::Abstract);
@@ -59,6 +188,11 @@ fn decorate_struct(
             .remove(&name.name)
             .unwrap_or_default()
     };
+    let iterable = if pod.is_generic {
+        None
+    } else {
+        iterables.remove(&name.name)
+    };
     Ok(Box::new(std::iter::once(Api::Struct {
         name,
         details,
@@ -66,6 +200,7 @@ fn decorate_struct(
             pod,
             constructor_and_allocator_deps,
             constructors: fn_struct.constructors,
+            iterable,
         },
     })))
 }
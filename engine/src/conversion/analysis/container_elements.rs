@@ -0,0 +1,95 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use indexmap::set::IndexSet as HashSet;
+use syn::{FnArg, GenericArgument, PathArguments, ReturnType, Type};
+
+use crate::{
+    conversion::{
+        api::{AnalysisPhase, Api, TypedefKind},
+        apivec::ApiVec,
+    },
+    types::QualifiedName,
+};
+
+/// Finds the types which appear as a template argument of any of the given
+/// C++ containers (e.g. `std::vector`) within the parameters or return type
+/// of a function, the fields of a struct, or a typedef, as reported by
+/// bindgen. We only generate helpers to put a type into such a container
+/// from Rust if the bindings could make use of the result.
+pub(crate) fn find_container_element_types<A: AnalysisPhase>(
+    apis: &ApiVec<A>,
+    containers: &[&str],
+) -> HashSet<QualifiedName> {
+    let mut results = HashSet::new();
+    for api in apis.iter() {
+        match api {
+            Api::Function { fun, .. } => {
+                for arg in fun.inputs.iter() {
+                    if let FnArg::Typed(pt) = &**arg {
+                        find_in_type(&pt.ty, containers, &mut results);
+                    }
+                }
+                if let ReturnType::Type(_, ty) = &*fun.output {
+                    find_in_type(ty, containers, &mut results);
+                }
+            }
+            Api::Struct { details, .. } => {
+                for field in details.item.fields.iter() {
+                    find_in_type(&field.ty, containers, &mut results);
+                }
+            }
+            Api::Typedef {
+                item: TypedefKind::Type(ity),
+                ..
+            } => find_in_type(&ity.ty, containers, &mut results),
+            Api::Typedef {
+                item: TypedefKind::Use(_, ty),
+                ..
+            } => find_in_type(ty, containers, &mut results),
+            _ => {}
+        }
+    }
+    results
+}
+
+/// Finds the types which appear within a `std::vector`, for which we
+/// generate helpers to build up a `CxxVector` from Rust.
+pub(crate) fn find_vector_element_types<A: AnalysisPhase>(
+    apis: &ApiVec<A>,
+) -> HashSet<QualifiedName> {
+    find_container_element_types(apis, &["std::vector"])
+}
+
+fn find_in_type(ty: &Type, containers: &[&str], results: &mut HashSet<QualifiedName>) {
+    match ty {
+        Type::Path(typ) => {
+            let is_container =
+                containers.contains(&QualifiedName::from_type_path(typ).to_cpp_name().as_str());
+            for seg in &typ.path.segments {
+                if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                    for arg in &args.args {
+                        if let GenericArgument::Type(arg) = arg {
+                            if let (true, Type::Path(element)) = (is_container, arg) {
+                                results.insert(QualifiedName::from_type_path(element));
+                            }
+                            find_in_type(arg, containers, results);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Ptr(ptr) => find_in_type(&ptr.elem, containers, results),
+        Type::Reference(reference) => find_in_type(&reference.elem, containers, results),
+        Type::Array(array) => find_in_type(&array.elem, containers, results),
+        Type::Slice(slice) => find_in_type(&slice.elem, containers, results),
+        Type::Paren(paren) => find_in_type(&paren.elem, containers, results),
+        Type::Group(group) => find_in_type(&group.elem, containers, results),
+        _ => {}
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use indexmap::map::IndexMap as HashMap;
+use itertools::Itertools;
+use syn::{parse_quote, Attribute};
+
+use crate::conversion::{
+    api::{Api, NullPhase},
+    apivec::ApiVec,
+};
+
+use super::probe::{BindgenProbe, ProbeNames};
+
+const START_MARKER: &str = "autocxx_deprecation_probe";
+
+/// bindgen doesn't tell us about `[[deprecated]]`, but clang will complain
+/// if we use anything deprecated. So we use every function and type we can
+/// name, having told clang that doing so with anything deprecated is an
+/// error. Whatever it complains about gets `#[deprecated]`, with the same
+/// message. Overloaded functions and constructors can't be named like that,
+/// so won't be found.
+pub(crate) fn mark_deprecated_apis(
+    apis: ApiVec<NullPhase>,
+    probe: &BindgenProbe,
+) -> ApiVec<NullPhase> {
+    let mut apis: Vec<_> = apis.into_iter().collect();
+    let names = ProbeNames::new(&apis);
+    let uses: Vec<_> = apis
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, api)| {
+            let use_ = match api {
+                Api::Struct { name, details, .. }
+                    if details.item.generics.params.is_empty()
+                        && !name.name.get_final_item().contains("__bindgen") =>
+                {
+                    Use::new(idx, name.qualified_cpp_name(), false)
+                }
+                Api::Enum { name, .. } => Use::new(idx, name.qualified_cpp_name(), false),
+                Api::Function { name, fun, .. } => Use::new(idx, names.function(name, fun)?, true),
+                _ => return None,
+            };
+            Some(use_)
+        })
+        .collect();
+    if !uses.is_empty() {
+        for (idx, attr) in find_deprecations(&uses, probe) {
+            match &mut apis[idx] {
+                Api::Struct { details, .. } => details.item.attrs.push(attr),
+                Api::Enum { item, .. } => item.attrs.push(attr),
+                Api::Function { fun, .. } => fun.lint_attrs.push(attr.into()),
+                _ => {}
+            }
+        }
+    }
+    let mut results = ApiVec::new();
+    results.extend(apis.into_iter());
+    results
+}
+
+/// A function or type we'll try to use.
+struct Use {
+    /// The index of its API.
+    idx: usize,
+    /// Its qualified C++ name.
+    name: String,
+    /// The C++ type we'll name in order to use it.
+    cpp_type: String,
+}
+
+impl Use {
+    fn new(idx: usize, name: String, is_function: bool) -> Self {
+        let cpp_type = if is_function {
+            format!("decltype(&{name})")
+        } else {
+            name.clone()
+        };
+        Self {
+            idx,
+            name,
+            cpp_type,
+        }
+    }
+}
+
+/// Returns the `#[deprecated]` attribute for each of the given uses which
+/// clang complains about, along with the index of its API.
+fn find_deprecations(uses: &[Use], probe: &BindgenProbe) -> Vec<(usize, Attribute)> {
+    // Each use goes on its own line after the start marker, which is itself
+    // an error, so that we can tell which line is which.
+    let cpp = [
+        "#pragma clang diagnostic error \"-Wdeprecated-declarations\"".to_string(),
+        format!("#error {START_MARKER}"),
+    ]
+    .into_iter()
+    .chain(uses.iter().enumerate().map(|(line, use_)| {
+        format!(
+            "using autocxx_deprecation_probe_{line} = {};",
+            use_.cpp_type
+        )
+    }))
+    .join("\n");
+    // We don't need bindings for anything.
+    let diagnostics = match probe(&cpp, "") {
+        Ok(_) => return Vec::new(),
+        Err(diagnostics) => diagnostics,
+    };
+    let errors: Vec<_> = diagnostics.lines().filter_map(parse_error).collect();
+    let start_line = match errors.iter().find(|error| error.message == START_MARKER) {
+        Some(start) => (start.file, start.line),
+        None => return Vec::new(),
+    };
+    let uses_by_line: HashMap<_, _> = uses
+        .iter()
+        .enumerate()
+        .map(|(line, use_)| (start_line.1 + 1 + line, use_))
+        .collect();
+    errors
+        .iter()
+        .filter(|error| error.file == start_line.0)
+        .filter_map(|error| {
+            let use_ = uses_by_line.get(&error.line)?;
+            let (entity, note) = parse_deprecation(error.message)?;
+            // Only if it's this API which is deprecated, not, say, the class
+            // containing it.
+            if use_.name.rsplit("::").next() != Some(entity) {
+                return None;
+            }
+            let attr = match note {
+                Some(note) => parse_quote! { #[deprecated(note = #note)] },
+                None => parse_quote! { #[deprecated] },
+            };
+            Some((use_.idx, attr))
+        })
+        .collect()
+}
+
+struct ClangError<'a> {
+    file: &'a str,
+    line: usize,
+    message: &'a str,
+}
+
+/// Parses an error from clang, formatted as
+/// `file:line:column: error: message [-Wflag]`.
+fn parse_error(diagnostic: &str) -> Option<ClangError<'_>> {
+    let (location, message) = diagnostic.split_once(": error: ")?;
+    let mut location = location.rsplitn(3, ':');
+    let _column = location.next()?;
+    let line = location.next()?.parse().ok()?;
+    let file = location.next()?;
+    let message = message
+        .rsplit_once(" [-W")
+        .map_or(message, |(message, _)| message);
+    Some(ClangError {
+        file,
+        line,
+        message,
+    })
+}
+
+/// Parses clang's complaint that `'entity' is deprecated: note` into the
+/// entity and note.
+fn parse_deprecation(message: &str) -> Option<(&str, Option<&str>)> {
+    let (entity, note) = message.strip_prefix('\'')?.split_once("' is deprecated")?;
+    let note = note.strip_prefix(": ").filter(|note| !note.is_empty());
+    Some((entity, note))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_deprecations, Use};
+    use quote::ToTokens;
+
+    #[test]
+    fn test_find_deprecations() {
+        let uses = [
+            Use::new(3, "ns::f".into(), true),
+            Use::new(5, "Outer::g".into(), true),
+            Use::new(7, "A".into(), false),
+        ];
+        let probe = |cpp: &str, _: &str| {
+            assert!(cpp.contains("using autocxx_deprecation_probe_0 = decltype(&ns::f);"));
+            assert!(cpp.contains("using autocxx_deprecation_probe_2 = A;"));
+            Err([
+                "/tmp/example.hpp:20:2: error: autocxx_deprecation_probe",
+                "/tmp/example.hpp:21:40: error: 'f' is deprecated: use \"h\" [-Werror,-Wdeprecated-declarations]",
+                "/tmp/example.hpp:22:40: error: 'Outer' is deprecated [-Werror,-Wdeprecated-declarations]",
+                "/tmp/example.hpp:23:40: error: 'A' is deprecated [-Werror,-Wdeprecated-declarations]",
+            ]
+            .join("\n"))
+        };
+        let deprecations: Vec<_> = find_deprecations(&uses, &probe)
+            .into_iter()
+            .map(|(idx, attr)| (idx, attr.into_token_stream().to_string()))
+            .collect();
+        assert_eq!(
+            deprecations,
+            [
+                (3, r#"# [deprecated (note = "use \"h\"")]"#.to_string()),
+                (7, "# [deprecated]".to_string()),
+            ]
+        );
+    }
+}
@@ -72,6 +72,7 @@ impl HasDependencies for Api<FnPhase> {
                                 ..
                             },
                         constructor_and_allocator_deps,
+                        iterable,
                         ..
                     },
                 ..
@@ -79,16 +80,22 @@ impl HasDependencies for Api<FnPhase> {
                 field_deps
                     .iter()
                     .chain(bases.iter())
-                    .chain(constructor_and_allocator_deps.iter()),
+                    .chain(constructor_and_allocator_deps.iter())
+                    .chain(iterable.iter().flat_map(|iterable| iterable.deps.iter())),
             ),
             Api::Struct {
                 analysis:
                     PodAndDepAnalysis {
                         constructor_and_allocator_deps,
+                        iterable,
                         ..
                     },
                 ..
-            } => Box::new(constructor_and_allocator_deps.iter()),
+            } => Box::new(
+                constructor_and_allocator_deps
+                    .iter()
+                    .chain(iterable.iter().flat_map(|iterable| iterable.deps.iter())),
+            ),
             Api::Function { analysis, .. } => Box::new(analysis.deps.iter()),
             Api::Subclass {
                 name: _,
@@ -27,6 +27,8 @@ pub(crate) enum CppConversionType {
     FromReturnValueToPlacementPtr,
     FromPointerToReference, // unwrapped_type is always Type::Ptr
     FromReferenceToPointer, // unwrapped_type is always Type::Ptr
+    FromStrToStringView,    // unwrapped_type is always &str
+    FromStringViewToStr,    // unwrapped_type is always &str
 }
 
 impl CppConversionType {
@@ -42,6 +44,8 @@ impl CppConversionType {
             CppConversionType::FromValueToUniquePtr => CppConversionType::FromUniquePtrToValue,
             CppConversionType::FromPointerToReference => CppConversionType::FromReferenceToPointer,
             CppConversionType::FromReferenceToPointer => CppConversionType::FromPointerToReference,
+            CppConversionType::FromStrToStringView => CppConversionType::FromStringViewToStr,
+            CppConversionType::FromStringViewToStr => CppConversionType::FromStrToStringView,
             _ => panic!("Did not expect to have to invert this conversion"),
         }
     }
@@ -240,6 +244,7 @@ pub(crate) enum CppFunctionBody {
     Destructor(Namespace, Ident),
     AllocUninitialized(QualifiedName),
     FreeUninitialized(QualifiedName),
+    MakeShared(QualifiedName),
 }
 
 #[derive(Clone, Debug)]
@@ -27,6 +27,7 @@ use crate::{
         convert_error::ErrorContext,
         convert_error::{ConvertErrorWithContext, ErrorContextType},
         error_reporter::{convert_apis, report_any_error},
+        naming_policy::to_snake_case,
     },
     known_types::known_types,
     minisyn::minisynize_punctuated,
@@ -35,7 +36,7 @@ use crate::{
 use indexmap::map::IndexMap as HashMap;
 use indexmap::set::IndexSet as HashSet;
 
-use autocxx_parser::{ExternCppType, IncludeCppConfig, UnsafePolicy};
+use autocxx_parser::{ExternCppType, IncludeCppConfig, NamingPolicy, OverloadNaming, UnsafePolicy};
 use function_wrapper::{CppFunction, CppFunctionBody, TypeConversionPolicy};
 use itertools::Itertools;
 use proc_macro2::Span;
@@ -65,8 +66,10 @@ use self::{
 };
 
 use super::{
+    container_elements::find_container_element_types,
     depth_first::HasFieldsAndBases,
     doc_label::make_doc_attrs,
+    iterable::Iterable,
     pod::{PodAnalysis, PodPhase},
     tdef::TypedefAnalysis,
     type_converter::{Annotated, PointerTreatment},
@@ -241,6 +244,8 @@ pub(crate) struct PodAndDepAnalysis {
     pub(crate) pod: PodAnalysis,
     pub(crate) constructor_and_allocator_deps: Vec<QualifiedName>,
     pub(crate) constructors: PublicConstructors,
+    /// If this type has `begin()` and `end()` methods.
+    pub(crate) iterable: Option<Iterable>,
 }
 
 /// Analysis phase after we've finished analyzing functions and determined
@@ -288,11 +293,19 @@ pub(crate) struct FnAnalyzer<'a> {
     moveit_safe_types: HashSet<QualifiedName>,
     config: &'a IncludeCppConfig,
     overload_trackers_by_mod: HashMap<Namespace, OverloadTracker>,
+    /// For each (namespace, type, name) chosen by our naming policy,
+    /// the name from which it was derived.
+    names_from_naming_policy: HashMap<(Namespace, Option<String>, String), String>,
+    /// Functions and methods which have more than one C++ overload.
+    overloaded_functions: HashSet<OverloadKey>,
     subclasses_by_superclass: HashMap<QualifiedName, Vec<SubclassName>>,
     nested_type_name_map: HashMap<QualifiedName, String>,
     generic_types: HashSet<QualifiedName>,
     types_in_anonymous_namespace: HashSet<QualifiedName>,
     existing_superclass_trait_api_names: HashSet<QualifiedName>,
+    /// Types which appear within a `std::shared_ptr` or `std::weak_ptr`,
+    /// for which we offer `make_shared` functions.
+    shared_ptr_targets: HashSet<QualifiedName>,
     force_wrapper_generation: bool,
 }
 
@@ -310,6 +323,8 @@ impl<'a> FnAnalyzer<'a> {
             bridge_name_tracker: BridgeNameTracker::new(),
             config,
             overload_trackers_by_mod: HashMap::new(),
+            names_from_naming_policy: Self::build_snake_case_name_map(&apis, config),
+            overloaded_functions: Self::build_overloaded_function_set(&apis),
             pod_safe_types: Self::build_pod_safe_type_set(&apis),
             moveit_safe_types: Self::build_correctly_sized_type_set(&apis),
             subclasses_by_superclass: subclass::subclasses_by_superclass(&apis),
@@ -317,6 +332,10 @@ impl<'a> FnAnalyzer<'a> {
             generic_types: Self::build_generic_type_set(&apis),
             existing_superclass_trait_api_names: HashSet::new(),
             types_in_anonymous_namespace: Self::build_types_in_anonymous_namespace(&apis),
+            shared_ptr_targets: find_container_element_types(
+                &apis,
+                &["std::shared_ptr", "std::weak_ptr"],
+            ),
             force_wrapper_generation,
         };
         let mut results = ApiVec::new();
@@ -400,6 +419,61 @@ impl<'a> FnAnalyzer<'a> {
             .collect()
     }
 
+    fn build_overloaded_function_set(apis: &ApiVec<PodPhase>) -> HashSet<OverloadKey> {
+        let mut counts: HashMap<OverloadKey, usize> = HashMap::new();
+        for api in apis.iter() {
+            if let Api::Function { name, fun, .. } = api {
+                // These become trait implementations rather than
+                // overloaded methods.
+                if !matches!(
+                    fun.special_member,
+                    Some(
+                        SpecialMemberKind::CopyConstructor
+                            | SpecialMemberKind::MoveConstructor
+                            | SpecialMemberKind::Destructor
+                    )
+                ) {
+                    *counts.entry(overload_key(name, fun)).or_default() += 1;
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Under `naming!(rust)`, functions and methods which are already
+    /// snake_case keep their names, so reserve those names before we rename
+    /// anything else. Otherwise whichever came first would win.
+    fn build_snake_case_name_map(
+        apis: &ApiVec<PodPhase>,
+        config: &IncludeCppConfig,
+    ) -> HashMap<(Namespace, Option<String>, String), String> {
+        if config.naming_policy != NamingPolicy::Rust {
+            return HashMap::new();
+        }
+        apis.iter()
+            .filter_map(|api| match api {
+                Api::Function { name, fun, .. } => {
+                    let (ns, self_ty, cpp_name) = overload_key(name, fun);
+                    (to_snake_case(&cpp_name) == cpp_name).then(|| {
+                        (
+                            (
+                                ns,
+                                self_ty.map(|self_ty| self_ty.get_final_item().to_string()),
+                                cpp_name.clone(),
+                            ),
+                            cpp_name,
+                        )
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     fn build_types_in_anonymous_namespace(apis: &ApiVec<PodPhase>) -> HashSet<QualifiedName> {
         apis.iter()
             .filter_map(|api| match api {
@@ -471,6 +545,7 @@ impl<'a> FnAnalyzer<'a> {
         &self,
         param_details: &[ArgumentAnalysis],
         kind: &FnKind,
+        unsafe_policy: &UnsafePolicy,
     ) -> UnsafetyNeeded {
         let unsafest_non_placement_param = UnsafetyNeeded::from_param_details(param_details, true);
         let unsafest_param = UnsafetyNeeded::from_param_details(param_details, false);
@@ -489,7 +564,7 @@ impl<'a> FnAnalyzer<'a> {
                 UnsafetyNeeded::Always => UnsafetyNeeded::JustBridge,
                 _ => unsafest_param,
             },
-            _ if matches!(self.unsafe_policy, UnsafePolicy::AllFunctionsUnsafe) => {
+            _ if matches!(unsafe_policy, UnsafePolicy::AllFunctionsUnsafe) => {
                 UnsafetyNeeded::Always
             }
             _ => match unsafest_non_placement_param {
@@ -677,6 +752,18 @@ impl<'a> FnAnalyzer<'a> {
             }
         }
 
+        // If this type is used within a std::shared_ptr, offer a way to
+        // construct it directly within one, using each of its constructors.
+        if let FnKind::Method {
+            impl_for,
+            method_kind: MethodKind::Constructor { .. },
+        } = &analysis.kind
+        {
+            if analysis.ignore_reason.is_ok() && self.shared_ptr_targets.contains(impl_for) {
+                self.add_make_shared(impl_for, &fun, &analysis.rust_name, &mut results);
+            }
+        }
+
         results.push(Api::Function {
             fun,
             analysis,
@@ -686,6 +773,80 @@ impl<'a> FnAnalyzer<'a> {
         Ok(Box::new(results.into_iter()))
     }
 
+    /// Creates a static method which calls `std::make_shared` with the same
+    /// arguments as the given constructor.
+    fn add_make_shared(
+        &mut self,
+        self_ty: &QualifiedName,
+        constructor: &FuncToConvert,
+        constructor_rust_name: &str,
+        results: &mut ApiVec<FnPrePhase1>,
+    ) {
+        let ns = self_ty.get_namespace();
+        let rust_name = match constructor_rust_name.strip_prefix("new") {
+            Some(suffix) => format!("make_shared{suffix}"),
+            None => format!("{constructor_rust_name}_make_shared"),
+        };
+        let rust_name = self.get_overload_name(ns, self_ty.get_final_item(), rust_name);
+        let ident = make_ident(self.config.uniquify_name_per_mod(&format!(
+            "{}_autocxx_{}",
+            self_ty.get_final_item(),
+            rust_name
+        )));
+        let typ = self_ty.to_type_path();
+        let output: ReturnType = parse_quote! {
+            -> root::std::shared_ptr<#typ>
+        };
+        let inputs = constructor
+            .inputs
+            .iter()
+            .skip(1) // this
+            .cloned()
+            .collect();
+        let make_shared = Box::new(FuncToConvert {
+            self_ty: Some(self_ty.clone()),
+            ident: ident.clone(),
+            doc_attrs: make_doc_attrs(format!(
+                "Makes a new {} within a std::shared_ptr, taking the same arguments as [`Self::{}`].",
+                self_ty.get_final_item(),
+                constructor_rust_name
+            ))
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+            lint_attrs: Vec::new(),
+            inputs,
+            output: output.into(),
+            vis: parse_quote! { pub },
+            virtualness: Virtualness::None,
+            cpp_vis: CppVisibility::Public,
+            special_member: None,
+            unused_template_param: false,
+            references: constructor.references.clone(),
+            original_name: None,
+            synthesized_this_type: None,
+            is_deleted: DeletedOrDefaulted::Neither,
+            add_to_trait: None,
+            synthetic_cpp: Some((
+                CppFunctionBody::MakeShared(self_ty.clone()),
+                CppFunctionKind::Function,
+            )),
+            provenance: Provenance::SynthesizedOther,
+            variadic: false,
+        });
+        let (analysis, name) = self.analyze_foreign_fn(
+            ApiName::new(ns, ident),
+            &make_shared,
+            TypeConversionSophistication::Regular,
+            Some(rust_name),
+        );
+        results.push(Api::Function {
+            fun: make_shared,
+            analysis,
+            name,
+        });
+    }
+
     /// Adds an API, usually a synthesized API. Returns the final calculated API name, which can be used
     /// for others to depend on this.
     fn analyze_and_add<P: AnalysisPhase<FunAnalysis = FnAnalysis>>(
@@ -786,6 +947,8 @@ impl<'a> FnAnalyzer<'a> {
         //   method,   IRN=A_foo,  CN=foo                       output: foo    case 4
         //   method,   IRN=A_move, CN=move   (keyword problem)  output: move_  case 5
         //   method,   IRN=A_foo1, CN=foo    (overload)         output: foo    case 6
+        let mut naming_policy_collision = None;
+        let overload_suffix = self.overload_suffix(&name, fun);
         let ideal_rust_name = match &cpp_name {
             None => initial_rust_name, // case 1
             Some(cpp_name) => {
@@ -814,6 +977,71 @@ impl<'a> FnAnalyzer<'a> {
             }
         };
 
+        // The fully-qualified C++ name, by which directives may refer
+        // to this function.
+        let unqualified_cpp_name = cpp_name.as_ref().unwrap_or(&ideal_rust_name);
+        let qualified_cpp_name = match &self_ty {
+            Some(self_ty) => format!("{}::{unqualified_cpp_name}", self_ty.to_cpp_name()),
+            None => ns
+                .iter()
+                .chain(std::iter::once(unqualified_cpp_name))
+                .join("::"),
+        };
+
+        // The user may have asked for a specific name using rename!,
+        // in which case that takes precedence over everything else.
+        // Renaming an overloaded function by name alone would give all its
+        // overloads the same name, so we insist on a signature in that case.
+        // The chosen name still goes through the overload tracker, so that
+        // we spot any clash with another function, and so that any function
+        // we come across later which wants the same name gets a suffix.
+        let mut rename_problem = None;
+        let predetermined_rust_name = match predetermined_rust_name {
+            Some(name) => Some(name),
+            None => {
+                let signature_rename = cpp_param_types(fun).and_then(|param_types| {
+                    self.config
+                        .get_signature_rename(&qualified_cpp_name, &param_types)
+                        .cloned()
+                });
+                let rename = signature_rename.or_else(|| {
+                    let rename = self.config.get_rename(&qualified_cpp_name, None)?;
+                    if self
+                        .overloaded_functions
+                        .contains(&overload_key(&name, fun))
+                    {
+                        rename_problem = Some(ConvertErrorFromCpp::AmbiguousRename(
+                            qualified_cpp_name.clone(),
+                        ));
+                    }
+                    Some(rename.clone())
+                });
+                rename.map(|rename| {
+                    let rename = rename.to_string();
+                    let tracked_name = match &self_ty {
+                        Some(self_ty) => {
+                            self.get_overload_name(ns, self_ty.get_final_item(), rename.clone())
+                        }
+                        None => self.get_function_overload_name(ns, rename.clone()),
+                    };
+                    if tracked_name != rename && rename_problem.is_none() {
+                        rename_problem = Some(ConvertErrorFromCpp::RenameClash(rename.clone()));
+                    }
+                    rename
+                })
+            }
+        };
+
+        // Similarly, the user may have asked for this function (or all
+        // methods of its type) to be safe or unsafe using safe! or unsafe_fn!.
+        let unsafe_policy = self
+            .config
+            .get_safety_override(
+                &qualified_cpp_name,
+                self_ty.as_ref().map(|ty| ty.to_cpp_name()).as_deref(),
+            )
+            .unwrap_or_else(|| self.unsafe_policy.clone());
+
         // Part two, work out if this is a function, or method, or whatever.
         // First determine if this is actually a trait implementation.
         let trait_details = self.trait_creation_details_for_synthetic_function(
@@ -970,8 +1198,25 @@ impl<'a> FnAnalyzer<'a> {
                     }
                 };
                 // Disambiguate overloads.
-                let rust_name = predetermined_rust_name
-                    .unwrap_or_else(|| self.get_overload_name(ns, type_ident, rust_name));
+                let rust_name = match predetermined_rust_name {
+                    Some(rust_name) => rust_name,
+                    None => {
+                        // Constructors are already called new, but we still need
+                        // to know if any renamed method clashes with them.
+                        let rename = !matches!(method_kind, MethodKind::Constructor { .. });
+                        match self.apply_naming_policy(ns, Some(type_ident), &rust_name, rename) {
+                            Ok(rust_name) => self.get_overload_name(
+                                ns,
+                                type_ident,
+                                add_overload_suffix(rust_name, &overload_suffix),
+                            ),
+                            Err(err) => {
+                                naming_policy_collision = Some(err);
+                                rust_name
+                            }
+                        }
+                    }
+                };
                 let error_context = self.error_context_for_method(&self_ty, &rust_name);
                 (
                     FnKind::Method {
@@ -985,7 +1230,29 @@ impl<'a> FnAnalyzer<'a> {
         } else {
             // Not a method.
             // What shall we call this function? It may be overloaded.
-            let rust_name = self.get_function_overload_name(ns, ideal_rust_name);
+            let rust_name = match predetermined_rust_name {
+                Some(rust_name) => {
+                    if rust_name != ideal_rust_name && cpp_name.is_none() {
+                        cpp_name = Some(ideal_rust_name);
+                    }
+                    rust_name
+                }
+                None => match self.apply_naming_policy(ns, None, &ideal_rust_name, true) {
+                    Ok(rust_name) => {
+                        if rust_name != ideal_rust_name && cpp_name.is_none() {
+                            cpp_name = Some(ideal_rust_name);
+                        }
+                        self.get_function_overload_name(
+                            ns,
+                            add_overload_suffix(rust_name, &overload_suffix),
+                        )
+                    }
+                    Err(err) => {
+                        naming_policy_collision = Some(err);
+                        ideal_rust_name
+                    }
+                },
+            };
             (
                 FnKind::Function,
                 ErrorContext::new_for_item(make_ident(&rust_name)),
@@ -1000,6 +1267,9 @@ impl<'a> FnAnalyzer<'a> {
         let mut ignore_reason = Ok(());
         let mut set_ignore_reason =
             |err| ignore_reason = Err(ConvertErrorWithContext(err, Some(error_context.clone())));
+        if let Some(err) = naming_policy_collision.or(rename_problem) {
+            set_ignore_reason(err);
+        }
 
         // Now we have figured out the type of function (from its parameters)
         // we might have determined that we have a constructor. If so,
@@ -1214,7 +1484,7 @@ impl<'a> FnAnalyzer<'a> {
             params.push(extra_param);
         }
 
-        let requires_unsafe = self.should_be_unsafe(&param_details, &kind);
+        let requires_unsafe = self.should_be_unsafe(&param_details, &kind, &unsafe_policy);
 
         // The following sections reject some types of function because of the arrangement
         // of Rust references. We could lift these restrictions when/if we switch to using
@@ -1509,6 +1779,63 @@ impl<'a> FnAnalyzer<'a> {
         })
     }
 
+    /// Applies the user's [`NamingPolicy`] to the name of a function or method
+    /// (if `rename`), and checks whether the result clashes with the name we
+    /// chose for some other function or method.
+    fn apply_naming_policy(
+        &mut self,
+        ns: &Namespace,
+        type_ident: Option<&str>,
+        rust_name: &str,
+        rename: bool,
+    ) -> Result<String, ConvertErrorFromCpp> {
+        if self.config.naming_policy != NamingPolicy::Rust {
+            return Ok(rust_name.to_string());
+        }
+        let new_name = if rename {
+            to_snake_case(rust_name)
+        } else {
+            rust_name.to_string()
+        };
+        let key = (ns.clone(), type_ident.map(str::to_string), new_name.clone());
+        match self.names_from_naming_policy.get(&key) {
+            Some(other) if other != rust_name => Err(ConvertErrorFromCpp::NamingPolicyCollision(
+                new_name,
+                other.clone(),
+            )),
+            _ => {
+                self.names_from_naming_policy
+                    .insert(key, rust_name.to_string());
+                Ok(new_name)
+            }
+        }
+    }
+
+    /// If the user asked for overloads to be named according to their
+    /// signatures, and this function is overloaded, works out the suffix
+    /// which distinguishes it from its other overloads. Otherwise, we'll
+    /// fall back to numbering overloads in the order we find them.
+    fn overload_suffix(&self, name: &ApiName, fun: &FuncToConvert) -> Option<String> {
+        if self.config.overload_naming == OverloadNaming::Sequential
+            || !self.overloaded_functions.contains(&overload_key(name, fun))
+        {
+            return None;
+        }
+        let params = params_excluding_this(fun)?;
+        if params.is_empty() {
+            return None;
+        }
+        match self.config.overload_naming {
+            OverloadNaming::Sequential => None,
+            OverloadNaming::Count => Some(params.len().to_string()),
+            OverloadNaming::Types => params
+                .into_iter()
+                .map(|(_, ty)| overload_suffix_for_type(ty))
+                .collect::<Option<Vec<_>>>()
+                .map(|suffixes| suffixes.join("_")),
+        }
+    }
+
     fn get_overload_name(&mut self, ns: &Namespace, type_ident: &str, rust_name: String) -> String {
         let overload_tracker = self.overload_trackers_by_mod.entry(ns.clone()).or_default();
         overload_tracker.get_method_real_name(type_ident, rust_name)
@@ -1824,6 +2151,12 @@ impl<'a> FnAnalyzer<'a> {
                         CppConversionType::FromPointerToReference,
                         RustConversionType::FromReferenceWrapperToPointer,
                     )
+                } else if known_types().is_string_view(&tn) {
+                    TypeConversionPolicy::new(
+                        parse_quote! { &str },
+                        CppConversionType::FromStrToStringView,
+                        RustConversionType::None,
+                    )
                 } else if self.pod_safe_types.contains(&tn) {
                     if known_types().lacks_copy_constructor(&tn) {
                         TypeConversionPolicy::new(
@@ -1929,6 +2262,11 @@ impl<'a> FnAnalyzer<'a> {
                     self.convert_boxed_type(boxed_type.clone(), ns, references.return_treatment())?;
                 let boxed_type = annotated_type.ty;
                 let ty: &Type = boxed_type.as_ref();
+                // We can't know how long the referenced characters live.
+                if matches!(ty, Type::Path(p) if known_types().is_string_view(&QualifiedName::from_type_path(p)))
+                {
+                    return Err(ConvertErrorFromCpp::StringViewNotParam);
+                }
                 match ty {
                     Type::Path(p)
                         if !self
@@ -2162,6 +2500,7 @@ impl<'a> FnAnalyzer<'a> {
                             .into_iter()
                             .map(Into::into)
                             .collect(),
+                        lint_attrs: Vec::new(),
                         inputs: minisynize_punctuated(inputs),
                         output: ReturnType::Default.into(),
                         vis: parse_quote! { pub },
@@ -2259,9 +2598,49 @@ impl Api<FnPhase> {
                     },
                     ..
                 }
+                | Api::Struct {
+                    analysis: PodAndDepAnalysis {
+                        iterable: Some(_),
+                        ..
+                    },
+                    ..
+                }
+        ) || self.has_move_helpers()
+    }
+
+    /// Whether we generate helpers which move objects of this type into
+    /// C++-owned storage, such as allocating a `SharedPtr`. That's possible
+    /// for any type which can be move constructed and destroyed.
+    pub(crate) fn has_move_helpers(&self) -> bool {
+        matches!(
+            &self,
+            Api::Enum { .. }
+                | Api::Struct {
+                    analysis: PodAndDepAnalysis {
+                        pod: PodAnalysis {
+                            kind: TypeKind::Pod | TypeKind::NonPod,
+                            is_generic: false,
+                            ..
+                        },
+                        constructors: PublicConstructors {
+                            move_constructor: true,
+                            destructor: true,
+                        },
+                        ..
+                    },
+                    ..
+                }
         )
     }
 
+    /// Whether we generate helpers which allow a `CxxVector` of this type
+    /// to be built up from Rust. That's possible for any type with
+    /// [`Self::has_move_helpers`], but we only bother for types which the
+    /// bindings actually use within a `std::vector`.
+    pub(crate) fn has_vector_builder(&self, vector_element_types: &HashSet<QualifiedName>) -> bool {
+        vector_element_types.contains(self.name()) && self.has_move_helpers()
+    }
+
     pub(crate) fn cxxbridge_name(&self) -> Option<crate::minisyn::Ident> {
         match self {
             Api::Function { ref analysis, .. } => Some(analysis.cxxbridge_name.clone()),
@@ -2341,6 +2720,108 @@ impl HasFieldsAndBases for Api<FnPrePhase2> {
     }
 }
 
+/// Spells out the C++ types of the parameters of this function, so that
+/// `rename!` directives can pick out a specific overload. Returns `None`
+/// if any parameter is of a type we can't describe.
+fn cpp_param_types(fun: &FuncToConvert) -> Option<Vec<String>> {
+    params_excluding_this(fun)?
+        .into_iter()
+        .map(|(id, ty)| cpp_param_type(ty, fun.references.param_treatment(&id.clone().into())))
+        .collect()
+}
+
+fn cpp_param_type(ty: &Type, pointer_treatment: PointerTreatment) -> Option<String> {
+    match ty {
+        Type::Path(typ) if typ.path.segments.iter().all(|seg| seg.arguments.is_none()) => {
+            Some(QualifiedName::from_type_path(typ).to_cpp_name())
+        }
+        Type::Ptr(TypePtr {
+            const_token, elem, ..
+        }) => {
+            let pointee = cpp_param_type(elem, PointerTreatment::Pointer)?;
+            let constness = if const_token.is_some() { "const " } else { "" };
+            let suffix = match pointer_treatment {
+                PointerTreatment::Pointer => "*",
+                PointerTreatment::Reference => "&",
+                PointerTreatment::RValueReference => "&&",
+            };
+            Some(format!("{constness}{pointee}{suffix}"))
+        }
+        _ => None,
+    }
+}
+
+/// The names and types of the parameters of this function, other than
+/// any `this` parameter. Returns `None` if any parameter is unnamed.
+fn params_excluding_this(fun: &FuncToConvert) -> Option<Vec<(&Ident, &Type)>> {
+    fun.inputs
+        .iter()
+        .filter_map(|arg| match &**arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(PatType { pat, ty, .. }) => match pat.as_ref() {
+                Pat::Ident(pti) if pti.ident == "this" => None,
+                Pat::Ident(pti) => Some(Some((&pti.ident, ty.as_ref()))),
+                _ => Some(None),
+            },
+        })
+        .collect()
+}
+
+/// Functions and methods are overloads of one another if they share a
+/// namespace, a `self` type (if any) and a C++ name.
+type OverloadKey = (Namespace, Option<QualifiedName>, String);
+
+fn overload_key(name: &ApiName, fun: &FuncToConvert) -> OverloadKey {
+    (
+        name.name.get_namespace().clone(),
+        fun.self_ty.clone(),
+        name.cpp_name_if_present()
+            .cloned()
+            .unwrap_or_else(|| fun.ident.to_string()),
+    )
+}
+
+fn add_overload_suffix(rust_name: String, overload_suffix: &Option<String>) -> String {
+    match overload_suffix {
+        Some(suffix) => format!("{rust_name}_{suffix}"),
+        None => rust_name,
+    }
+}
+
+/// Describes a parameter type for the purposes of naming an overload,
+/// e.g. `i32` for `int`, `str` for `std::string` or `view` for `View`.
+/// Pointers and references are described in the same way as their
+/// pointee; if that leaves two overloads with the same name we'll
+/// number them.
+fn overload_suffix_for_type(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Ptr(TypePtr { elem, .. }) => overload_suffix_for_type(elem),
+        Type::Path(typ) if typ.path.segments.iter().all(|seg| seg.arguments.is_none()) => {
+            let name = QualifiedName::from_type_path(typ);
+            if name.to_cpp_name() == "std::string" {
+                return Some("str".to_string());
+            }
+            let suffix = match name.get_final_item() {
+                "c_schar" => "i8",
+                "c_uchar" => "u8",
+                "c_short" => "i16",
+                "c_ushort" => "u16",
+                "c_int" => "i32",
+                "c_uint" => "u32",
+                "c_longlong" => "i64",
+                "c_ulonglong" => "u64",
+                // These vary in size between platforms.
+                "c_char" => "char",
+                "c_long" => "long",
+                "c_ulong" => "ulong",
+                other => return Some(to_snake_case(other).trim_end_matches('_').to_string()),
+            };
+            Some(suffix.to_string())
+        }
+        _ => None,
+    }
+}
+
 /// Stringify a function argument for diagnostics
 fn describe_arg(arg: &FnArg) -> String {
     match arg {
@@ -59,6 +59,7 @@ pub(super) fn create_subclass_fn_wrapper(
         self_ty,
         ident: super_fn_name.get_final_ident(),
         doc_attrs: fun.doc_attrs.clone(),
+        lint_attrs: fun.lint_attrs.clone(),
         inputs: fun.inputs.clone(),
         output: fun.output.clone(),
         vis: fun.vis.clone(),
@@ -241,6 +242,7 @@ pub(super) fn create_subclass_constructor(
     let maybe_wrap = Box::new(FuncToConvert {
         ident: subclass_constructor_name.clone(),
         doc_attrs: fun.doc_attrs.clone(),
+        lint_attrs: fun.lint_attrs.clone(),
         inputs,
         output: fun.output.clone(),
         vis: fun.vis.clone(),
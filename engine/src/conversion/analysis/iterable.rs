@@ -0,0 +1,152 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use autocxx_parser::IncludeCppConfig;
+use indexmap::map::IndexMap as HashMap;
+use indexmap::set::IndexSet as HashSet;
+use syn::Type;
+
+use crate::{
+    conversion::{
+        api::{Api, CppVisibility, TypedefKind},
+        apivec::ApiVec,
+    },
+    types::{make_ident, QualifiedName},
+};
+
+use super::{
+    fun::{FnAnalysis, FnKind, FnPrePhase2, MethodKind, ReceiverMutability},
+    tdef::TypedefAnalysis,
+};
+
+/// A type with `begin()` and `end()` methods, over which we can
+/// generate a Rust iterator.
+#[derive(Debug)]
+pub(crate) struct Iterable {
+    /// The type to which the iterators refer.
+    pub(crate) element: crate::minisyn::Type,
+    /// Types we need in order to refer to the element type.
+    pub(crate) deps: Vec<QualifiedName>,
+}
+
+/// Finds types which have public `begin() const` and `end() const` methods,
+/// and for which we can figure out the element type. That's either from a
+/// nested `value_type` typedef, per the usual C++ container conventions, or
+/// else because `begin()` returns a plain pointer.
+///
+/// We hand out references to the elements, so dereferencing an iterator
+/// must give a reference rather than a proxy or a value. We know that's so
+/// if `begin()` returns a plain pointer, but otherwise we can't see the
+/// iterator's `operator*` so we only go ahead if the user has promised it
+/// using `iterable!`.
+///
+/// We consider all methods, even those we've decided not to generate,
+/// because `begin()` and `end()` typically return iterator types which we
+/// can't represent in Rust. We don't need to: we only ever call them from
+/// C++.
+pub(crate) fn find_iterables(
+    apis: &ApiVec<FnPrePhase2>,
+    config: &IncludeCppConfig,
+) -> HashMap<QualifiedName, Iterable> {
+    let mut begins = HashMap::new();
+    let mut ends = HashSet::new();
+    let mut has_iter_method = HashSet::new();
+    for api in apis.iter() {
+        if let Api::Function {
+            fun,
+            analysis:
+                analysis @ FnAnalysis {
+                    kind:
+                        FnKind::Method {
+                            impl_for,
+                            method_kind,
+                        },
+                    ..
+                },
+            ..
+        } = api
+        {
+            if analysis.rust_name == "iter" {
+                has_iter_method.insert(impl_for.clone());
+            }
+            let is_const_getter = matches!(
+                method_kind,
+                MethodKind::Normal(ReceiverMutability::Const)
+                    | MethodKind::Virtual(ReceiverMutability::Const)
+            ) && fun.inputs.len() == 1
+                && matches!(fun.cpp_vis, CppVisibility::Public);
+            if !is_const_getter {
+                continue;
+            }
+            let cpp_name = fun
+                .original_name
+                .clone()
+                .unwrap_or_else(|| fun.ident.to_string());
+            match cpp_name.as_str() {
+                "begin" => {
+                    begins.insert(impl_for.clone(), analysis);
+                }
+                "end" => {
+                    ends.insert(impl_for.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+    begins
+        .into_iter()
+        .filter(|(container, _)| ends.contains(container) && !has_iter_method.contains(container))
+        .filter_map(|(container, begin)| {
+            let pointee = pointee_of(begin);
+            if pointee.is_none() && !config.is_iterable(&container.to_cpp_name()) {
+                return None;
+            }
+            let iterable = find_value_type(apis, &container).or(pointee)?;
+            Some((container, iterable))
+        })
+        .collect()
+}
+
+/// Looks for a `value_type` typedef nested within the container.
+fn find_value_type(apis: &ApiVec<FnPrePhase2>, container: &QualifiedName) -> Option<Iterable> {
+    let value_type = QualifiedName::new(
+        container.get_namespace(),
+        make_ident(format!("{}_value_type", container.get_final_item())),
+    );
+    apis.iter().find_map(|api| match api {
+        Api::Typedef {
+            name,
+            analysis: TypedefAnalysis { kind, deps },
+            ..
+        } if name.name == value_type => {
+            let element = match kind {
+                TypedefKind::Type(item) => (*item.0.ty).clone(),
+                TypedefKind::Use(_, ty) => ty.0.clone(),
+            };
+            matches!(element, Type::Path(_)).then(|| Iterable {
+                element: element.into(),
+                deps: deps.iter().cloned().collect(),
+            })
+        }
+        _ => None,
+    })
+}
+
+/// If `begin()` returns a pointer, the element type is whatever it points to.
+fn pointee_of(begin: &FnAnalysis) -> Option<Iterable> {
+    match &begin.ret_type.0 {
+        syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Ptr(ptr) if matches!(*ptr.elem, Type::Path(_)) => Some(Iterable {
+                element: (*ptr.elem).clone().into(),
+                deps: begin.deps.iter().cloned().collect(),
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
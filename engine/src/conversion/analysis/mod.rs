@@ -10,16 +10,21 @@ pub(crate) mod abstract_types;
 pub(crate) mod allocators;
 pub(crate) mod casts;
 pub(crate) mod constructor_deps;
+pub(crate) mod container_elements;
 pub(crate) mod ctypes;
+pub(crate) mod deprecated;
 pub(crate) mod deps;
 mod depth_first;
 mod doc_label;
 pub(crate) mod fun;
 pub(crate) mod gc;
+pub(crate) mod iterable;
 mod name_check;
 pub(crate) mod pod; // hey, that rhymes
+pub(crate) mod probe;
 pub(crate) mod remove_ignored;
 mod replace_hopeless_typedef_targets;
+pub(crate) mod std_array;
 pub(crate) mod tdef;
 mod type_converter;
 
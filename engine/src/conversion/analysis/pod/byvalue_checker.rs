@@ -77,8 +77,21 @@ impl ByValueChecker {
         config: &IncludeCppConfig,
     ) -> Result<ByValueChecker, ConvertErrorFromCpp> {
         let mut byvalue_checker = ByValueChecker::new();
-        for blocklisted in config.get_blocklist() {
-            let tn = QualifiedName::new_from_cpp_name(blocklisted);
+        // Blocked types never reach us as APIs, so find those which match
+        // a block! pattern amongst the fields of the types which do.
+        let blocklisted_fields = apis
+            .iter()
+            .filter_map(|api| match api {
+                Api::Struct { details, .. } => Some(Self::get_field_types(&details.item)),
+                _ => None,
+            })
+            .flatten()
+            .filter(|tn| config.is_on_blocklist(&tn.to_cpp_name()));
+        let blocklisted = config
+            .get_blocklist()
+            .map(|name| QualifiedName::new_from_cpp_name(name))
+            .chain(blocklisted_fields);
+        for tn in blocklisted {
             let safety = PodState::UnsafeToBePod(format!("type {} is on the blocklist", &tn));
             byvalue_checker
                 .results
@@ -256,11 +269,15 @@ impl ByValueChecker {
     fn get_field_types(def: &ItemStruct) -> Vec<QualifiedName> {
         let mut results = Vec::new();
         for f in &def.fields {
-            let fty = &f.ty;
+            let mut fty = &f.ty;
+            // An array is only POD if its elements are.
+            while let Type::Array(arr) = fty {
+                fty = &arr.elem;
+            }
             if let Type::Path(p) = fty {
                 results.push(QualifiedName::from_type_path(p));
             }
-            // TODO handle anything else which bindgen might spit out, e.g. arrays?
+            // TODO handle anything else which bindgen might spit out?
         }
         results
     }
@@ -358,4 +375,18 @@ mod tests {
         bvc.ingest_struct(&t, &Namespace::new());
         assert!(bvc.satisfy_requests(vec![t_id]).is_err());
     }
+
+    #[test]
+    fn test_with_cxxstring_array() {
+        let mut bvc = ByValueChecker::new();
+        let t: ItemStruct = parse_quote! {
+            struct Bar {
+                a: [CxxString; 2],
+                b: i64,
+            }
+        };
+        let t_id = ty_from_ident(&t.ident);
+        bvc.ingest_struct(&t, &Namespace::new());
+        assert!(bvc.satisfy_requests(vec![t_id]).is_err());
+    }
 }
@@ -11,9 +11,10 @@ mod byvalue_checker;
 use indexmap::map::IndexMap as HashMap;
 use indexmap::set::IndexSet as HashSet;
 
-use autocxx_parser::IncludeCppConfig;
+use autocxx_parser::{IncludeCppConfig, PodDerive};
 use byvalue_checker::ByValueChecker;
-use syn::{ItemStruct, Type, Visibility};
+use quote::ToTokens;
+use syn::{Expr, ExprLit, ItemStruct, Lit, Type, TypeArray, Visibility};
 
 use crate::{
     conversion::{
@@ -122,9 +123,140 @@ pub(crate) fn analyze_pod_apis(
         Api::typedef_unchanged,
     );
     assert!(more_extra_apis.is_empty());
+    check_pod_derives(&results, config)?;
     Ok(results)
 }
 
+/// Return an error if any of the traits requested using `pod_derive!`
+/// can't be derived, because the type isn't POD or because one of its
+/// fields doesn't implement that trait.
+fn check_pod_derives(
+    apis: &ApiVec<PodPhase>,
+    config: &IncludeCppConfig,
+) -> Result<(), ConvertErrorFromCpp> {
+    let enums: HashSet<QualifiedName> = apis
+        .iter()
+        .filter_map(|api| match api {
+            Api::Enum { name, .. } => Some(name.name.clone()),
+            _ => None,
+        })
+        .collect();
+    for api in apis.iter() {
+        let cpp_name = api.name().to_cpp_name();
+        let derives = config.get_pod_derives(&cpp_name);
+        for derive in derives {
+            let problem = match api {
+                _ if matches!(derive, PodDerive::Copy) && !derives.contains(&PodDerive::Clone) => {
+                    Some("Copy also requires Clone".to_string())
+                }
+                Api::Struct { analysis, .. } if !matches!(analysis.kind, TypeKind::Pod) => {
+                    Some("it is not a POD type".to_string())
+                }
+                Api::Struct { analysis, .. } => analysis
+                    .field_info
+                    .iter()
+                    .find(|field| !type_supports_derive(&field.ty, *derive, config, &enums))
+                    .map(|field| {
+                        format!(
+                            "the field type {} does not implement it",
+                            field.ty.to_token_stream()
+                        )
+                    }),
+                Api::Enum { .. } if matches!(derive, PodDerive::Default) => {
+                    Some("it is an enum".to_string())
+                }
+                _ => None,
+            };
+            if let Some(problem) = problem {
+                return Err(ConvertErrorFromCpp::UnsupportedPodDerive(
+                    cpp_name,
+                    derive.to_string(),
+                    problem,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn type_supports_derive(
+    ty: &Type,
+    derive: PodDerive,
+    config: &IncludeCppConfig,
+    enums: &HashSet<QualifiedName>,
+) -> bool {
+    match ty {
+        Type::Path(typ) => {
+            let name = QualifiedName::from_type_path(typ);
+            let ns = name.get_namespace();
+            match (ns.to_cpp_path().as_str(), name.get_final_item()) {
+                ("", "f32" | "f64") => !matches!(derive, PodDerive::Eq | PodDerive::Hash),
+                (
+                    "",
+                    "i8" | "u8" | "i16" | "u16" | "i32" | "u32" | "i64" | "u64" | "isize" | "usize"
+                    | "bool" | "char",
+                ) => true,
+                // These are aliases for Rust primitives.
+                ("std::os::raw", _) => true,
+                // autocxx's own wrappers for C integer types.
+                ("autocxx", final_item) if final_item.starts_with("c_") => {
+                    !matches!(derive, PodDerive::Serialize | PodDerive::Deserialize)
+                }
+                _ => {
+                    // bindgen always derives these for enums.
+                    (enums.contains(&name)
+                        && matches!(
+                            derive,
+                            PodDerive::Clone
+                                | PodDerive::Copy
+                                | PodDerive::Debug
+                                | PodDerive::PartialEq
+                                | PodDerive::Eq
+                                | PodDerive::Hash
+                        ))
+                        || config
+                            .get_pod_derives(&name.to_cpp_name())
+                            .contains(&derive)
+                }
+            }
+        }
+        Type::Array(arr) => {
+            type_supports_derive(&arr.elem, derive, config, enums)
+                // Default and the serde traits are only implemented for
+                // arrays of up to 32 elements.
+                && (matches!(
+                    derive,
+                    PodDerive::Clone
+                        | PodDerive::Copy
+                        | PodDerive::Debug
+                        | PodDerive::PartialEq
+                        | PodDerive::Eq
+                        | PodDerive::Hash
+                ) || array_len(arr).map(|len| len <= 32).unwrap_or(false))
+        }
+        Type::Ptr(_) => matches!(
+            derive,
+            PodDerive::Clone
+                | PodDerive::Copy
+                | PodDerive::Debug
+                | PodDerive::PartialEq
+                | PodDerive::Eq
+                | PodDerive::Hash
+        ),
+        _ => false,
+    }
+}
+
+/// The length of an array type, if it's a literal.
+fn array_len(arr: &TypeArray) -> Option<usize> {
+    match &arr.len {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(len), ..
+        }) => len.base10_parse().ok(),
+        _ => None,
+    }
+}
+
 fn analyze_enum(
     name: ApiName,
     mut item: crate::minisyn::ItemEnum,
@@ -238,12 +370,15 @@ fn get_struct_field_types(
                     .unwrap_or(false)
                 {
                     field_deps.extend(r.types_encountered);
-                    if let Type::Path(typ) = &r.ty {
-                        // Later analyses need to know about the field
-                        // types where we need full definitions, as opposed
-                        // to just declarations. That means just the outermost
-                        // type path.
-                        // TODO: consider arrays.
+                    // Later analyses need to know about the field
+                    // types where we need full definitions, as opposed
+                    // to just declarations. That means just the outermost
+                    // type path, or the element type of an array.
+                    let mut fty = &r.ty;
+                    while let Type::Array(arr) = fty {
+                        fty = &arr.elem;
+                    }
+                    if let Type::Path(typ) = fty {
                         field_definition_deps.insert(QualifiedName::from_type_path(typ));
                     }
                     field_info.push(FieldInfo {
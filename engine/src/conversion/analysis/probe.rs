@@ -0,0 +1,102 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use indexmap::map::IndexMap as HashMap;
+use itertools::Itertools;
+use syn::ItemMod;
+
+use crate::{
+    conversion::api::{Api, ApiName, CppVisibility, DeletedOrDefaulted, FuncToConvert, NullPhase},
+    types::{Namespace, QualifiedName},
+};
+
+/// Runs bindgen again over the same headers with some extra C++ appended,
+/// returning bindings for just the variables whose names match the given
+/// pattern. If that fails, e.g. because the extra C++ doesn't compile,
+/// returns the error, including any diagnostics from clang.
+pub(crate) type BindgenProbe<'a> = dyn Fn(&str, &str) -> Result<ItemMod, String> + 'a;
+
+/// Functions with the same C++ name in the same scope are overloads.
+type FunctionKey = (Option<QualifiedName>, Namespace, String);
+
+fn function_key(name: &ApiName, fun: &FuncToConvert) -> FunctionKey {
+    (
+        fun.self_ty.clone(),
+        name.name.get_namespace().clone(),
+        name.cpp_name(),
+    )
+}
+
+/// Works out how to refer to functions in the extra C++ we give to a
+/// [`BindgenProbe`].
+pub(super) struct ProbeNames {
+    class_names: HashMap<QualifiedName, String>,
+    overloads: HashMap<FunctionKey, usize>,
+}
+
+impl ProbeNames {
+    pub(super) fn new(apis: &[Api<NullPhase>]) -> Self {
+        let class_names = apis
+            .iter()
+            .filter_map(|api| match api {
+                Api::Struct { name, .. } => Some((name.name.clone(), name.qualified_cpp_name())),
+                _ => None,
+            })
+            .collect();
+        let mut overloads = HashMap::new();
+        for api in apis {
+            if let Api::Function { name, fun, .. } = api {
+                *overloads.entry(function_key(name, fun)).or_default() += 1;
+            }
+        }
+        Self {
+            class_names,
+            overloads,
+        }
+    }
+
+    /// The qualified C++ name of a function, if we can take its address.
+    pub(super) fn function(&self, name: &ApiName, fun: &FuncToConvert) -> Option<String> {
+        if !matches!(fun.cpp_vis, CppVisibility::Public)
+            || !matches!(fun.is_deleted, DeletedOrDefaulted::Neither)
+            || fun.special_member.is_some()
+            || fun.variadic
+            || fun.unused_template_param
+            || fun.synthetic_cpp.is_some()
+        {
+            return None;
+        }
+        // We can't take the address of an overloaded function.
+        if self.overloads.get(&function_key(name, fun)).copied() != Some(1) {
+            return None;
+        }
+        let cpp_name = name.cpp_name();
+        match &fun.self_ty {
+            Some(self_ty) => {
+                let class_name = self
+                    .class_names
+                    .get(self_ty)
+                    .cloned()
+                    .unwrap_or_else(|| self_ty.to_cpp_name());
+                // Nor of a constructor or destructor.
+                let unqualified_class_name = class_name.rsplit("::").next().unwrap_or(&class_name);
+                if cpp_name == unqualified_class_name || cpp_name.starts_with('~') {
+                    return None;
+                }
+                Some(format!("{class_name}::{cpp_name}"))
+            }
+            None => Some(
+                name.name
+                    .get_namespace()
+                    .iter()
+                    .chain(std::iter::once(&cpp_name))
+                    .join("::"),
+            ),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use indexmap::map::IndexMap as HashMap;
+use itertools::Itertools;
+use syn::{
+    parse_quote, Expr, ExprLit, FnArg, GenericArgument, Item, Lit, Pat, PathArguments, ReturnType,
+    Type, TypePath, Visibility,
+};
+
+use crate::{
+    conversion::{
+        api::{Api, NullPhase, TypedefKind},
+        apivec::ApiVec,
+    },
+    known_types::known_types,
+    types::{validate_ident_ok_for_rust, QualifiedName},
+};
+
+use super::probe::{BindgenProbe, ProbeNames};
+
+const LEN_PREFIX: &str = "autocxx_array_len_";
+
+/// bindgen can't represent the length of a `std::array`, so it tells us
+/// only about the element type. Here we ask the C++ compiler for the
+/// length of each `std::array` we can name, by running bindgen again.
+/// Within struct fields, they then become Rust arrays, so that the struct
+/// can still be POD. Elsewhere, we add the length as a second template
+/// argument, and the type converter decides how to represent the array.
+pub(crate) fn find_std_array_lengths(
+    apis: ApiVec<NullPhase>,
+    probe: &BindgenProbe,
+) -> ApiVec<NullPhase> {
+    let mut apis: Vec<_> = apis.into_iter().collect();
+    let names = ProbeNames::new(&apis);
+    let mut array_types = Vec::new();
+    for api in apis.iter_mut() {
+        visit_std_arrays_in_api(api, &names, &mut |_, cpp_type, _| {
+            array_types.push(cpp_type.to_string())
+        });
+    }
+    if array_types.is_empty() {
+        let mut results = ApiVec::new();
+        results.extend(apis.into_iter());
+        return results;
+    }
+    let mut lengths = probe_lengths(&array_types, probe).into_iter();
+    for api in apis.iter_mut() {
+        visit_std_arrays_in_api(api, &names, &mut |ty, _, in_field| {
+            if let Some(len) = lengths.next().flatten() {
+                set_len(ty, len, in_field)
+            }
+        });
+    }
+    let mut results = ApiVec::new();
+    results.extend(apis.into_iter());
+    results
+}
+
+/// Calls `f` for each `std::array` of unknown length in the fields of a
+/// struct, a typedef, or the parameters and return type of a function,
+/// along with the C++ type of that `std::array` and whether it's in a
+/// struct field. We skip anything which C++ won't let us name.
+fn visit_std_arrays_in_api(
+    api: &mut Api<NullPhase>,
+    names: &ProbeNames,
+    f: &mut dyn FnMut(&mut Type, &str, bool),
+) {
+    match api {
+        Api::Struct { name, details, .. }
+            if details.item.generics.params.is_empty()
+                && !name.name.get_final_item().contains("__bindgen") =>
+        {
+            let struct_name = name.qualified_cpp_name();
+            for field in details.item.fields.iter_mut() {
+                let field_name = match &field.ident {
+                    Some(id) if matches!(field.vis, Visibility::Public(_)) => id.to_string(),
+                    _ => continue,
+                };
+                if field_name.starts_with("_base") || field_name.starts_with("__bindgen") {
+                    continue;
+                }
+                // bindgen appends an underscore to fields named after Rust keywords.
+                let field_name = match field_name.strip_suffix('_') {
+                    Some(cpp_name) if validate_ident_ok_for_rust(cpp_name).is_err() => {
+                        cpp_name.to_string()
+                    }
+                    _ => field_name,
+                };
+                let cpp_type = format!("decltype({struct_name}::{field_name})");
+                visit_std_arrays(&mut field.ty, &cpp_type, &mut |ty, cpp_type| {
+                    f(ty, cpp_type, true)
+                });
+            }
+        }
+        Api::Typedef {
+            name,
+            item: TypedefKind::Type(ity),
+            ..
+        } if ity.generics.params.is_empty() => {
+            visit_std_arrays(
+                &mut ity.ty,
+                &name.qualified_cpp_name(),
+                &mut |ty, cpp_type| f(ty, cpp_type, false),
+            );
+        }
+        Api::Function { name, fun, .. } => {
+            let function = match names.function(name, fun) {
+                Some(function) => function,
+                None => return,
+            };
+            let sig = format!("autocxx_probe::sig<decltype(&{function})>");
+            let params = fun.inputs.iter_mut().filter_map(|arg| match &mut **arg {
+                FnArg::Typed(pt) => match pt.pat.as_ref() {
+                    Pat::Ident(pti) if pti.ident == "this" => None,
+                    _ => Some(&mut pt.ty),
+                },
+                FnArg::Receiver(_) => None,
+            });
+            for (idx, ty) in params.enumerate() {
+                let cpp_type = format!("std::tuple_element_t<{idx}, {sig}::args>");
+                visit_std_arrays(ty, &cpp_type, &mut |ty, cpp_type| f(ty, cpp_type, false));
+            }
+            if let ReturnType::Type(_, ty) = &mut *fun.output {
+                let cpp_type = format!("{sig}::ret");
+                visit_std_arrays(ty, &cpp_type, &mut |ty, cpp_type| f(ty, cpp_type, false));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Calls `f` for each `std::array` of unknown length within `ty`, innermost
+/// first, along with its C++ type, given `cpp_type`, the C++ type of `ty`.
+/// We don't look inside any other templates, since we can't generally
+/// name their template arguments.
+fn visit_std_arrays(ty: &mut Type, cpp_type: &str, f: &mut dyn FnMut(&mut Type, &str)) {
+    match ty {
+        Type::Path(typ) if is_std_array_of_unknown_length(typ) => {
+            if let Some(GenericArgument::Type(element)) = get_args(typ).first_mut() {
+                let element_type = format!("std::remove_cv_t<{cpp_type}>::value_type");
+                visit_std_arrays(element, &element_type, f);
+            }
+            f(ty, cpp_type)
+        }
+        Type::Ptr(ptr) => {
+            let pointee_type = format!(
+                "std::remove_pointer_t<std::remove_cv_t<std::remove_reference_t<{cpp_type}>>>"
+            );
+            visit_std_arrays(&mut ptr.elem, &pointee_type, f)
+        }
+        Type::Array(arr) => {
+            let element_type = format!("std::remove_extent_t<{cpp_type}>");
+            visit_std_arrays(&mut arr.elem, &element_type, f)
+        }
+        _ => {}
+    }
+}
+
+fn is_std_array_of_unknown_length(typ: &TypePath) -> bool {
+    known_types().is_array(&QualifiedName::from_type_path(typ))
+        && matches!(
+            &typ.path.segments.last().unwrap().arguments,
+            PathArguments::AngleBracketed(ab) if ab.args.len() == 1
+        )
+}
+
+fn get_args(typ: &mut TypePath) -> Vec<&mut GenericArgument> {
+    match &mut typ.path.segments.last_mut().unwrap().arguments {
+        PathArguments::AngleBracketed(ab) => ab.args.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Records the length of a `std::array`: as a Rust array for a struct
+/// field, and otherwise as a second template argument for the type
+/// converter to find.
+fn set_len(ty: &mut Type, len: usize, in_field: bool) {
+    let len = proc_macro2::Literal::usize_unsuffixed(len);
+    if let Type::Path(typ) = ty {
+        if in_field {
+            if let Some(GenericArgument::Type(element)) = get_args(typ).first() {
+                let element = (*element).clone();
+                *ty = Type::Array(parse_quote! { [#element; #len] });
+            }
+        } else if let PathArguments::AngleBracketed(ab) =
+            &mut typ.path.segments.last_mut().unwrap().arguments
+        {
+            ab.args.push(parse_quote! { #len });
+        }
+    }
+}
+
+/// Asks the C++ compiler for the length of each of the given `std::array`
+/// types. If that fails, perhaps because we named something C++ won't let
+/// us, we try each one separately so that one failure doesn't stop us
+/// finding the rest.
+fn probe_lengths(array_types: &[String], probe: &BindgenProbe) -> Vec<Option<usize>> {
+    let all: Vec<_> = array_types.iter().enumerate().collect();
+    match run_probe(&all, probe) {
+        Some(lengths) => (0..array_types.len())
+            .map(|idx| lengths.get(&idx).copied())
+            .collect(),
+        None if array_types.len() > 1 => all
+            .into_iter()
+            .map(|array_type| {
+                run_probe(&[array_type], probe)
+                    .and_then(|lengths| lengths.get(&array_type.0).copied())
+            })
+            .collect(),
+        None => vec![None],
+    }
+}
+
+fn run_probe(
+    array_types: &[(usize, &String)],
+    probe: &BindgenProbe,
+) -> Option<HashMap<usize, usize>> {
+    let cpp = std::iter::once(probe_prelude())
+        .chain(array_types.iter().map(|(idx, array_type)| {
+            format!("constexpr std::size_t {LEN_PREFIX}{idx} = std::tuple_size<std::remove_cv_t<{array_type}>>::value;")
+        }))
+        .join("\n");
+    let bindings = probe(&cpp, &format!("{LEN_PREFIX}.*"))
+        .map_err(|err| log::info!("Unable to find std::array lengths: {}", err))
+        .ok()?;
+    let mut lengths = HashMap::new();
+    find_lengths(&bindings.content?.1, &mut lengths);
+    Some(lengths)
+}
+
+fn find_lengths(items: &[Item], lengths: &mut HashMap<usize, usize>) {
+    for item in items {
+        match item {
+            Item::Mod(itm) => {
+                if let Some((_, items)) = &itm.content {
+                    find_lengths(items, lengths)
+                }
+            }
+            Item::Const(ic) => {
+                let idx = ic
+                    .ident
+                    .to_string()
+                    .strip_prefix(LEN_PREFIX)
+                    .and_then(|idx| idx.parse().ok());
+                if let (
+                    Some(idx),
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(len), ..
+                    }),
+                ) = (idx, ic.expr.as_ref())
+                {
+                    if let Ok(len) = len.base10_parse() {
+                        lengths.insert(idx, len);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lets us name the types of the parameters and return value of a
+/// function, given a pointer to it.
+fn probe_prelude() -> String {
+    let sig = |qualifiers: &String| {
+        format!("template <typename R, typename... A> struct sig<R(A...){qualifiers}> {{ using ret = R; using args = std::tuple<A...>; }};")
+    };
+    let qualifiers: Vec<_> = ["", " const", " volatile", " const volatile"]
+        .into_iter()
+        .cartesian_product(["", " &", " &&"])
+        .map(|(cv, reference)| format!("{cv}{reference}"))
+        .collect();
+    let noexcept_qualifiers: Vec<_> = qualifiers
+        .iter()
+        .map(|qualifiers| format!("{qualifiers} noexcept"))
+        .collect();
+    [
+        "#include <array>",
+        "#include <cstddef>",
+        "#include <tuple>",
+        "#include <type_traits>",
+        "namespace autocxx_probe {",
+        "template <typename F> struct sig;",
+        "template <typename F> struct sig<F*> : sig<F> {};",
+        "template <typename C, typename F> struct sig<F C::*> : sig<F> {};",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .chain(qualifiers.iter().map(sig))
+    .chain(std::iter::once(
+        "#if __cpp_noexcept_function_type".to_string(),
+    ))
+    .chain(noexcept_qualifiers.iter().map(sig))
+    .chain(["#endif".to_string(), "}".to_string()])
+    .join("\n")
+}
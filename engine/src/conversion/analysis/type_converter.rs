@@ -8,7 +8,9 @@
 
 use crate::{
     conversion::{
-        api::{AnalysisPhase, Api, ApiName, NullPhase, TypedefKind, UnanalyzedApi},
+        api::{
+            AnalysisPhase, Api, ApiName, ConcreteContainer, NullPhase, TypedefKind, UnanalyzedApi,
+        },
         apivec::ApiVec,
         codegen_cpp::type_to_cpp::CppNameMap,
         ConvertErrorFromCpp,
@@ -23,8 +25,8 @@ use itertools::Itertools;
 use proc_macro2::Ident;
 use quote::ToTokens;
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Comma, GenericArgument, PathArguments, PathSegment,
-    Type, TypePath, TypePtr,
+    parse_quote, punctuated::Punctuated, token::Comma, Expr, ExprLit, GenericArgument, Lit,
+    PathArguments, PathSegment, Type, TypePath, TypePtr,
 };
 
 use super::tdef::TypedefAnalysis;
@@ -294,6 +296,24 @@ impl<'a> TypeConverter<'a> {
             }
         };
 
+        if known_types().is_array(&tn) {
+            return self.convert_std_array(typ, &tn, ns, ctx, deps);
+        }
+
+        // std::string_view parameters are turned into &str by the function
+        // analysis, but we can't do that anywhere else. We let it through
+        // within struct fields, which are either opaque or will be rejected
+        // by the POD analysis.
+        if known_types().is_string_view(&tn)
+            && !matches!(
+                ctx,
+                TypeConversionContext::OuterType { .. }
+                    | TypeConversionContext::WithinStructField { .. }
+            )
+        {
+            return Err(ConvertErrorFromCpp::StringViewNotParam);
+        }
+
         // Now let's see if it's a known type.
         // (We may entirely reject some types at this point too.)
         let mut typ = match known_types().consider_substitution(&tn) {
@@ -319,25 +339,103 @@ impl<'a> TypeConverter<'a> {
             if generic_behavior != CxxGenericType::Not {
                 // this is a type of generic understood by cxx (e.g. CxxVector)
                 // so let's convert any generic type arguments. This recurses.
+                let mut concrete_fallback = None;
                 if let PathArguments::AngleBracketed(ref mut ab) = last_seg.arguments {
+                    if generic_behavior == CxxGenericType::CppPtr {
+                        Self::strip_default_deleter(&mut ab.args);
+                    }
+                    let original_arguments = PathArguments::AngleBracketed(ab.clone());
                     let mut innerty = self.convert_punctuated(
                         ab.args.clone(),
                         ns,
                         &TypeConversionContext::WithinContainer,
                     )?;
                     ab.args = innerty.ty;
-                    kind = self.confirm_inner_type_is_acceptable_generic_payload(
-                        &ab.args,
-                        &tn,
-                        generic_behavior,
-                        forward_declarations_ok,
-                    )?;
+                    if generic_behavior == CxxGenericType::CppPtr {
+                        // cxx's UniquePtr always uses the default deleter on
+                        // a single object, so for anything else we make an
+                        // opaque owning type.
+                        match ab.args.first() {
+                            Some(GenericArgument::Type(Type::Path(element)))
+                                if ab.args.len() > 1 =>
+                            {
+                                concrete_fallback = Some((
+                                    original_arguments.clone(),
+                                    ConcreteContainer::UniquePtr {
+                                        element: Type::Path(element.clone()).into(),
+                                        array: false,
+                                    },
+                                ))
+                            }
+                            Some(GenericArgument::Type(Type::Array(arr))) => {
+                                if !matches!(*arr.elem, Type::Path(_)) {
+                                    return Err(
+                                        ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
+                                            tn.clone(),
+                                        ),
+                                    );
+                                }
+                                concrete_fallback = Some((
+                                    original_arguments.clone(),
+                                    ConcreteContainer::UniquePtr {
+                                        element: (*arr.elem).clone().into(),
+                                        array: true,
+                                    },
+                                ))
+                            }
+                            _ => {}
+                        }
+                    }
+                    if concrete_fallback.is_none() {
+                        match self.confirm_inner_type_is_acceptable_generic_payload(
+                            &ab.args,
+                            &tn,
+                            generic_behavior,
+                            forward_declarations_ok,
+                        ) {
+                            Ok(inner_kind) => kind = inner_kind,
+                            // cxx can't put this in a CxxVector, but we can still
+                            // make an opaque type with our own accessors.
+                            Err(
+                                err @ (ConvertErrorFromCpp::InvalidTypeForCppVector(_)
+                                | ConvertErrorFromCpp::GenericsWithinVector),
+                            ) => match ab.args.first() {
+                                Some(GenericArgument::Type(Type::Path(element)))
+                                    if ab.args.len() == 1 =>
+                                {
+                                    // std::vector<bool> is bit-packed, so we can't
+                                    // refer to its elements.
+                                    let element_by_value = element.path.is_ident("bool");
+                                    concrete_fallback = Some((
+                                        original_arguments,
+                                        ConcreteContainer::Vector {
+                                            element: Type::Path(element.clone()).into(),
+                                            element_by_value,
+                                        },
+                                    ))
+                                }
+                                _ => return Err(err),
+                            },
+                            Err(err) => return Err(err),
+                        }
+                    }
                     deps.extend(innerty.types_encountered.drain(..));
+                    extra_apis.append(&mut innerty.extra_apis);
                 } else {
                     return Err(ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
                         tn.clone(),
                     ));
                 }
+                if let Some((original_arguments, container)) = concrete_fallback {
+                    let mut original_typ = typ.clone();
+                    original_typ.path.segments.last_mut().unwrap().arguments = original_arguments;
+                    let (new_tn, api) =
+                        self.get_templated_typename(&Type::Path(original_typ), Some(container))?;
+                    extra_apis.extend(api.into_iter());
+                    deps.remove(&tn);
+                    typ = new_tn.to_type_path();
+                    deps.insert(new_tn);
+                }
             } else {
                 // Oh poop. It's a generic type which cxx won't be able to handle.
                 // We'll have to come up with a concrete type in both the cxx::bridge (in Rust)
@@ -370,7 +468,9 @@ impl<'a> TypeConverter<'a> {
                 if self.ignored_types.contains(&qn) {
                     return Err(ConvertErrorFromCpp::ConcreteVersionOfIgnoredTemplate);
                 }
-                let (new_tn, api) = self.get_templated_typename(&Type::Path(typ))?;
+                let container =
+                    self.get_concrete_container(&tn, &typ, ns, &mut deps, &mut extra_apis);
+                let (new_tn, api) = self.get_templated_typename(&Type::Path(typ), container)?;
                 extra_apis.extend(api.into_iter());
                 deps.remove(&tn);
                 typ = new_tn.to_type_path();
@@ -380,6 +480,80 @@ impl<'a> TypeConverter<'a> {
         Ok(Annotated::new(Type::Path(typ), deps, extra_apis, kind))
     }
 
+    /// bindgen can't see the length of a `std::array`, so the `std_array`
+    /// analysis phase adds it as a second template argument where it can.
+    /// cxx can pass around Rust arrays of its own built-in types, so we
+    /// use those. Otherwise, or within some other template, we make an
+    /// opaque type with accessors.
+    fn convert_std_array(
+        &mut self,
+        typ: TypePath,
+        tn: &QualifiedName,
+        ns: &Namespace,
+        ctx: &TypeConversionContext,
+        mut deps: HashSet<QualifiedName>,
+    ) -> Result<Annotated<Type>, ConvertErrorFromCpp> {
+        let (element, len) = match &typ.path.segments.last().unwrap().arguments {
+            PathArguments::AngleBracketed(ab) if ab.args.len() == 2 => {
+                match (&ab.args[0], &ab.args[1]) {
+                    (
+                        GenericArgument::Type(element),
+                        GenericArgument::Const(Expr::Lit(ExprLit {
+                            lit: Lit::Int(len), ..
+                        })),
+                    ) => (
+                        element.clone(),
+                        len.base10_parse::<usize>()
+                            .map_err(|_| ConvertErrorFromCpp::UnknownStdArrayLength)?,
+                    ),
+                    _ => return Err(ConvertErrorFromCpp::UnknownStdArrayLength),
+                }
+            }
+            _ => return Err(ConvertErrorFromCpp::UnknownStdArrayLength),
+        };
+        let mut innerty =
+            self.convert_type(element, ns, &TypeConversionContext::WithinContainer)?;
+        deps.extend(innerty.types_encountered.drain(..));
+        let element = match innerty.ty {
+            Type::Path(element) => element,
+            _ => {
+                return Err(ConvertErrorFromCpp::TemplatedTypeContainingNonPathArg(
+                    tn.clone(),
+                ))
+            }
+        };
+        if !matches!(ctx, TypeConversionContext::WithinContainer)
+            && known_types().permissible_within_array(&QualifiedName::from_type_path(&element))
+        {
+            let len = proc_macro2::Literal::usize_unsuffixed(len);
+            return Ok(Annotated::new(
+                Type::Array(parse_quote! { [#element; #len] }),
+                deps,
+                innerty.extra_apis,
+                TypeKind::Regular,
+            ));
+        }
+        let (new_tn, api) = self.get_templated_typename(
+            &Type::Path(typ),
+            Some(ConcreteContainer::Array {
+                element: Type::Path(element).into(),
+                len,
+                // We don't know which types are POD yet.
+                pod: false,
+            }),
+        )?;
+        innerty.extra_apis.extend(api.into_iter());
+        deps.remove(tn);
+        let new_typ = new_tn.to_type_path();
+        deps.insert(new_tn);
+        Ok(Annotated::new(
+            Type::Path(new_typ),
+            deps,
+            innerty.extra_apis,
+            TypeKind::Regular,
+        ))
+    }
+
     fn get_generic_args(typ: &mut TypePath) -> Option<&mut PathSegment> {
         match typ.path.segments.last_mut() {
             Some(s) if !s.arguments.is_empty() => Some(s),
@@ -519,9 +693,97 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    /// For a `std::map`, `std::unordered_map`, `std::pair`, `std::tuple` or
+    /// `std::variant`, find the types of the template arguments so that we can generate
+    /// helper functions. If they're not types we can handle, we'll just
+    /// make an opaque type as we do for any other template.
+    fn get_concrete_container(
+        &mut self,
+        tn: &QualifiedName,
+        typ: &TypePath,
+        ns: &Namespace,
+        deps: &mut HashSet<QualifiedName>,
+        extra_apis: &mut ApiVec<NullPhase>,
+    ) -> Option<ConcreteContainer> {
+        let is_map = known_types().is_map(tn);
+        let is_pair = known_types().is_pair(tn);
+        let is_variant = known_types().is_variant(tn);
+        if !is_map && !is_pair && !is_variant && !known_types().is_tuple(tn) {
+            return None;
+        }
+        let args = match &typ.path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(ab) => ab.args.clone(),
+            _ => return None,
+        };
+        let mut converted = Vec::new();
+        for arg in args {
+            let ty = match arg {
+                GenericArgument::Type(ty) => ty,
+                _ => return None,
+            };
+            let annotated = self
+                .convert_type(ty, ns, &TypeConversionContext::WithinContainer)
+                .ok()?;
+            match &annotated.ty {
+                Type::Path(inner) if self.is_permissible_container_payload(inner) => {}
+                _ => return None,
+            }
+            converted.push(annotated);
+        }
+        if converted.is_empty() || ((is_map || is_pair) && converted.len() != 2) {
+            return None;
+        }
+        let mut types = Vec::new();
+        for mut annotated in converted {
+            deps.extend(annotated.types_encountered.drain(..));
+            extra_apis.append(&mut annotated.extra_apis);
+            types.push(crate::minisyn::Type::from(annotated.ty));
+        }
+        Some(if is_map || is_pair {
+            let second = types.pop().unwrap();
+            let first = types.pop().unwrap();
+            if is_map {
+                ConcreteContainer::Map {
+                    key: first,
+                    value: second,
+                    // We don't know about copy constructors yet.
+                    insertable: false,
+                }
+            } else {
+                ConcreteContainer::Pair {
+                    first,
+                    second,
+                    // We don't know which types are POD yet.
+                    pod: false,
+                }
+            }
+        } else if is_variant {
+            ConcreteContainer::Variant {
+                alternatives: types,
+            }
+        } else {
+            ConcreteContainer::Tuple {
+                elements: types,
+                pod: false,
+            }
+        })
+    }
+
+    fn is_permissible_container_payload(&self, typ: &TypePath) -> bool {
+        let qn = QualifiedName::from_type_path(typ);
+        typ.path
+            .segments
+            .last()
+            .map(|seg| seg.arguments.is_empty())
+            .unwrap_or(false)
+            && known_types().permissible_within_concrete_container(&qn)
+            && !self.forward_declarations.contains(&qn)
+    }
+
     fn get_templated_typename(
         &mut self,
         rs_definition: &Type,
+        container: Option<ConcreteContainer>,
     ) -> Result<(QualifiedName, Option<UnanalyzedApi>), ConvertErrorFromCpp> {
         let count = self.concrete_templates.len();
         // We just use this as a hash key, essentially.
@@ -557,6 +819,7 @@ impl<'a> TypeConverter<'a> {
                     name: ApiName::new_in_root_namespace(make_ident(synthetic_ident)),
                     cpp_definition: cpp_definition.clone(),
                     rs_definition: Some(Box::new(rs_definition.clone().into())),
+                    container: container.map(Box::new),
                 };
                 self.concrete_templates
                     .insert(cpp_definition, api.name().clone());
@@ -565,6 +828,18 @@ impl<'a> TypeConverter<'a> {
         }
     }
 
+    /// `std::unique_ptr<T, std::default_delete<T>>` is just a regular
+    /// `std::unique_ptr<T>`, so drop the deleter if it's the default one.
+    fn strip_default_deleter(args: &mut Punctuated<GenericArgument, Comma>) {
+        if let Some(GenericArgument::Type(Type::Path(deleter))) = args.iter().nth(1) {
+            if args.len() == 2
+                && QualifiedName::from_type_path(deleter).to_cpp_name() == "std::default_delete"
+            {
+                *args = args.iter().take(1).cloned().collect();
+            }
+        }
+    }
+
     fn confirm_inner_type_is_acceptable_generic_payload(
         &self,
         path_args: &Punctuated<GenericArgument, Comma>,
@@ -696,10 +971,12 @@ pub(crate) fn add_analysis<A: AnalysisPhase>(api: UnanalyzedApi) -> Api<A> {
             name,
             rs_definition,
             cpp_definition,
+            container,
         } => Api::ConcreteType {
             name,
             rs_definition,
             cpp_definition,
+            container,
         },
         Api::IgnoredItem { name, err, ctx } => Api::IgnoredItem { name, err, ctx },
         _ => panic!("Function analysis created an unexpected type of extra API"),
@@ -273,6 +273,9 @@ pub(crate) struct FuncToConvert {
     pub(crate) provenance: Provenance,
     pub(crate) ident: Ident,
     pub(crate) doc_attrs: Vec<Attribute>,
+    /// Any `#[must_use]` or `#[deprecated]` attributes derived from the
+    /// corresponding C++ attributes.
+    pub(crate) lint_attrs: Vec<Attribute>,
     pub(crate) inputs: Punctuated<FnArg, Comma>,
     pub(crate) variadic: bool,
     pub(crate) output: ReturnType,
@@ -448,6 +451,51 @@ impl SubclassName {
     }
 }
 
+/// A well-known C++ container type for which we generate helper
+/// functions, beyond just representing it as an opaque type.
+#[derive(Clone, Debug)]
+pub(crate) enum ConcreteContainer {
+    /// `std::map` or `std::unordered_map`, with the (converted)
+    /// types of the keys and values. `insertable` is set once we know
+    /// that both can be copy constructed, which `insert` requires.
+    Map {
+        key: Type,
+        value: Type,
+        insertable: bool,
+    },
+    /// `std::pair`, with the (converted) types of its two elements.
+    /// `pod` is set once we know that both elements are POD, so it can
+    /// be copied into Rust by value.
+    Pair {
+        first: Type,
+        second: Type,
+        pod: bool,
+    },
+    /// `std::tuple`, with the (converted) types of its elements. `pod`
+    /// is as for [`ConcreteContainer::Pair`].
+    Tuple { elements: Vec<Type>, pod: bool },
+    /// `std::variant`, with the (converted) types of its alternatives.
+    Variant { alternatives: Vec<Type> },
+    /// A `std::vector` whose elements cxx can't handle in a `CxxVector`.
+    /// `element_by_value` is set where elements can't be referenced, as
+    /// for `std::vector<bool>`.
+    Vector {
+        element: Type,
+        element_by_value: bool,
+    },
+    /// A `std::array` which can't be represented as a Rust array, with
+    /// the (converted) type of its elements and its length. `pod` is as
+    /// for [`ConcreteContainer::Pair`], and allows access as a slice.
+    Array {
+        element: Type,
+        len: usize,
+        pod: bool,
+    },
+    /// A `std::unique_ptr` which cxx can't represent as a `UniquePtr`,
+    /// because it has a custom deleter or owns an array (`T[]`).
+    UniquePtr { element: Type, array: bool },
+}
+
 #[derive(std::fmt::Debug)]
 /// Different types of API we might encounter.
 ///
@@ -490,6 +538,7 @@ pub(crate) enum Api<T: AnalysisPhase> {
         name: ApiName,
         rs_definition: Option<Box<Type>>,
         cpp_definition: String,
+        container: Option<Box<ConcreteContainer>>,
     },
     /// A simple note that we want to make a constructor for
     /// a `std::string` on the heap.
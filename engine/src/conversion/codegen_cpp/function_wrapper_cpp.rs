@@ -26,6 +26,7 @@ impl TypeConversionPolicy {
             CppConversionType::FromPtrToValue => {
                 Ok(format!("{}*", self.unwrapped_type_as_string(cpp_name_map)?))
             }
+            CppConversionType::FromStringViewToStr => Ok("std::string_view".into()),
             _ => self.unwrapped_type_as_string(cpp_name_map),
         }
     }
@@ -52,6 +53,7 @@ impl TypeConversionPolicy {
                     cpp_name_map.type_to_cpp(ty)?
                 ))
             }
+            CppConversionType::FromStrToStringView => Ok("std::string_view".into()),
             _ => self.unwrapped_type_as_string(cpp_name_map),
         }
     }
@@ -116,6 +118,13 @@ impl TypeConversionPolicy {
             }
             CppConversionType::IgnoredPlacementPtrParameter => None,
             CppConversionType::FromReferenceToPointer { .. } => Some(format!("&{var_name}")),
+            CppConversionType::FromStrToStringView => Some(format!(
+                "std::string_view({var_name}.data(), {var_name}.size())"
+            )),
+            // This checks that the characters are valid UTF-8.
+            CppConversionType::FromStringViewToStr => {
+                Some(format!("rust::Str({var_name}.data(), {var_name}.size())"))
+            }
         })
     }
 }
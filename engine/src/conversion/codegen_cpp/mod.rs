@@ -12,10 +12,11 @@ pub(crate) mod type_to_cpp;
 
 use crate::{
     conversion::analysis::fun::{function_wrapper::CppFunctionKind, FnAnalysis},
+    known_types::known_types,
     types::{make_ident, QualifiedName},
     CppCodegenOptions, CppFilePair,
 };
-use autocxx_parser::IncludeCppConfig;
+use autocxx_parser::{IncludeCppConfig, PodDerive};
 use indexmap::map::IndexMap as HashMap;
 use indexmap::set::IndexSet as HashSet;
 use itertools::Itertools;
@@ -24,14 +25,16 @@ use type_to_cpp::CppNameMap;
 
 use super::{
     analysis::{
+        container_elements::find_vector_element_types,
         fun::{
             function_wrapper::{CppFunction, CppFunctionBody},
             FnPhase, PodAndDepAnalysis,
         },
         pod::PodAnalysis,
     },
-    api::{Api, Provenance, SubclassName, TypeKind},
+    api::{Api, ConcreteContainer, Provenance, SubclassName, TypeKind},
     apivec::ApiVec,
+    utilities::type_helper_name,
     ConvertErrorFromCpp,
 };
 
@@ -125,9 +128,13 @@ impl<'a> CppCodeGenerator<'a> {
             cpp_codegen_options,
             cxxgen_header_name,
         };
+        let vector_element_types = find_vector_element_types(apis);
         // The 'filter' on the following line is designed to ensure we don't accidentally
         // end up out of sync with needs_cpp_codegen
-        gen.add_needs(apis.iter().filter(|api| api.needs_cpp_codegen()))?;
+        gen.add_needs(
+            apis.iter().filter(|api| api.needs_cpp_codegen()),
+            &vector_element_types,
+        )?;
         Ok(gen.generate())
     }
 
@@ -135,11 +142,42 @@ impl<'a> CppCodeGenerator<'a> {
     fn add_needs<'b>(
         &mut self,
         apis: impl Iterator<Item = &'a Api<FnPhase>>,
+        vector_element_types: &HashSet<QualifiedName>,
     ) -> Result<(), ConvertErrorFromCpp> {
         let mut constructors_by_subclass: HashMap<SubclassName, Vec<&CppFunction>> = HashMap::new();
         let mut methods_by_subclass: HashMap<SubclassName, Vec<SubclassFunction>> = HashMap::new();
         let mut deferred_apis = Vec::new();
         for api in apis {
+            if api.has_move_helpers() {
+                self.generate_move_into_shared(api.name());
+            }
+            if api.has_vector_builder(vector_element_types) {
+                let by_value = !matches!(
+                    api,
+                    Api::Struct {
+                        analysis: PodAndDepAnalysis {
+                            pod: PodAnalysis {
+                                kind: TypeKind::NonPod,
+                                ..
+                            },
+                            ..
+                        },
+                        ..
+                    }
+                );
+                self.generate_vector_builder(api.name(), by_value);
+            }
+            if let Api::Struct {
+                name,
+                analysis:
+                    PodAndDepAnalysis {
+                        iterable: Some(_), ..
+                    },
+                ..
+            } = api
+            {
+                self.generate_iterable_helpers(&name.name);
+            }
             match &api {
                 Api::StringConstructor { .. } => self.generate_string_constructor(),
                 Api::Function {
@@ -164,6 +202,7 @@ impl<'a> CppCodeGenerator<'a> {
                 Api::ConcreteType {
                     rs_definition,
                     cpp_definition,
+                    container,
                     ..
                 } => {
                     let effective_cpp_definition = match rs_definition {
@@ -173,7 +212,10 @@ impl<'a> CppCodeGenerator<'a> {
                         None => Cow::Borrowed(cpp_definition),
                     };
 
-                    self.generate_typedef(api.name(), &effective_cpp_definition)
+                    self.generate_typedef(api.name(), &effective_cpp_definition);
+                    if let Some(container) = container {
+                        self.generate_container_helpers(api.name(), container);
+                    }
                 }
                 Api::CType { typename, .. } => self.generate_ctype_typedef(typename),
                 Api::Subclass { .. } => deferred_apis.push(api),
@@ -202,7 +244,17 @@ impl<'a> CppCodeGenerator<'a> {
                     ..
                 } => {
                     self.generate_pod_assertion(name.qualified_cpp_name());
+                    if self
+                        .config
+                        .get_pod_derives(&name.name.to_cpp_name())
+                        .iter()
+                        .any(|derive| matches!(derive, PodDerive::Clone | PodDerive::Copy))
+                    {
+                        self.generate_trivially_copyable_assertion(name.qualified_cpp_name());
+                    }
                 }
+                // Only here for their vector builders or iterators.
+                Api::Struct { .. } | Api::Enum { .. } => {}
                 _ => panic!("Should have filtered on needs_cpp_codegen"),
             }
         }
@@ -229,36 +281,79 @@ impl<'a> CppCodeGenerator<'a> {
             let cpp_headers = self.collect_headers(|additional_need| &additional_need.cpp_headers);
             let type_definitions = self.concat_additional_items(|x| x.type_definition.as_ref());
             let declarations = self.concat_additional_items(|x| x.declaration.as_ref());
+            let standard_check = self.cpp_codegen_options.cpp_standard.cpp_check();
             let declarations = format!(
-                "#ifndef __AUTOCXXGEN_H__\n#define __AUTOCXXGEN_H__\n\n{}\n{}\n{}\n{}#endif // __AUTOCXXGEN_H__\n",
-                headers, self.inclusions, type_definitions, declarations
+                "#ifndef __AUTOCXXGEN_H__\n#define __AUTOCXXGEN_H__\n\n{}{}\n{}\n{}\n{}#endif // __AUTOCXXGEN_H__\n",
+                standard_check, headers, self.inclusions, type_definitions, declarations
             );
             log::info!("Additional C++ decls:\n{}", declarations);
             let header_name = self
                 .cpp_codegen_options
                 .autocxxgen_header_namer
                 .name_header(self.config.get_mod_name().to_string());
-            let implementation = if self
+            let mut implementations = if self
                 .additional_functions
                 .iter()
                 .any(|x| x.definition.is_some())
             {
-                let definitions = self.concat_additional_items(|x| x.definition.as_ref());
-                let definitions =
-                    format!("#include \"{header_name}\"\n{cpp_headers}\n{definitions}");
-                log::info!("Additional C++ defs:\n{}", definitions);
-                Some(definitions.into_bytes())
+                self.shard_definitions()
+                    .into_iter()
+                    .map(|definitions| {
+                        let definitions =
+                            format!("#include \"{header_name}\"\n{cpp_headers}\n{definitions}");
+                        log::info!("Additional C++ defs:\n{}", definitions);
+                        definitions.into_bytes()
+                    })
+                    .collect()
             } else {
-                None
-            };
+                Vec::new()
+            }
+            .into_iter();
             Some(CppFilePair {
                 header: declarations.into_bytes(),
-                implementation,
+                implementation: implementations.next(),
+                extra_implementations: implementations.collect(),
                 header_name,
             })
         }
     }
 
+    /// Whether wrapper functions should be defined in implementation files
+    /// rather than inline in the header.
+    fn sharding(&self) -> bool {
+        self.cpp_codegen_options.cpp_file_shards.is_some()
+    }
+
+    /// Distribute the definitions between the requested number of
+    /// implementation files, round-robin in the order in which we generated
+    /// them, so that the output is deterministic. We never produce more
+    /// files than there are definitions, so no file is empty.
+    fn shard_definitions(&self) -> Vec<String> {
+        let definitions: Vec<_> = self
+            .additional_functions
+            .iter()
+            .filter_map(|x| x.definition.as_ref())
+            .collect();
+        let shard_count = self
+            .cpp_codegen_options
+            .cpp_file_shards
+            .unwrap_or(1)
+            .min(definitions.len())
+            .max(1);
+        let mut shards = vec![Vec::new(); shard_count];
+        for (i, definition) in definitions.into_iter().enumerate() {
+            shards[i % shard_count].push(definition);
+        }
+        shards
+            .into_iter()
+            .map(|definitions| {
+                let mut s = definitions.into_iter().join("\n");
+                s.push('\n');
+                s
+            })
+            .collect()
+    }
+
     fn collect_headers<F>(&self, filter: F) -> String
     where
         F: Fn(&ExtraCpp) -> &[Header],
@@ -304,6 +399,17 @@ impl<'a> CppCodeGenerator<'a> {
         })
     }
 
+    /// `pod_derive!(Clone)` and `pod_derive!(Copy)` copy the object bitwise,
+    /// which is only right if C++ would do the same.
+    fn generate_trivially_copyable_assertion(&mut self, name: String) {
+        let declaration = Some(format!("static_assert(std::is_trivially_copyable<{name}>::value, \"type {name} should be trivially copyable to be used with pod_derive!(Clone) or pod_derive!(Copy) in autocxx\");"));
+        self.additional_functions.push(ExtraCpp {
+            declaration,
+            headers: vec![Header::System("type_traits")],
+            ..Default::default()
+        })
+    }
+
     fn generate_string_constructor(&mut self) {
         let makestring_name = self.config.get_makestring_name();
         let declaration = Some(format!("inline std::unique_ptr<std::string> {makestring_name}(::rust::Str str) {{ return std::make_unique<std::string>(std::string(str)); }}"));
@@ -319,12 +425,14 @@ impl<'a> CppCodeGenerator<'a> {
     }
 
     fn generate_cpp_function(&mut self, details: &CppFunction) -> Result<(), ConvertErrorFromCpp> {
+        // If we're sharding, we declare the wrapper in the header but
+        // define it out-of-line, exactly as if it were needed by Rust.
         self.additional_functions
             .push(self.generate_cpp_function_inner(
                 details,
                 false,
                 ConversionDirection::RustCallsCpp,
-                false,
+                self.sharding(),
                 None,
             )?);
         Ok(())
@@ -556,6 +664,11 @@ impl<'a> CppCodeGenerator<'a> {
                 "".to_string(),
                 true,
             ),
+            CppFunctionBody::MakeShared(ty) => (
+                format!("std::make_shared<{}>({arg_list})", self.namespaced_name(ty)),
+                "".to_string(),
+                false,
+            ),
         };
         if let Some(ret) = &details.return_conversion {
             let call_itself = match conversion_direction {
@@ -626,7 +739,36 @@ impl<'a> CppCodeGenerator<'a> {
 
     fn generate_ctype_typedef(&mut self, tn: &QualifiedName) {
         let cpp_name = tn.to_cpp_name();
-        self.generate_typedef(tn, &cpp_name)
+        self.generate_typedef(tn, &cpp_name);
+        if !self.config.exclude_utilities() {
+            if let Some(encoding) = known_types().wide_string_encoding(tn) {
+                self.generate_wide_string_helpers(tn, encoding.code_unit_bits())
+            }
+        }
+    }
+
+    /// Conversions between a wide string and a sequence of code units,
+    /// which Rust then converts to and from UTF-8.
+    fn generate_wide_string_helpers(&mut self, tn: &QualifiedName, code_unit_bits: u8) {
+        let string = tn.get_final_item();
+        let unit = format!("uint{code_unit_bits}_t");
+        let to_units = type_helper_name(self.config, tn, "to_units");
+        let from_units = type_helper_name(self.config, tn, "from_units");
+        let declaration = [
+            format!("inline ::rust::Vec<{unit}> {to_units}(const {string}& str) {{ ::rust::Vec<{unit}> units; units.reserve(str.size()); for (auto c : str) {{ units.push_back(static_cast<{unit}>(c)); }} return units; }}"),
+            format!("inline std::unique_ptr<{string}> {from_units}(::rust::Slice<const {unit}> units) {{ auto str = std::make_unique<{string}>(); str->reserve(units.size()); for (auto unit : units) {{ str->push_back(static_cast<{string}::value_type>(unit)); }} return str; }}"),
+        ]
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declaration),
+            headers: vec![
+                Header::System("memory"),
+                Header::System("string"),
+                Header::System("cstdint"),
+                Header::CxxH,
+            ],
+            ..Default::default()
+        })
     }
 
     fn generate_typedef(&mut self, tn: &QualifiedName, definition: &str) {
@@ -637,6 +779,270 @@ impl<'a> CppCodeGenerator<'a> {
         })
     }
 
+    fn generate_container_helpers(&mut self, name: &QualifiedName, container: &ConcreteContainer) {
+        match container {
+            ConcreteContainer::Map { insertable, .. } => {
+                self.generate_map_helpers(name, *insertable)
+            }
+            ConcreteContainer::Pair { pod, .. } => self.generate_tuple_helpers(name, 2, *pod),
+            ConcreteContainer::Tuple { elements, pod } => {
+                self.generate_tuple_helpers(name, elements.len(), *pod)
+            }
+            ConcreteContainer::Variant { alternatives } => {
+                self.generate_variant_helpers(name, alternatives.len())
+            }
+            ConcreteContainer::Vector {
+                element_by_value, ..
+            } => self.generate_vector_helpers(name, *element_by_value),
+            ConcreteContainer::Array { pod, .. } => self.generate_array_helpers(name, *pod),
+            ConcreteContainer::UniquePtr { .. } => self.generate_unique_ptr_helpers(name),
+        }
+    }
+
+    /// Access to the object(s) owned by a `std::unique_ptr` which cxx
+    /// can't represent as a `UniquePtr`. Dropping it is taken care of by
+    /// its C++ destructor, which uses the right deleter.
+    fn generate_unique_ptr_helpers(&mut self, name: &QualifiedName) {
+        let ptr = name.get_final_item();
+        let get = type_helper_name(self.config, name, "get");
+        let get_mut = type_helper_name(self.config, name, "get_mut");
+        // For an array, these give the first element. Rust does the
+        // indexing, because only the caller knows how many elements there are.
+        let declaration = [
+            format!(
+                "inline const {ptr}::element_type* {get}(const {ptr}& ptr) {{ return ptr.get(); }}"
+            ),
+            format!("inline {ptr}::element_type* {get_mut}({ptr}& ptr) {{ return ptr.get(); }}"),
+        ]
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declaration),
+            headers: vec![Header::System("memory")],
+            ..Default::default()
+        })
+    }
+
+    /// Lets Rust build up a `std::vector` of this type. POD types are passed
+    /// in by value; anything else is moved out of a Rust `UniquePtr`.
+    fn generate_vector_builder(&mut self, name: &QualifiedName, by_value: bool) {
+        let ty = self.namespaced_name(name);
+        let new_vector = type_helper_name(self.config, name, "vector_new");
+        let reserve = type_helper_name(self.config, name, "vector_reserve");
+        let push = type_helper_name(self.config, name, "vector_push");
+        let value = if by_value {
+            ty.clone()
+        } else {
+            format!("{ty}&")
+        };
+        let declaration = [
+            format!("inline std::unique_ptr<std::vector<{ty}>> {new_vector}() {{ return std::make_unique<std::vector<{ty}>>(); }}"),
+            format!("inline void {reserve}(std::vector<{ty}>& vec, size_t additional) {{ vec.reserve(vec.size() + additional); }}"),
+            format!("inline void {push}(std::vector<{ty}>& vec, {value} value) {{ vec.push_back(std::move(value)); }}"),
+        ]
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declaration),
+            headers: vec![
+                Header::System("memory"),
+                Header::System("vector"),
+                Header::System("utility"),
+                Header::System("cstddef"),
+            ],
+            ..Default::default()
+        })
+    }
+
+    /// Moves an existing object into a new `std::shared_ptr`, which
+    /// underpins `autocxx::WithinSharedPtr`.
+    fn generate_move_into_shared(&mut self, name: &QualifiedName) {
+        let ty = self.namespaced_name(name);
+        let move_into_shared = type_helper_name(self.config, name, "move_into_shared");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(format!(
+                "inline std::shared_ptr<{ty}> {move_into_shared}({ty}& value) {{ return std::make_shared<{ty}>(std::move(value)); }}"
+            )),
+            headers: vec![Header::System("memory"), Header::System("utility")],
+            ..Default::default()
+        })
+    }
+
+    /// Iteration over a type with `begin()` and `end()` methods, using a
+    /// small struct holding a pair of its C++ iterators.
+    fn generate_iterable_helpers(&mut self, name: &QualifiedName) {
+        let ty = self.namespaced_name(name);
+        let helper = |helper: &str| type_helper_name(self.config, name, helper);
+        let iterator = helper("iterator");
+        let (iter, iter_get, iter_advance) =
+            (helper("iter"), helper("iter_get"), helper("iter_advance"));
+        let deref = format!("decltype(*std::declval<const {iterator}&>().pos)");
+        let element = format!("std::remove_reference<{deref}>::type");
+        let declaration = [
+            format!("static_assert(std::is_lvalue_reference<{deref}>::value, \"iterators of {ty} must give references to its elements, not proxies or values, to be used with iterable! in autocxx\");"),
+            format!("inline std::unique_ptr<{iterator}> {iter}(const {ty}& container) {{ return std::make_unique<{iterator}>({iterator} {{ container.begin(), container.end() }}); }}"),
+            format!("inline const {element}* {iter_get}(const {iterator}& iter) {{ return iter.pos == iter.end ? nullptr : &*iter.pos; }}"),
+            format!("inline void {iter_advance}({iterator}& iter) {{ ++iter.pos; }}"),
+        ]
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            type_definition: Some(format!(
+                "struct {iterator} {{ decltype(std::declval<const {ty}&>().begin()) pos; decltype(std::declval<const {ty}&>().end()) end; }};"
+            )),
+            declaration: Some(declaration),
+            headers: vec![
+                Header::System("memory"),
+                Header::System("type_traits"),
+                Header::System("utility"),
+            ],
+            ..Default::default()
+        })
+    }
+
+    /// Element access for vectors which cxx can't represent as a
+    /// `CxxVector`.
+    fn generate_vector_helpers(&mut self, name: &QualifiedName, element_by_value: bool) {
+        let vec = name.get_final_item();
+        let len = type_helper_name(self.config, name, "len");
+        let get = type_helper_name(self.config, name, "get");
+        let element = if element_by_value {
+            format!("{vec}::value_type")
+        } else {
+            format!("const {vec}::value_type&")
+        };
+        let declaration = [
+            format!("inline size_t {len}(const {vec}& vec) {{ return vec.size(); }}"),
+            format!(
+                "inline {element} {get}(const {vec}& vec, size_t index) {{ return vec[index]; }}"
+            ),
+        ]
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declaration),
+            headers: vec![Header::System("cstddef")],
+            ..Default::default()
+        })
+    }
+
+    /// Element access for a `std::array` which isn't represented as a Rust
+    /// array. Rust knows the length already. If the elements are POD, Rust
+    /// can also have them all as a slice.
+    fn generate_array_helpers(&mut self, name: &QualifiedName, pod: bool) {
+        let arr = name.get_final_item();
+        let get = type_helper_name(self.config, name, "get");
+        let get_mut = type_helper_name(self.config, name, "get_mut");
+        let mut declarations = vec![
+            format!("inline const {arr}::value_type& {get}(const {arr}& arr, size_t index) {{ return arr[index]; }}"),
+            format!("inline {arr}::value_type& {get_mut}({arr}& arr, size_t index) {{ return arr[index]; }}"),
+        ];
+        if pod {
+            let data = type_helper_name(self.config, name, "data");
+            let data_mut = type_helper_name(self.config, name, "data_mut");
+            declarations.extend([
+                format!("inline const {arr}::value_type* {data}(const {arr}& arr) {{ return arr.data(); }}"),
+                format!("inline {arr}::value_type* {data_mut}({arr}& arr) {{ return arr.data(); }}"),
+            ]);
+        }
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declarations.join("\n")),
+            headers: vec![Header::System("array"), Header::System("cstddef")],
+            ..Default::default()
+        })
+    }
+
+    /// Helpers to find which alternative a `std::variant` holds, and to
+    /// get at it.
+    fn generate_variant_helpers(&mut self, name: &QualifiedName, count: usize) {
+        let variant = name.get_final_item();
+        let index = type_helper_name(self.config, name, "index");
+        let declaration = std::iter::once(format!(
+            "inline size_t {index}(const {variant}& variant) {{ return variant.index(); }}"
+        ))
+        .chain((0..count).map(|idx| {
+            let get = type_helper_name(self.config, name, &format!("get{idx}"));
+            format!("inline const std::variant_alternative<{idx}, {variant}>::type* {get}(const {variant}& variant) {{ return std::get_if<{idx}>(&variant); }}")
+        }))
+        .collect::<Vec<_>>()
+        .join("\n");
+        self.additional_functions.push(ExtraCpp {
+            declaration: Some(declaration),
+            headers: vec![Header::System("variant"), Header::System("cstddef")],
+            ..Default::default()
+        })
+    }
+
+    /// Accessors for each element of a `std::pair` or `std::tuple`. If all
+    /// the elements are POD, also a plain struct holding a copy of each, so
+    /// that Rust can have the whole thing by value.
+    fn generate_tuple_helpers(&mut self, name: &QualifiedName, count: usize, pod: bool) {
+        let tuple = name.get_final_item();
+        let mut declarations: Vec<_> = (0..count)
+            .map(|idx| {
+                let get = type_helper_name(self.config, name, &format!("get{idx}"));
+                format!("inline const std::tuple_element<{idx}, {tuple}>::type& {get}(const {tuple}& tuple) {{ return std::get<{idx}>(tuple); }}")
+            })
+            .collect();
+        let mut type_definition = None;
+        if pod {
+            let pod_struct = type_helper_name(self.config, name, "pod");
+            let to_pod = type_helper_name(self.config, name, "to_pod");
+            let fields = (0..count)
+                .map(|idx| format!("std::tuple_element<{idx}, {tuple}>::type e{idx};"))
+                .join(" ");
+            let elements = (0..count)
+                .map(|idx| format!("std::get<{idx}>(tuple)"))
+                .join(", ");
+            type_definition = Some(format!("struct {pod_struct} {{ {fields} }};"));
+            declarations.push(format!("inline {pod_struct} {to_pod}(const {tuple}& tuple) {{ return {pod_struct} {{ {elements} }}; }}"));
+        }
+        self.additional_functions.push(ExtraCpp {
+            type_definition,
+            declaration: Some(declarations.join("\n")),
+            headers: vec![Header::System("tuple"), Header::System("utility")],
+            ..Default::default()
+        })
+    }
+
+    /// Helper functions for `std::map` and `std::unordered_map`. Iteration
+    /// uses a small struct holding a pair of C++ iterators. `insert`
+    /// copies the key and value, so we only generate it if that's possible.
+    fn generate_map_helpers(&mut self, name: &QualifiedName, insertable: bool) {
+        let map = name.get_final_item();
+        let helper = |helper: &str| type_helper_name(self.config, name, helper);
+        let iterator = helper("iterator");
+        let (len, get, insert, remove) = (
+            helper("len"),
+            helper("get"),
+            helper("insert"),
+            helper("remove"),
+        );
+        let (iter, iter_key, iter_value, iter_advance) = (
+            helper("iter"),
+            helper("iter_key"),
+            helper("iter_value"),
+            helper("iter_advance"),
+        );
+        let mut declarations = vec![
+            format!("inline size_t {len}(const {map}& map) {{ return map.size(); }}"),
+            format!("inline const {map}::mapped_type* {get}(const {map}& map, const {map}::key_type& key) {{ auto it = map.find(key); return it == map.end() ? nullptr : &it->second; }}"),
+            format!("inline bool {remove}({map}& map, const {map}::key_type& key) {{ return map.erase(key) > 0; }}"),
+            format!("inline std::unique_ptr<{iterator}> {iter}(const {map}& map) {{ return std::make_unique<{iterator}>({iterator} {{ map.begin(), map.end() }}); }}"),
+            format!("inline const {map}::key_type* {iter_key}(const {iterator}& iter) {{ return iter.pos == iter.end ? nullptr : &iter.pos->first; }}"),
+            format!("inline const {map}::mapped_type* {iter_value}(const {iterator}& iter) {{ return iter.pos == iter.end ? nullptr : &iter.pos->second; }}"),
+            format!("inline void {iter_advance}({iterator}& iter) {{ ++iter.pos; }}"),
+        ];
+        if insertable {
+            declarations.push(format!("inline bool {insert}({map}& map, const {map}::key_type& key, const {map}::mapped_type& value) {{ auto result = map.insert({{key, value}}); if (!result.second) {{ result.first->second = value; }} return result.second; }}"));
+        }
+        let declaration = declarations.join("\n");
+        self.additional_functions.push(ExtraCpp {
+            type_definition: Some(format!(
+                "struct {iterator} {{ {map}::const_iterator pos; {map}::const_iterator end; }};"
+            )),
+            declaration: Some(declaration),
+            headers: vec![Header::System("memory"), Header::System("cstddef")],
+            ..Default::default()
+        })
+    }
+
     fn generate_subclass(
         &mut self,
         superclass: &QualifiedName,
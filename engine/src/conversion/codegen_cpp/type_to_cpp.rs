@@ -14,7 +14,7 @@ use indexmap::map::IndexMap as HashMap;
 use itertools::Itertools;
 use quote::ToTokens;
 use std::iter::once;
-use syn::{Token, Type};
+use syn::{Expr, ExprLit, Lit, Token, Type, TypeArray};
 
 /// Map from QualifiedName to original C++ name. Original C++ name does not
 /// include the namespace; this can be assumed to be the same as the namespace
@@ -67,6 +67,15 @@ impl CppNameMap {
         }
     }
 
+    /// bindgen gives us `[T; 0]` for an array of unknown bound, `T[]`.
+    fn array_to_cpp(&self, arr: &TypeArray) -> Result<String, ConvertErrorFromCpp> {
+        let elem = self.type_to_cpp(&arr.elem)?;
+        match array_len(arr)? {
+            0 => Ok(format!("{elem}[]")),
+            len => Ok(format!("{elem}[{len}]")),
+        }
+    }
+
     /// Convert a type to its C++ spelling.
     pub(crate) fn type_to_cpp(&self, ty: &Type) -> Result<String, ConvertErrorFromCpp> {
         match ty {
@@ -92,7 +101,18 @@ impl CppNameMap {
                             .args
                             .iter()
                             .map(|x| match x {
+                                // Only as template arguments, e.g.
+                                // std::unique_ptr<T[]>, can we spell an array
+                                // type without a declarator.
+                                syn::GenericArgument::Type(Type::Array(arr)) => {
+                                    self.array_to_cpp(arr)
+                                }
                                 syn::GenericArgument::Type(gat) => self.type_to_cpp(gat),
+                                // The length of a std::array.
+                                syn::GenericArgument::Const(Expr::Lit(ExprLit {
+                                    lit: Lit::Int(len),
+                                    ..
+                                })) => Ok(len.base10_digits().to_string()),
                                 _ => Ok("".to_string()),
                             })
                             .collect();
@@ -118,8 +138,14 @@ impl CppNameMap {
                 get_mut_string(&typp.mutability),
                 self.type_to_cpp(typp.elem.as_ref())?
             )),
-            Type::Array(_)
-            | Type::BareFn(_)
+            // Anywhere else, an array must have been a std::array, since
+            // C arrays can't be passed by value.
+            Type::Array(arr) => Ok(format!(
+                "std::array<{}, {}>",
+                self.type_to_cpp(&arr.elem)?,
+                array_len(arr)?
+            )),
+            Type::BareFn(_)
             | Type::Group(_)
             | Type::ImplTrait(_)
             | Type::Infer(_)
@@ -151,3 +177,16 @@ fn get_mut_string(mutability: &Option<Token![mut]>) -> &'static str {
         Some(_) => "",
     }
 }
+
+fn array_len(arr: &TypeArray) -> Result<usize, ConvertErrorFromCpp> {
+    match &arr.len {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(len), ..
+        }) => len
+            .base10_parse::<usize>()
+            .map_err(|_| ConvertErrorFromCpp::UnsupportedType(arr.to_token_stream().to_string())),
+        _ => Err(ConvertErrorFromCpp::UnsupportedType(
+            arr.to_token_stream().to_string(),
+        )),
+    }
+}
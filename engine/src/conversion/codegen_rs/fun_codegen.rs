@@ -106,6 +106,7 @@ pub(super) fn gen_function(
     let vis = analysis.vis;
     let kind = analysis.kind;
     let doc_attrs = minisynize_vec(fun.doc_attrs);
+    let lint_attrs = minisynize_vec(fun.lint_attrs);
 
     let mut cpp_name_attr = Vec::new();
     let mut impl_entry = None;
@@ -122,6 +123,7 @@ pub(super) fn gen_function(
         unsafety: &analysis.requires_unsafe,
         always_unsafe_due_to_trait_definition,
         doc_attrs: &doc_attrs,
+        lint_attrs: &lint_attrs,
         non_pod_types,
         ret_type: &ret_type,
         ret_conversion: &ret_conversion,
@@ -208,12 +210,20 @@ pub(super) fn gen_function(
             ))
             .unwrap()
     };
+    // If there's a Rust wrapper, that's what users call, so it gets any
+    // #[must_use] or #[deprecated]; otherwise cxx passes them through.
+    let bridge_lint_attrs = if analysis.rust_wrapper_needed {
+        Vec::new()
+    } else {
+        lint_attrs.clone()
+    };
     // At last, actually generate the cxx::bridge entry.
     let bridge_unsafety = analysis.requires_unsafe.bridge_token();
     let extern_c_mod_item = ForeignItem::Fn(parse_quote!(
         #(#namespace_attr)*
         #(#cpp_name_attr)*
         #(#doc_attrs)*
+        #(#bridge_lint_attrs)*
         #vis #bridge_unsafety fn #cxxbridge_name #lifetime_tokens ( #params ) #ret_type;
     ));
     RsCodegenResult {
@@ -237,6 +247,7 @@ struct FnGenerator<'a> {
     unsafety: &'a UnsafetyNeeded,
     always_unsafe_due_to_trait_definition: bool,
     doc_attrs: &'a Vec<Attribute>,
+    lint_attrs: &'a Vec<Attribute>,
     non_pod_types: &'a HashSet<QualifiedName>,
     reference_wrappers: bool,
 }
@@ -394,6 +405,7 @@ impl<'a> FnGenerator<'a> {
         let rust_name = make_ident(self.rust_name);
         let unsafety = self.unsafety.wrapper_token();
         let doc_attrs = self.doc_attrs;
+        let lint_attrs = self.lint_attrs;
         let receiver_pointerness = self
             .param_details
             .iter()
@@ -422,6 +434,7 @@ impl<'a> FnGenerator<'a> {
         Box::new(ImplBlockDetails {
             item: ImplItem::Fn(parse_quote! {
                 #(#doc_attrs)*
+                #(#lint_attrs)*
                 pub #unsafety fn #rust_name #lifetime_tokens ( #wrapper_params ) #ret_type {
                     #call_body
                 }
@@ -431,6 +444,7 @@ impl<'a> FnGenerator<'a> {
     }
 
     /// Generate an 'impl Trait for Type { methods-go-here }' in its entrety.
+    /// Lint attributes aren't permitted on trait impls, so are omitted.
     fn generate_trait_impl(&self, details: &TraitMethodDetails) -> Box<TraitImplBlockDetails> {
         let (lifetime_tokens, wrapper_params, ret_type, call_body) =
             self.common_parts(details.avoid_self, &details.parameter_reordering, None);
@@ -458,11 +472,13 @@ impl<'a> FnGenerator<'a> {
             self.common_parts(true, &None, Some(ret_type));
         let rust_name = make_ident(self.rust_name);
         let doc_attrs = self.doc_attrs;
+        let lint_attrs = self.lint_attrs;
         let unsafety = self.unsafety.wrapper_token();
         let ty = impl_block_type_name.get_final_ident();
         let ty = parse_quote! { #ty };
         let stuff = quote! {
                 #(#doc_attrs)*
+                #(#lint_attrs)*
                 pub #unsafety fn #rust_name #lifetime_tokens ( #wrapper_params ) #ret_type {
                     #call_body
                 }
@@ -479,9 +495,11 @@ impl<'a> FnGenerator<'a> {
             self.common_parts(false, &None, None);
         let rust_name = make_ident(self.rust_name);
         let doc_attrs = self.doc_attrs;
+        let lint_attrs = self.lint_attrs;
         let unsafety = self.unsafety.wrapper_token();
         Item::Fn(parse_quote! {
             #(#doc_attrs)*
+            #(#lint_attrs)*
             pub #unsafety fn #rust_name #lifetime_tokens ( #wrapper_params ) #ret_type {
                 #call_body
             }
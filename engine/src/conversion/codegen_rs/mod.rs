@@ -17,21 +17,24 @@ pub(crate) mod unqualify;
 use indexmap::map::IndexMap as HashMap;
 use indexmap::set::IndexSet as HashSet;
 
-use autocxx_parser::{ExternCppType, IncludeCppConfig, RustFun, UnsafePolicy};
+use autocxx_parser::{
+    ExternCppType, IncludeCppConfig, NamingPolicy, PodDerive, RustFun, UnsafePolicy,
+};
 
 use itertools::Itertools;
 use proc_macro2::{Span, TokenStream};
 use syn::{
     parse_quote, punctuated::Punctuated, token::Comma, Attribute, Expr, FnArg, ForeignItem,
-    ForeignItemFn, Ident, ImplItem, Item, ItemForeignMod, ItemMod, Lifetime, TraitItem, Type,
-    TypePath,
+    ForeignItemFn, Ident, ImplItem, Item, ItemEnum, ItemForeignMod, ItemMod, ItemStruct, Lifetime,
+    TraitItem, Type, TypePath,
 };
 
 use crate::{
     conversion::codegen_rs::{
-        non_pod_struct::{make_non_pod, new_non_pod_struct},
-        unqualify::{unqualify_params, unqualify_ret_type},
+        non_pod_struct::{make_non_pod, make_thread_safety_impls, new_non_pod_struct},
+        unqualify::{unqualify_params, unqualify_ret_type, unqualify_type},
     },
+    known_types::{known_types, WideStringEncoding},
     minisyn::minisynize_punctuated,
     types::{make_ident, Namespace, QualifiedName},
 };
@@ -44,12 +47,15 @@ use self::{
 
 use super::{
     analysis::{
-        fun::{FnPhase, PodAndDepAnalysis, ReceiverMutability},
+        container_elements::find_vector_element_types,
+        fun::{FnAnalysis, FnKind, FnPhase, PodAndDepAnalysis, ReceiverMutability},
         pod::PodAnalysis,
     },
-    api::{AnalysisPhase, Api, SubclassName, TypeKind, TypedefKind},
+    api::{AnalysisPhase, Api, ConcreteContainer, SubclassName, TypeKind, TypedefKind},
     convert_error::ErrorContextType,
-    doc_attr::get_doc_attrs,
+    doc_attr::{get_doc_attrs, link_see_also_references, replace_doc_attrs, DocLinkTargets},
+    naming_policy::to_upper_camel_case,
+    utilities::type_helper_name,
 };
 use super::{
     api::{Layout, Provenance, RustSubclassFnDetails, SuperclassMethod, TraitImplSignature},
@@ -57,7 +63,7 @@ use super::{
     codegen_cpp::type_to_cpp::CppNameMap,
 };
 use super::{convert_error::ErrorContext, ConvertErrorFromCpp};
-use quote::quote;
+use quote::{quote, ToTokens};
 
 #[derive(Clone, Hash, PartialEq, Eq)]
 struct ImplBlockKey {
@@ -86,6 +92,9 @@ enum Use {
     UsedFromCxxBridgeWithAlias(Ident),
     /// 'use' directive points to bindgen
     UsedFromBindgen,
+    /// 'use' directive points to a type in bindgen, which may be given
+    /// a different name according to the naming policy
+    TypeFromBindgen,
     /// 'use' a specific name from bindgen.
     SpecificNameFromBindgen(Ident),
     /// Some kind of custom item
@@ -94,6 +103,18 @@ enum Use {
 
 fn get_string_items() -> Vec<Item> {
     [
+        Item::Trait(parse_quote! {
+            /// Conversion of C++ wide strings (e.g. `std::wstring`) to Rust
+            /// strings.
+            pub trait ToRustString {
+                /// Converts to a Rust string, replacing any invalid
+                /// characters with U+FFFD.
+                fn to_string_lossy(&self) -> String;
+                /// Converts to a Rust string, failing if there are any
+                /// invalid characters.
+                fn to_rust_string(&self) -> Result<String, autocxx::WideStringError>;
+            }
+        }),
         Item::Trait(parse_quote! {
             pub trait ToCppString {
                 fn into_cpp(self) -> cxx::UniquePtr<cxx::CxxString>;
@@ -130,6 +151,21 @@ fn get_string_items() -> Vec<Item> {
                 }
             }
         }),
+        Item::Fn(parse_quote! {
+            /// Builds a C++ string by concatenating the pieces yielded by
+            /// an iterator.
+            pub fn collect_string<I>(iter: I) -> cxx::UniquePtr<cxx::CxxString>
+            where
+                I: IntoIterator,
+                I::Item: AsRef<str>,
+            {
+                let mut str_ = make_string("");
+                for piece in iter {
+                    str_.pin_mut().push_str(piece.as_ref());
+                }
+                str_
+            }
+        }),
     ]
     .to_vec()
 }
@@ -144,6 +180,8 @@ pub(crate) struct RsCodeGenerator<'a> {
     original_name_map: CppNameMap,
     config: &'a IncludeCppConfig,
     header_name: Option<String>,
+    /// Types for which we generate helpers to build a `CxxVector`.
+    vector_element_types: HashSet<QualifiedName>,
 }
 
 impl<'a> RsCodeGenerator<'a> {
@@ -163,6 +201,7 @@ impl<'a> RsCodeGenerator<'a> {
             original_name_map: CppNameMap::new_from_apis(&all_apis),
             config,
             header_name,
+            vector_element_types: find_vector_element_types(&all_apis),
         };
         c.rs_codegen(all_apis)
     }
@@ -175,6 +214,7 @@ impl<'a> RsCodeGenerator<'a> {
         let subclasses_with_a_single_trivial_constructor =
             find_trivially_constructed_subclasses(&all_apis);
         let non_pod_types = find_non_pod_types(&all_apis);
+        let doc_link_targets = find_doc_link_targets(&all_apis);
         // Now let's generate the Rust code.
         let (rs_codegen_results_and_namespaces, additional_cpp_needs): (Vec<_>, Vec<_>) = all_apis
             .into_iter()
@@ -193,7 +233,7 @@ impl<'a> RsCodeGenerator<'a> {
         // First, the hierarchy of mods containing lots of 'use' statements
         // which is the final API exposed as 'ffi'.
         let mut use_statements =
-            Self::generate_final_use_statements(&rs_codegen_results_and_namespaces);
+            self.generate_final_use_statements(&rs_codegen_results_and_namespaces);
         // And work out what we need for the bindgen mod.
         let bindgen_root_items =
             self.generate_final_bindgen_mods(&rs_codegen_results_and_namespaces);
@@ -267,6 +307,7 @@ impl<'a> RsCodeGenerator<'a> {
             use bindgen::root;
         }));
         all_items.append(&mut use_statements);
+        link_see_also_references(&mut all_items, &doc_link_targets);
         all_items
     }
 
@@ -318,18 +359,21 @@ impl<'a> RsCodeGenerator<'a> {
     /// Generate lots of 'use' statements to pull cxxbridge items into the output
     /// mod hierarchy according to C++ namespaces.
     fn generate_final_use_statements(
+        &self,
         input_items: &[(QualifiedName, RsCodegenResult)],
     ) -> Vec<Item> {
         let mut output_items = Vec::new();
         let ns_entries = NamespaceEntries::new(input_items);
-        Self::append_child_use_namespace(&ns_entries, &mut output_items);
+        self.append_child_use_namespace(&ns_entries, &mut output_items);
         output_items
     }
 
     fn append_child_use_namespace(
+        &self,
         ns_entries: &NamespaceEntries<(QualifiedName, RsCodegenResult)>,
         output_items: &mut Vec<Item>,
     ) {
+        let type_renames = self.find_type_renames(ns_entries);
         for (name, codegen) in ns_entries.entries() {
             output_items.extend(codegen.materializations.iter().map(|materialization| {
                 match materialization {
@@ -338,6 +382,17 @@ impl<'a> RsCodeGenerator<'a> {
                     }
                     Use::UsedFromCxxBridge => Self::generate_cxx_use_stmt(name, None),
                     Use::UsedFromBindgen => Self::generate_bindgen_use_stmt(name),
+                    Use::TypeFromBindgen => match type_renames.get(name) {
+                        Some(Ok(alias)) => Self::generate_bindgen_use_stmt_with_alias(name, alias),
+                        Some(Err(problem)) => {
+                            let mut use_stmt = Self::generate_bindgen_use_stmt(name);
+                            if let Item::Use(ref mut use_stmt) = use_stmt {
+                                use_stmt.attrs.push(parse_quote! { #[doc = #problem] });
+                            }
+                            use_stmt
+                        }
+                        None => Self::generate_bindgen_use_stmt(name),
+                    },
                     Use::SpecificNameFromBindgen(id) => {
                         let name = QualifiedName::new(name.get_namespace(), id.clone().into());
                         Self::generate_bindgen_use_stmt(&name)
@@ -355,7 +410,7 @@ impl<'a> RsCodeGenerator<'a> {
                 pub mod #child_id {
                 }
             );
-            Self::append_child_use_namespace(
+            self.append_child_use_namespace(
                 child_ns_entries,
                 &mut new_mod.content.as_mut().unwrap().1,
             );
@@ -363,6 +418,61 @@ impl<'a> RsCodeGenerator<'a> {
         }
     }
 
+    /// Works out new names for the types in a given namespace, either
+    /// because the user asked for one using `rename!` or because of
+    /// `naming!(rust)`. If the new name would clash with some other item,
+    /// we keep the C++ name and instead return an explanation for the
+    /// documentation.
+    fn find_type_renames(
+        &self,
+        ns_entries: &NamespaceEntries<(QualifiedName, RsCodegenResult)>,
+    ) -> HashMap<QualifiedName, Result<Ident, String>> {
+        let mut names_in_use: HashSet<String> = HashSet::new();
+        for (name, codegen) in ns_entries.entries() {
+            names_in_use.insert(name.get_final_item().to_string());
+            for materialization in &codegen.materializations {
+                match materialization {
+                    Use::UsedFromCxxBridgeWithAlias(id) | Use::SpecificNameFromBindgen(id) => {
+                        names_in_use.insert(id.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut renames = HashMap::new();
+        for (name, codegen) in ns_entries.entries() {
+            if !codegen
+                .materializations
+                .iter()
+                .any(|m| matches!(m, Use::TypeFromBindgen))
+            {
+                continue;
+            }
+            let new_name = match self
+                .config
+                .get_rename(&self.original_name_map.map(name), None)
+            {
+                Some(id) => id.to_string(),
+                None if self.config.naming_policy == NamingPolicy::Rust => {
+                    to_upper_camel_case(name.get_final_item())
+                }
+                None => continue,
+            };
+            if new_name == name.get_final_item() {
+                continue;
+            }
+            let rename = if names_in_use.insert(new_name.clone()) {
+                Ok(make_ident(new_name).into())
+            } else {
+                Err(format!(
+                    "autocxx couldn't rename this type to {new_name} because that name is already in use."
+                ))
+            };
+            renames.insert(name.clone(), rename);
+        }
+        renames
+    }
+
     fn append_uses_for_ns(&mut self, items: &mut Vec<Item>, ns: &Namespace) {
         let mut imports_from_super = vec!["cxxbridge"];
         if !self.config.exclude_utilities() {
@@ -506,7 +616,7 @@ impl<'a> RsCodeGenerator<'a> {
                     TypedefKind::Type(type_item) => Item::Type(type_item.into()),
                     TypedefKind::Use(use_item, _) => Item::Use(use_item.into()),
                 }],
-                materializations: vec![Use::UsedFromBindgen],
+                materializations: vec![Use::TypeFromBindgen],
                 ..Default::default()
             },
             Api::Struct {
@@ -518,49 +628,64 @@ impl<'a> RsCodeGenerator<'a> {
                                 is_generic, kind, ..
                             },
                         constructors,
+                        iterable,
                         ..
                     },
                 ..
             } => {
                 let doc_attrs = get_doc_attrs(&details.item.attrs);
+                let mut item: ItemStruct = details.item.into();
+                replace_doc_attrs(&mut item.attrs, &doc_attrs);
                 let layout = details.layout.clone();
-                self.generate_type(
+                let mut result = self.generate_type(
                     &name,
                     id,
                     kind,
                     constructors.move_constructor,
                     constructors.destructor,
-                    || Some((Item::Struct(details.item.into()), doc_attrs)),
+                    || Some((Item::Struct(item), doc_attrs)),
                     associated_methods,
                     layout,
                     is_generic,
-                )
+                );
+                if let Some(iterable) = iterable {
+                    self.add_iterable_helpers(&name, iterable.element.into(), &mut result);
+                }
+                result
             }
             Api::Enum { item, .. } => {
                 let doc_attrs = get_doc_attrs(&item.attrs);
+                let mut item: ItemEnum = item.into();
+                replace_doc_attrs(&mut item.attrs, &doc_attrs);
                 self.generate_type(
                     &name,
                     id,
                     TypeKind::Pod,
                     true,
                     true,
-                    || Some((Item::Enum(item.into()), doc_attrs)),
+                    || Some((Item::Enum(item), doc_attrs)),
                     associated_methods,
                     None,
                     false,
                 )
             }
-            Api::ConcreteType { .. } => self.generate_type(
-                &name,
-                id,
-                TypeKind::Abstract,
-                false, // assume for now that these types can't be kept in a Vector
-                true,  // assume for now that these types can be put in a smart pointer
-                || None,
-                associated_methods,
-                None,
-                false,
-            ),
+            Api::ConcreteType { container, .. } => {
+                let mut result = self.generate_type(
+                    &name,
+                    id,
+                    TypeKind::Abstract,
+                    false, // assume for now that these types can't be kept in a Vector
+                    true,  // assume for now that these types can be put in a smart pointer
+                    || None,
+                    associated_methods,
+                    None,
+                    false,
+                );
+                if let Some(container) = container {
+                    self.add_container_helpers(&name, &container, &mut result);
+                }
+                result
+            }
             Api::ForwardDeclaration { .. } | Api::OpaqueTypedef { .. } => self.generate_type(
                 &name,
                 id,
@@ -572,11 +697,14 @@ impl<'a> RsCodeGenerator<'a> {
                 None,
                 false,
             ),
-            Api::CType { .. } => RsCodegenResult {
-                extern_c_mod_items: vec![ForeignItem::Verbatim(quote! {
-                    type #id = autocxx::#id;
-                })],
-                ..Default::default()
+            Api::CType { typename, .. } => match known_types().wide_string_encoding(&typename) {
+                Some(encoding) => self.generate_wide_string(&typename, &id, encoding),
+                None => RsCodegenResult {
+                    extern_c_mod_items: vec![ForeignItem::Verbatim(quote! {
+                        type #id = autocxx::#id;
+                    })],
+                    ..Default::default()
+                },
             },
             Api::RustType { path, .. } => {
                 let id = path.get_final_ident();
@@ -884,7 +1012,7 @@ impl<'a> RsCodeGenerator<'a> {
         F: FnOnce() -> Option<(Item, Vec<Attribute>)>,
     {
         let mut bindgen_mod_items = Vec::new();
-        let mut materializations = vec![Use::UsedFromBindgen];
+        let mut materializations = vec![Use::TypeFromBindgen];
         Self::add_superclass_stuff_to_type(
             name,
             &mut bindgen_mod_items,
@@ -914,6 +1042,13 @@ impl<'a> RsCodeGenerator<'a> {
                 let mut item = orig_item
                     .expect("Instantiable types must provide instance")
                     .0;
+                match &mut item {
+                    Item::Struct(s) if matches!(type_kind, TypeKind::Pod) => {
+                        self.add_pod_derives(name, &mut s.attrs)
+                    }
+                    Item::Enum(e) => self.add_pod_derives(name, &mut e.attrs),
+                    _ => {}
+                }
                 if matches!(type_kind, TypeKind::NonPod) {
                     if let Item::Struct(ref mut s) = item {
                         // Retain generics and doc attrs.
@@ -934,7 +1069,8 @@ impl<'a> RsCodeGenerator<'a> {
                         ..Default::default()
                     }
                 } else {
-                    RsCodegenResult {
+                    bindgen_mod_items.extend(self.generate_thread_safety_impls(name, &id));
+                    let mut result = RsCodegenResult {
                         global_items: self.generate_extern_type_impl(type_kind, name),
                         bridge_items: create_impl_items(&id, movable, destroyable, self.config),
                         extern_c_mod_items: vec![
@@ -943,7 +1079,14 @@ impl<'a> RsCodeGenerator<'a> {
                         bindgen_mod_items,
                         materializations,
                         ..Default::default()
+                    };
+                    if movable && destroyable && !self.config.exclude_impls {
+                        self.add_make_shared(name, &id, &mut result);
+                        if self.vector_element_types.contains(name) {
+                            self.add_vector_builder(name, &id, type_kind, &mut result);
+                        }
                     }
+                    result
                 }
             }
             TypeKind::Abstract => {
@@ -954,6 +1097,7 @@ impl<'a> RsCodeGenerator<'a> {
                     // We MUST do this because otherwise cxx assumes this can be
                     // instantiated using UniquePtr etc.
                     bindgen_mod_items.push(Item::Use(parse_quote! { pub use cxxbridge::#id; }));
+                    bindgen_mod_items.extend(self.generate_thread_safety_impls(name, &id));
                     RsCodegenResult {
                         extern_c_mod_items: vec![
                             self.generate_cxxbridge_type(name, false, doc_attrs)
@@ -968,6 +1112,921 @@ impl<'a> RsCodeGenerator<'a> {
         }
     }
 
+    /// Add any derives requested using `pod_derive!`, skipping those which
+    /// bindgen has already generated.
+    fn add_pod_derives(&self, name: &QualifiedName, attrs: &mut Vec<Attribute>) {
+        let existing: HashSet<String> = attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("derive"))
+            .filter_map(|attr| {
+                attr.parse_args_with(Punctuated::<syn::Path, Comma>::parse_terminated)
+                    .ok()
+            })
+            .flatten()
+            .map(|path| path.to_token_stream().to_string())
+            .collect();
+        let derives: Vec<_> = self
+            .config
+            .get_pod_derives(&name.to_cpp_name())
+            .iter()
+            .filter(|derive| !existing.contains(&derive.to_token_stream().to_string()))
+            .collect();
+        if !derives.is_empty() {
+            attrs.push(parse_quote! {
+                #[derive(#(#derives),*)]
+            });
+        }
+    }
+
+    fn generate_thread_safety_impls(
+        &self,
+        name: &QualifiedName,
+        id: &crate::minisyn::Ident,
+    ) -> Vec<Item> {
+        make_thread_safety_impls(
+            &id.clone().into(),
+            self.config.get_thread_safety(&name.to_cpp_name()),
+        )
+    }
+
+    /// A wide string is an opaque type declared within the cxx::bridge,
+    /// plus (unless we're excluding utilities) conversions to and from
+    /// Rust strings.
+    fn generate_wide_string(
+        &self,
+        typename: &QualifiedName,
+        id: &crate::minisyn::Ident,
+        encoding: WideStringEncoding,
+    ) -> RsCodegenResult {
+        let mut result = RsCodegenResult {
+            extern_c_mod_items: vec![ForeignItem::Verbatim(quote! {
+                type #id;
+            })],
+            materializations: vec![Use::UsedFromCxxBridge],
+            ..Default::default()
+        };
+        if self.config.exclude_utilities() {
+            return result;
+        }
+        let to_units = make_ident(type_helper_name(self.config, typename, "to_units"));
+        let from_units = make_ident(type_helper_name(self.config, typename, "from_units"));
+        let unit = make_ident(format!("u{}", encoding.code_unit_bits()));
+        let marker = make_ident(encoding.marker());
+        let cpp_name = typename.to_cpp_name();
+        let make_fn = make_ident(format!("make_{}", cpp_name.trim_start_matches("std::")));
+        let make_fn_doc = format!("Creates a C++ `{cpp_name}` from a Rust string.");
+        result.extern_c_mod_items.extend([
+            parse_quote! {
+                pub fn #to_units(str_: &#id) -> Vec<#unit>;
+            },
+            parse_quote! {
+                pub fn #from_units(units: &[#unit]) -> UniquePtr<#id>;
+            },
+        ]);
+        result.global_items.extend([
+            Item::Impl(parse_quote! {
+                impl ToRustString for cxxbridge::#id {
+                    fn to_string_lossy(&self) -> String {
+                        <autocxx::#marker as autocxx::WideStringEncoding>::decode_lossy(
+                            &cxxbridge::#to_units(self),
+                        )
+                    }
+
+                    fn to_rust_string(&self) -> Result<String, autocxx::WideStringError> {
+                        <autocxx::#marker as autocxx::WideStringEncoding>::decode(
+                            &cxxbridge::#to_units(self),
+                        )
+                    }
+                }
+            }),
+            Item::Fn(parse_quote! {
+                #[doc = #make_fn_doc]
+                pub fn #make_fn(str_: &str) -> cxx::UniquePtr<cxxbridge::#id> {
+                    cxxbridge::#from_units(
+                        &<autocxx::#marker as autocxx::WideStringEncoding>::encode(str_),
+                    )
+                }
+            }),
+        ]);
+        result
+    }
+
+    /// Lets us build a `CxxVector` of this type from Rust, by implementing
+    /// `autocxx::VectorBuilder` using some C++ helpers. POD types are passed
+    /// by value and so can also be `collect`ed (from references, too, if
+    /// `pod_derive!` made them `Copy`); non-POD types are moved out of a
+    /// `UniquePtr`.
+    fn add_vector_builder(
+        &self,
+        name: &QualifiedName,
+        id: &crate::minisyn::Ident,
+        type_kind: TypeKind,
+        result: &mut RsCodegenResult,
+    ) {
+        let new_vector = make_ident(type_helper_name(self.config, name, "vector_new"));
+        let reserve = make_ident(type_helper_name(self.config, name, "vector_reserve"));
+        let push = make_ident(type_helper_name(self.config, name, "vector_push"));
+        let fulltypath = name.get_bindgen_path_idents();
+        let ty: Type = parse_quote! { #(#fulltypath)::* };
+        let new_vector_fn: ImplItem = parse_quote! {
+            fn new_vector() -> cxx::UniquePtr<cxx::CxxVector<Self>> {
+                cxxbridge::#new_vector()
+            }
+        };
+        let reserve_fn: ImplItem = parse_quote! {
+            fn reserve(vec: ::core::pin::Pin<&mut cxx::CxxVector<Self>>, additional: usize) {
+                cxxbridge::#reserve(vec, additional)
+            }
+        };
+        result.extern_c_mod_items.extend([
+            parse_quote! {
+                pub fn #new_vector() -> UniquePtr<CxxVector<#id>>;
+            },
+            parse_quote! {
+                pub fn #reserve(vec: Pin<&mut CxxVector<#id>>, additional: usize);
+            },
+        ]);
+        if matches!(type_kind, TypeKind::Pod) {
+            result.extern_c_mod_items.push(parse_quote! {
+                pub fn #push(vec: Pin<&mut CxxVector<#id>>, value: #id);
+            });
+            result.global_items.extend([
+                Item::Impl(parse_quote! {
+                    impl autocxx::VectorBuilder<#ty> for #ty {
+                        #new_vector_fn
+                        #reserve_fn
+                        fn push(vec: ::core::pin::Pin<&mut cxx::CxxVector<Self>>, item: #ty) {
+                            cxxbridge::#push(vec, item)
+                        }
+                    }
+                }),
+                Item::Impl(parse_quote! {
+                    impl ::core::iter::FromIterator<#ty> for cxx::UniquePtr<cxx::CxxVector<#ty>> {
+                        fn from_iter<I: IntoIterator<Item = #ty>>(iter: I) -> Self {
+                            autocxx::collect_vector(iter)
+                        }
+                    }
+                }),
+            ]);
+            let is_copy = self
+                .config
+                .get_pod_derives(&name.to_cpp_name())
+                .contains(&PodDerive::Copy);
+            if is_copy {
+                result.global_items.push(Item::Impl(parse_quote! {
+                    impl<'a> ::core::iter::FromIterator<&'a #ty> for cxx::UniquePtr<cxx::CxxVector<#ty>> {
+                        fn from_iter<I: IntoIterator<Item = &'a #ty>>(iter: I) -> Self {
+                            autocxx::collect_vector(iter)
+                        }
+                    }
+                }));
+            }
+        } else {
+            result.extern_c_mod_items.extend([parse_quote! {
+                pub fn #push(vec: Pin<&mut CxxVector<#id>>, value: Pin<&mut #id>);
+            }]);
+            result.global_items.push(Item::Impl(parse_quote! {
+                impl autocxx::VectorBuilder<cxx::UniquePtr<#ty>> for #ty {
+                    #new_vector_fn
+                    #reserve_fn
+                    fn push(vec: ::core::pin::Pin<&mut cxx::CxxVector<Self>>, mut item: cxx::UniquePtr<#ty>) {
+                        cxxbridge::#push(vec, item.pin_mut())
+                    }
+                }
+            }));
+        }
+    }
+
+    /// Implements `autocxx::MakeShared`, which underpins
+    /// `autocxx::WithinSharedPtr`, using a C++ `std::make_shared` wrapper
+    /// which moves from an existing object.
+    fn add_make_shared(
+        &self,
+        name: &QualifiedName,
+        id: &crate::minisyn::Ident,
+        result: &mut RsCodegenResult,
+    ) {
+        let move_into_shared = make_ident(type_helper_name(self.config, name, "move_into_shared"));
+        let fulltypath = name.get_bindgen_path_idents();
+        result.extern_c_mod_items.push(parse_quote! {
+            pub fn #move_into_shared(value: Pin<&mut #id>) -> SharedPtr<#id>;
+        });
+        result.global_items.push(Item::Impl(parse_quote! {
+            impl autocxx::MakeShared for #(#fulltypath)::* {
+                fn make_shared(obj: ::core::pin::Pin<&mut Self>) -> cxx::SharedPtr<Self> {
+                    cxxbridge::#move_into_shared(obj)
+                }
+            }
+        }));
+    }
+
+    /// Rust bindings for the C++ helpers generated for well-known
+    /// containers by the C++ code generator.
+    fn add_container_helpers(
+        &self,
+        name: &QualifiedName,
+        container: &ConcreteContainer,
+        result: &mut RsCodegenResult,
+    ) {
+        match container {
+            ConcreteContainer::Map {
+                key,
+                value,
+                insertable,
+            } => self.add_map_helpers(
+                name,
+                key.clone().into(),
+                value.clone().into(),
+                *insertable,
+                result,
+            ),
+            ConcreteContainer::Pair { first, second, pod } => self.add_tuple_helpers(
+                name,
+                vec![
+                    (make_ident("first"), first.clone().into()),
+                    (make_ident("second"), second.clone().into()),
+                ],
+                *pod,
+                result,
+            ),
+            ConcreteContainer::Tuple { elements, pod } => self.add_tuple_helpers(
+                name,
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, ty)| (make_ident(format!("get{idx}")), ty.clone().into()))
+                    .collect(),
+                *pod,
+                result,
+            ),
+            ConcreteContainer::Variant { alternatives } => self.add_variant_helpers(
+                name,
+                alternatives.iter().cloned().map(Type::from).collect(),
+                result,
+            ),
+            ConcreteContainer::Vector {
+                element,
+                element_by_value,
+            } => self.add_vector_helpers(name, element.clone().into(), *element_by_value, result),
+            ConcreteContainer::Array { element, len, pod } => {
+                self.add_array_helpers(name, element.clone().into(), *len, *pod, result)
+            }
+            ConcreteContainer::UniquePtr { element, array } => {
+                self.add_unique_ptr_helpers(name, element.clone().into(), *array, result)
+            }
+        }
+    }
+
+    /// Access to the contents of a `std::unique_ptr` which cxx can't
+    /// represent as a `UniquePtr`.
+    fn add_unique_ptr_helpers(
+        &self,
+        name: &QualifiedName,
+        element: Type,
+        array: bool,
+        result: &mut RsCodegenResult,
+    ) {
+        let ptr = name.get_final_ident();
+        let get = make_ident(type_helper_name(self.config, name, "get"));
+        let get_mut = make_ident(type_helper_name(self.config, name, "get_mut"));
+        let bridge_element = unqualify_type(element.clone());
+        result.extern_c_mod_items.extend([
+            parse_quote! {
+                pub fn #get(ptr: &#ptr) -> *const #bridge_element;
+            },
+            parse_quote! {
+                pub fn #get_mut(ptr: Pin<&mut #ptr>) -> *mut #bridge_element;
+            },
+        ]);
+        if array {
+            result.bindgen_mod_items.push(Item::Impl(parse_quote! {
+                impl #ptr {
+                    /// Returns `true` if this doesn't own an array.
+                    pub fn is_null(&self) -> bool {
+                        cxxbridge::#get(self).is_null()
+                    }
+
+                    /// Returns the first `len` elements of the array as a
+                    /// slice, or an empty slice if this doesn't own an array.
+                    ///
+                    /// # Safety
+                    ///
+                    /// C++ doesn't record how many elements there are, so
+                    /// the caller must ensure that the array has at least
+                    /// `len` elements, all of which have been initialized.
+                    pub unsafe fn as_slice(&self, len: usize) -> &[#element] {
+                        let data = cxxbridge::#get(self);
+                        if data.is_null() {
+                            &[]
+                        } else {
+                            ::core::slice::from_raw_parts(data, len)
+                        }
+                    }
+
+                    /// Returns a reference to the element at the given index.
+                    ///
+                    /// # Safety
+                    ///
+                    /// C++ doesn't record how many elements there are, so
+                    /// the caller must ensure that this owns an array (see
+                    /// [`Self::is_null`]), that `index` is less than the
+                    /// number of elements in it, and that the element has
+                    /// been initialized. Prefer [`Self::as_slice`], which
+                    /// checks each index against the length you give it.
+                    pub unsafe fn get_unchecked(&self, index: usize) -> &#element {
+                        &*cxxbridge::#get(self).add(index)
+                    }
+
+                    /// Returns a pinned mutable reference to the element at
+                    /// the given index.
+                    ///
+                    /// # Safety
+                    ///
+                    /// As for [`Self::get_unchecked`].
+                    pub unsafe fn get_unchecked_mut(
+                        self: ::core::pin::Pin<&mut Self>,
+                        index: usize,
+                    ) -> ::core::pin::Pin<&mut #element> {
+                        ::core::pin::Pin::new_unchecked(&mut *cxxbridge::#get_mut(self).add(index))
+                    }
+                }
+            }));
+        } else {
+            result.bindgen_mod_items.push(Item::Impl(parse_quote! {
+                impl #ptr {
+                    /// Returns `true` if this doesn't own an object.
+                    pub fn is_null(&self) -> bool {
+                        cxxbridge::#get(self).is_null()
+                    }
+
+                    /// Returns a reference to the owned object, if any.
+                    pub fn get(&self) -> Option<&#element> {
+                        unsafe { cxxbridge::#get(self).as_ref() }
+                    }
+
+                    /// Returns a pinned mutable reference to the owned
+                    /// object, if any.
+                    pub fn get_mut(
+                        self: ::core::pin::Pin<&mut Self>,
+                    ) -> Option<::core::pin::Pin<&mut #element>> {
+                        unsafe {
+                            cxxbridge::#get_mut(self)
+                                .as_mut()
+                                .map(|element| ::core::pin::Pin::new_unchecked(element))
+                        }
+                    }
+                }
+            }));
+        }
+    }
+
+    /// Element access for vectors which cxx can't represent as a
+    /// `CxxVector`.
+    fn add_vector_helpers(
+        &self,
+        name: &QualifiedName,
+        element: Type,
+        element_by_value: bool,
+        result: &mut RsCodegenResult,
+    ) {
+        let vec = name.get_final_ident();
+        let len = make_ident(type_helper_name(self.config, name, "len"));
+        let get = make_ident(type_helper_name(self.config, name, "get"));
+        let bridge_element = unqualify_type(element.clone());
+        let (bridge_item, item) = if element_by_value {
+            (quote! { #bridge_element }, quote! { #element })
+        } else {
+            (quote! { &#bridge_element }, quote! { &#element })
+        };
+        result.extern_c_mod_items.extend([
+            parse_quote! {
+                pub fn #len(vec: &#vec) -> usize;
+            },
+            parse_quote! {
+                pub fn #get(vec: &#vec, index: usize) -> #bridge_item;
+            },
+        ]);
+        result.bindgen_mod_items.push(Item::Impl(parse_quote! {
+            impl #vec {
+                /// Returns the number of elements in the vector.
+                pub fn len(&self) -> usize {
+                    cxxbridge::#len(self)
+                }
+
+                /// Returns `true` if the vector contains no elements.
+                pub fn is_empty(&self) -> bool {
+                    self.len() == 0
+                }
+
+                /// Returns the element at the given index, if any.
+                pub fn get(&self, index: usize) -> Option<#item> {
+                    (index < self.len()).then(|| cxxbridge::#get(self, index))
+                }
+
+                /// Iterates over the elements of the vector.
+                pub fn iter(&self) -> impl Iterator<Item = #item> + '_ {
+                    (0..self.len()).map(move |index| cxxbridge::#get(self, index))
+                }
+            }
+        }));
+    }
+
+    /// Element access for a `std::array` which we can't represent as a Rust
+    /// array.
+    fn add_array_helpers(
+        &self,
+        name: &QualifiedName,
+        element: Type,
+        len: usize,
+        pod: bool,
+        result: &mut RsCodegenResult,
+    ) {
+        let arr = name.get_final_ident();
+        let get = make_ident(type_helper_name(self.config, name, "get"));
+        let get_mut = make_ident(type_helper_name(self.config, name, "get_mut"));
+        let bridge_element = unqualify_type(element.clone());
+        result.extern_c_mod_items.extend([
+            parse_quote! {
+                pub fn #get(arr: &#arr, index: usize) -> &#bridge_element;
+            },
+            parse_quote! {
+                pub fn #get_mut(arr: Pin<&mut #arr>, index: usize) -> Pin<&mut #bridge_element>;
+            },
+        ]);
+        result.bindgen_mod_items.push(Item::Impl(parse_quote! {
+            impl #arr {
+                /// Returns the number of elements in the array.
+                pub fn len(&self) -> usize {
+                    #len
+                }
+
+                /// Returns `true` if the array has no elements.
+                pub fn is_empty(&self) -> bool {
+                    self.len() == 0
+                }
+
+                /// Returns the element at the given index, if any.
+                pub fn get(&self, index: usize) -> Option<&#element> {
+                    (index < self.len()).then(|| cxxbridge::#get(self, index))
+                }
+
+                /// Returns a pinned mutable reference to the element at the
+                /// given index, if any.
+                pub fn get_mut(
+                    self: ::core::pin::Pin<&mut Self>,
+                    index: usize,
+                ) -> Option<::core::pin::Pin<&mut #element>> {
+                    (index < self.len()).then(|| cxxbridge::#get_mut(self, index))
+                }
+
+                /// Iterates over the elements of the array.
+                pub fn iter(&self) -> impl Iterator<Item = &#element> + '_ {
+                    (0..self.len()).map(move |index| cxxbridge::#get(self, index))
+                }
+            }
+        }));
+        if pod {
+            let data = make_ident(type_helper_name(self.config, name, "data"));
+            let data_mut = make_ident(type_helper_name(self.config, name, "data_mut"));
+            result.extern_c_mod_items.extend([
+                parse_quote! {
+                    pub fn #data(arr: &#arr) -> *const #bridge_element;
+                },
+                parse_quote! {
+                    pub fn #data_mut(arr: Pin<&mut #arr>) -> *mut #bridge_element;
+                },
+            ]);
+            result.bindgen_mod_items.push(Item::Impl(parse_quote! {
+                impl #arr {
+                    /// Returns the elements as a slice.
+                    pub fn as_slice(&self) -> &[#element] {
+                        unsafe { ::core::slice::from_raw_parts(cxxbridge::#data(self), #len) }
+                    }
+
+                    /// Returns the elements as a mutable slice. The elements
+                    /// are POD, so they needn't stay pinned.
+                    pub fn as_mut_slice(self: ::core::pin::Pin<&mut Self>) -> &mut [#element] {
+                        unsafe { ::core::slice::from_raw_parts_mut(cxxbridge::#data_mut(self), #len) }
+                    }
+                }
+            }));
+        }
+    }
+
+    /// An enum of references to the alternatives of a `std::variant`,
+    /// so that it can be inspected using `match`.
+    fn add_variant_helpers(
+        &self,
+        name: &QualifiedName,
+        alternatives: Vec<Type>,
+        result: &mut RsCodegenResult,
+    ) {
+        let variant = name.get_final_ident();
+        let enum_type = make_ident(format!("{variant}Ref"));
+        let index = make_ident(type_helper_name(self.config, name, "index"));
+        // Name each enum variant after the type it holds, unless two
+        // alternatives have the same type.
+        let mut variant_names: Vec<_> = alternatives
+            .iter()
+            .map(|ty| match ty {
+                Type::Path(typ) => typ
+                    .path
+                    .segments
+                    .last()
+                    .map(|seg| to_upper_camel_case(&seg.ident.to_string())),
+                _ => None,
+            })
+            .collect();
+        if variant_names.iter().any(|name| name.is_none())
+            || variant_names.iter().collect::<HashSet<_>>().len() != variant_names.len()
+        {
+            variant_names = (0..alternatives.len())
+                .map(|idx| Some(format!("Alternative{idx}")))
+                .collect();
+        }
+        let variant_names: Vec<_> = variant_names
+            .into_iter()
+            .map(|name| make_ident(name.unwrap()))
+            .collect();
+        result.extern_c_mod_items.push(parse_quote! {
+            pub fn #index(variant: &#variant) -> usize;
+        });
+        let mut arms = Vec::new();
+        for (idx, ty) in alternatives.iter().enumerate() {
+            let get = make_ident(type_helper_name(self.config, name, &format!("get{idx}")));
+            let bridge_ty = unqualify_type(ty.clone());
+            result.extern_c_mod_items.push(parse_quote! {
+                pub fn #get(variant: &#variant) -> *const #bridge_ty;
+            });
+            let variant_name = &variant_names[idx];
+            arms.push(quote! {
+                #idx => unsafe { cxxbridge::#get(self).as_ref() }.map(#enum_type::#variant_name),
+            });
+        }
+        let enum_doc = format!("A reference to whichever alternative a [`{variant}`] holds.");
+        result.bindgen_mod_items.extend([
+            Item::Enum(parse_quote! {
+                #[doc = #enum_doc]
+                #[derive(Clone, Copy)]
+                pub enum #enum_type<'a> {
+                    #(#variant_names(&'a #alternatives),)*
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl #variant {
+                    /// Returns the zero-based index of the alternative held.
+                    pub fn index(&self) -> usize {
+                        cxxbridge::#index(self)
+                    }
+
+                    /// Returns a reference to the alternative held, or `None`
+                    /// if the variant is valueless because an exception was
+                    /// thrown during assignment.
+                    pub fn as_enum(&self) -> Option<#enum_type<'_>> {
+                        match self.index() {
+                            #(#arms)*
+                            _ => None,
+                        }
+                    }
+                }
+            }),
+        ]);
+        result
+            .materializations
+            .push(Use::SpecificNameFromBindgen(enum_type.into()));
+    }
+
+    /// Accessors for a `std::pair` or `std::tuple`, plus conversion into a
+    /// Rust tuple of references. If all the elements are POD, we can also
+    /// copy them into Rust by value, via a struct laid out to match the
+    /// C++ one from the C++ code generator.
+    fn add_tuple_helpers(
+        &self,
+        name: &QualifiedName,
+        elements: Vec<(crate::minisyn::Ident, Type)>,
+        pod: bool,
+        result: &mut RsCodegenResult,
+    ) {
+        let tuple = name.get_final_ident();
+        let mut accessors = Vec::new();
+        for (idx, (accessor, ty)) in elements.iter().enumerate() {
+            let get = make_ident(type_helper_name(self.config, name, &format!("get{idx}")));
+            let bridge_ty = unqualify_type(ty.clone());
+            result.extern_c_mod_items.push(parse_quote! {
+                pub fn #get(tuple: &#tuple) -> &#bridge_ty;
+            });
+            let doc = format!("Returns a reference to element {idx}.");
+            accessors.push(quote! {
+                #[doc = #doc]
+                pub fn #accessor(&self) -> &#ty {
+                    cxxbridge::#get(self)
+                }
+            });
+        }
+        let accessor_names = elements.iter().map(|(accessor, _)| accessor);
+        let tys: Vec<_> = elements.iter().map(|(_, ty)| ty).collect();
+        result.bindgen_mod_items.extend([
+            Item::Impl(parse_quote! {
+                impl #tuple {
+                    #(#accessors)*
+
+                    /// Returns a Rust tuple of references to each element.
+                    pub fn to_tuple(&self) -> ( #(&#tys,)* ) {
+                        ( #(self.#accessor_names(),)* )
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> From<&'a #tuple> for ( #(&'a #tys,)* ) {
+                    fn from(tuple: &'a #tuple) -> Self {
+                        tuple.to_tuple()
+                    }
+                }
+            }),
+        ]);
+        if pod {
+            self.add_tuple_pod_helpers(name, &tys, result);
+        }
+    }
+
+    fn add_tuple_pod_helpers(
+        &self,
+        name: &QualifiedName,
+        tys: &[&Type],
+        result: &mut RsCodegenResult,
+    ) {
+        let tuple = name.get_final_ident();
+        let pod_type = make_ident(format!("{tuple}Pod"));
+        let cpp_pod_type = type_helper_name(self.config, name, "pod");
+        let to_pod = make_ident(type_helper_name(self.config, name, "to_pod"));
+        let indices = (0..tys.len()).map(syn::Index::from);
+        result.extern_c_mod_items.extend([
+            ForeignItem::Verbatim(quote! {
+                #[cxx_name = #cpp_pod_type]
+                type #pod_type = super::bindgen::root::#pod_type;
+            }),
+            parse_quote! {
+                pub fn #to_pod(tuple: &#tuple) -> #pod_type;
+            },
+        ]);
+        let doc = format!("A copy of the elements of a [`{tuple}`], in the same order.");
+        result.bindgen_mod_items.extend([
+            Item::Struct(parse_quote! {
+                #[doc = #doc]
+                #[repr(C)]
+                pub struct #pod_type( #(pub #tys,)* );
+            }),
+            Item::Impl(parse_quote! {
+                unsafe impl cxx::ExternType for #pod_type {
+                    type Id = cxx::type_id!(#cpp_pod_type);
+                    type Kind = cxx::kind::Trivial;
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl #tuple {
+                    /// Copies all the elements into Rust.
+                    pub fn to_pod(&self) -> #pod_type {
+                        cxxbridge::#to_pod(self)
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl From<#pod_type> for ( #(#tys,)* ) {
+                    fn from(pod: #pod_type) -> Self {
+                        ( #(pod.#indices,)* )
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> From<&'a #tuple> for ( #(#tys,)* ) {
+                    fn from(tuple: &'a #tuple) -> Self {
+                        tuple.to_pod().into()
+                    }
+                }
+            }),
+        ]);
+        result
+            .materializations
+            .push(Use::SpecificNameFromBindgen(pod_type.into()));
+    }
+
+    fn add_map_helpers(
+        &self,
+        name: &QualifiedName,
+        key: Type,
+        value: Type,
+        insertable: bool,
+        result: &mut RsCodegenResult,
+    ) {
+        let map = name.get_final_ident();
+        let helper = |helper: &str| make_ident(type_helper_name(self.config, name, helper));
+        let iterator = helper("iterator");
+        let (len, get, insert, remove) = (
+            helper("len"),
+            helper("get"),
+            helper("insert"),
+            helper("remove"),
+        );
+        let (iter, iter_key, iter_value, iter_advance) = (
+            helper("iter"),
+            helper("iter_key"),
+            helper("iter_value"),
+            helper("iter_advance"),
+        );
+        let iter_type = make_ident(format!("{map}Iter"));
+        // Within the cxx::bridge, types are referred to by their final
+        // identifier only.
+        let bridge_key = unqualify_type(key.clone());
+        let bridge_value = unqualify_type(value.clone());
+        result.extern_c_mod_items.extend([
+            ForeignItem::Verbatim(quote! {
+                type #iterator;
+            }),
+            parse_quote! {
+                pub fn #len(map: &#map) -> usize;
+            },
+            parse_quote! {
+                pub fn #get(map: &#map, key: &#bridge_key) -> *const #bridge_value;
+            },
+            parse_quote! {
+                pub fn #remove(map: Pin<&mut #map>, key: &#bridge_key) -> bool;
+            },
+            parse_quote! {
+                pub fn #iter(map: &#map) -> UniquePtr<#iterator>;
+            },
+            parse_quote! {
+                pub fn #iter_key(iter: &#iterator) -> *const #bridge_key;
+            },
+            parse_quote! {
+                pub fn #iter_value(iter: &#iterator) -> *const #bridge_value;
+            },
+            parse_quote! {
+                pub fn #iter_advance(iter: Pin<&mut #iterator>);
+            },
+        ]);
+        // The C++ helper copies the key and value, so only exists if
+        // they're copy constructible.
+        let insert_fn = insertable.then(|| {
+            result.extern_c_mod_items.push(parse_quote! {
+                pub fn #insert(map: Pin<&mut #map>, key: &#bridge_key, value: &#bridge_value) -> bool;
+            });
+            quote! {
+                /// Sets the value for the given key, copying both. Returns
+                /// `true` if the key was not previously present.
+                pub fn insert(self: ::core::pin::Pin<&mut Self>, key: &#key, value: &#value) -> bool {
+                    cxxbridge::#insert(self, key, value)
+                }
+            }
+        });
+        result.bindgen_mod_items.extend([
+            Item::Impl(parse_quote! {
+                impl #map {
+                    /// Returns the number of entries in the map.
+                    pub fn len(&self) -> usize {
+                        cxxbridge::#len(self)
+                    }
+
+                    /// Returns `true` if the map contains no entries.
+                    pub fn is_empty(&self) -> bool {
+                        self.len() == 0
+                    }
+
+                    /// Returns a reference to the value for the given key, if any.
+                    pub fn get(&self, key: &#key) -> Option<&#value> {
+                        unsafe { cxxbridge::#get(self, key).as_ref() }
+                    }
+
+                    #insert_fn
+
+                    /// Removes the given key, returning `true` if it was present.
+                    pub fn remove(self: ::core::pin::Pin<&mut Self>, key: &#key) -> bool {
+                        cxxbridge::#remove(self, key)
+                    }
+
+                    /// Iterates over the entries in the map, in the order used by C++.
+                    pub fn iter(&self) -> #iter_type<'_> {
+                        #iter_type {
+                            iter: cxxbridge::#iter(self),
+                            map: ::core::marker::PhantomData,
+                        }
+                    }
+                }
+            }),
+            Item::Struct(parse_quote! {
+                /// An iterator over the entries in a C++ map.
+                pub struct #iter_type<'a> {
+                    iter: cxx::UniquePtr<cxxbridge::#iterator>,
+                    map: ::core::marker::PhantomData<&'a #map>,
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> Iterator for #iter_type<'a> {
+                    type Item = (&'a #key, &'a #value);
+
+                    fn next(&mut self) -> Option<Self::Item> {
+                        // The map can't be modified while we borrow it, so
+                        // the C++ iterator remains valid.
+                        let key = unsafe { cxxbridge::#iter_key(&self.iter).as_ref()? };
+                        let value = unsafe { cxxbridge::#iter_value(&self.iter).as_ref()? };
+                        cxxbridge::#iter_advance(self.iter.pin_mut());
+                        Some((key, value))
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> IntoIterator for &'a #map {
+                    type Item = (&'a #key, &'a #value);
+                    type IntoIter = #iter_type<'a>;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.iter()
+                    }
+                }
+            }),
+        ]);
+        result
+            .materializations
+            .push(Use::SpecificNameFromBindgen(iter_type.into()));
+    }
+
+    /// Rust iteration over a type with `begin()` and `end()` methods,
+    /// using the C++ helpers from the C++ code generator.
+    fn add_iterable_helpers(
+        &self,
+        name: &QualifiedName,
+        element: Type,
+        result: &mut RsCodegenResult,
+    ) {
+        let container = name.get_final_ident();
+        let helper = |helper: &str| make_ident(type_helper_name(self.config, name, helper));
+        let iterator = helper("iterator");
+        let (iter, iter_get, iter_advance) =
+            (helper("iter"), helper("iter_get"), helper("iter_advance"));
+        let iter_type = make_ident(format!("{container}AutocxxIter"));
+        let bridge_element = unqualify_type(element.clone());
+        result.extern_c_mod_items.extend([
+            ForeignItem::Verbatim(quote! {
+                type #iterator;
+            }),
+            parse_quote! {
+                pub fn #iter(container: &#container) -> UniquePtr<#iterator>;
+            },
+            parse_quote! {
+                pub fn #iter_get(iter: &#iterator) -> *const #bridge_element;
+            },
+            parse_quote! {
+                pub fn #iter_advance(iter: Pin<&mut #iterator>);
+            },
+        ]);
+        result.bindgen_mod_items.extend([
+            Item::Impl(parse_quote! {
+                impl #container {
+                    /// Iterates over this object from `begin()` to `end()`.
+                    pub fn iter(&self) -> #iter_type<'_> {
+                        #iter_type {
+                            iter: cxxbridge::#iter(self),
+                            container: ::core::marker::PhantomData,
+                        }
+                    }
+                }
+            }),
+            Item::Struct(parse_quote! {
+                /// An iterator over a C++ object with `begin()` and `end()`
+                /// methods.
+                pub struct #iter_type<'a> {
+                    iter: cxx::UniquePtr<cxxbridge::#iterator>,
+                    container: ::core::marker::PhantomData<&'a #container>,
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> Iterator for #iter_type<'a> {
+                    type Item = &'a #element;
+
+                    fn next(&mut self) -> Option<Self::Item> {
+                        // We only allow const access to the container while
+                        // we borrow it, so the C++ iterators remain valid.
+                        let item = unsafe { cxxbridge::#iter_get(&self.iter).as_ref()? };
+                        cxxbridge::#iter_advance(self.iter.pin_mut());
+                        Some(item)
+                    }
+                }
+            }),
+            Item::Impl(parse_quote! {
+                impl<'a> IntoIterator for &'a #container {
+                    type Item = &'a #element;
+                    type IntoIter = #iter_type<'a>;
+
+                    fn into_iter(self) -> Self::IntoIter {
+                        self.iter()
+                    }
+                }
+            }),
+        ]);
+        result
+            .materializations
+            .push(Use::SpecificNameFromBindgen(iter_type.into()));
+    }
+
     fn add_superclass_stuff_to_type(
         name: &QualifiedName,
         bindgen_mod_items: &mut Vec<Item>,
@@ -1135,6 +2194,15 @@ impl<'a> RsCodeGenerator<'a> {
         })
     }
 
+    fn generate_bindgen_use_stmt_with_alias(name: &QualifiedName, alias: &Ident) -> Item {
+        let segs =
+            Self::find_output_mod_root(name.get_namespace()).chain(name.get_bindgen_path_idents());
+        Item::Use(parse_quote! {
+            #[allow(unused_imports)]
+            pub use #(#segs)::* as #alias;
+        })
+    }
+
     fn generate_extern_type_impl(&self, type_kind: TypeKind, tyname: &QualifiedName) -> Vec<Item> {
         let tynamestring = self.original_name_map.map(tyname);
         let fulltypath = tyname.get_bindgen_path_idents();
@@ -1259,6 +2327,34 @@ fn find_non_pod_types(apis: &ApiVec<FnPhase>) -> HashSet<QualifiedName> {
         .collect()
 }
 
+/// Find the things which documentation may link to.
+fn find_doc_link_targets(apis: &ApiVec<FnPhase>) -> DocLinkTargets {
+    let mut targets = DocLinkTargets::default();
+    for api in apis.iter() {
+        match api {
+            Api::Struct { name, .. }
+            | Api::Enum { name, .. }
+            | Api::Typedef { name, .. }
+            | Api::OpaqueTypedef { name, .. }
+            | Api::ForwardDeclaration { name, .. } => targets.add_type(&name.name),
+            Api::Function {
+                name,
+                analysis:
+                    FnAnalysis {
+                        kind: FnKind::Method { impl_for, .. },
+                        rust_name,
+                        ignore_reason: Ok(_),
+                        externally_callable: true,
+                        ..
+                    },
+                ..
+            } => targets.add_method(impl_for, name.cpp_name(), rust_name),
+            _ => {}
+        }
+    }
+    targets
+}
+
 impl HasNs for (QualifiedName, RsCodegenResult) {
     fn get_namespace(&self) -> &Namespace {
         self.0.get_namespace()
@@ -8,11 +8,12 @@
 
 use crate::conversion::api::Layout;
 use crate::types::make_ident;
+use autocxx_parser::ThreadSafety;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::parse::Parser;
 use syn::punctuated::Punctuated;
-use syn::{parse_quote, Field, Fields, GenericParam, ItemStruct, LitInt};
+use syn::{parse_quote, Field, Fields, GenericParam, Item, ItemStruct, LitInt};
 
 pub(crate) fn new_non_pod_struct(id: Ident) -> ItemStruct {
     let mut s = parse_quote! {
@@ -25,7 +26,7 @@ pub(crate) fn new_non_pod_struct(id: Ident) -> ItemStruct {
 
 pub(crate) fn make_non_pod(s: &mut ItemStruct, layout: Option<Layout>) {
     // Make an opaque struct. If we have layout information, we pass
-    // that through to Rust. We keep only doc attrs, plus add a #[repr(C)]
+    // that through to Rust. We keep only doc and lint attrs, plus add a #[repr(C)]
     // if necessary.
     // Constraints here (thanks to dtolnay@ for this explanation of why the
     // following is needed:)
@@ -37,7 +38,8 @@ pub(crate) fn make_non_pod(s: &mut ItemStruct, layout: Option<Layout>) {
     // Rustc can use least-significant bits of the reference for other storage.
     // (if we have layout information from bindgen we use that instead)
     // (2) We want to ensure the type is !Unpin
-    // (3) We want to ensure it's not Send or Sync
+    // (3) We want to ensure it's not Send or Sync, unless the user
+    // tells us otherwise using thread_safe! (see make_thread_safety_impls)
     //
     // For opaque types, the Rusty opaque structure could in fact be generated
     // by three different things:
@@ -55,7 +57,12 @@ pub(crate) fn make_non_pod(s: &mut ItemStruct, layout: Option<Layout>) {
     let doc_attr = s
         .attrs
         .iter()
-        .filter(|a| a.path().get_ident().iter().any(|p| *p == "doc"))
+        .filter(|a| {
+            a.path()
+                .get_ident()
+                .iter()
+                .any(|p| *p == "doc" || *p == "must_use" || *p == "deprecated")
+        })
         .cloned();
     let repr_attr = if let Some(layout) = &layout {
         let align = make_lit_int(layout.align);
@@ -127,6 +134,24 @@ pub(crate) fn make_non_pod(s: &mut ItemStruct, layout: Option<Layout>) {
     } })
 }
 
+/// Implement `Send` and/or `Sync` for a type which the user has declared
+/// to be thread-safe using `thread_safe!`. cxx's `UniquePtr` and
+/// `SharedPtr` then pick these up from their own impls.
+pub(crate) fn make_thread_safety_impls(id: &Ident, thread_safety: ThreadSafety) -> Vec<Item> {
+    let mut items = Vec::new();
+    if thread_safety.send {
+        items.push(parse_quote! {
+            unsafe impl Send for #id {}
+        });
+    }
+    if thread_safety.sync {
+        items.push(parse_quote! {
+            unsafe impl Sync for #id {}
+        });
+    }
+    items
+}
+
 fn make_lit_int(val: usize) -> LitInt {
     LitInt::new(&val.to_string(), Span::call_site())
 }
@@ -35,6 +35,7 @@ fn do_test(input: ItemMod) {
         inclusions,
         &CodegenOptions::default(),
         "",
+        &|_, _| Err(String::new()),
     )
     .unwrap();
 }
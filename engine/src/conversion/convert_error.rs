@@ -39,6 +39,8 @@ pub enum ConvertError {
 pub enum ConvertErrorFromCpp {
     #[error("An item was requested using 'generate_pod' which was not safe to hold by value in Rust. {0}")]
     UnsafePodType(String),
+    #[error("pod_derive! requested {1} for {0}, but {2}")]
+    UnsupportedPodDerive(String, String, String),
     #[error("Bindgen generated some unexpected code in a foreign mod section. You may have specified something in a 'generate' directive which is not currently compatible with autocxx.")]
     UnexpectedForeignItem,
     #[error("Bindgen generated some unexpected code in its outermost mod section. You may have specified something in a 'generate' directive which is not currently compatible with autocxx.")]
@@ -75,18 +77,22 @@ pub enum ConvertErrorFromCpp {
     UnexpectedUseStatement(Option<String>),
     #[error("Type {} was parameterized over something complex which we don't yet support", .0.to_cpp_name())]
     TemplatedTypeContainingNonPathArg(QualifiedName),
+    #[error("autocxx couldn't find the length of this std::array. It can only do so for the fields of structs, for typedefs, and for the parameters and return types of functions which aren't overloaded, and not where the std::array is inside some other template.")]
+    UnknownStdArrayLength,
     #[error("Pointer pointed to an array, which is not yet supported")]
     InvalidArrayPointee,
     #[error("Pointer pointed to another pointer, which is not yet supported")]
     InvalidPointerPointee,
     #[error("Pointer pointed to something unsupported (autocxx only supports pointers to named types): {0}")]
     InvalidPointee(String),
-    #[error("The 'generate' or 'generate_pod' directive for '{0}' did not result in any code being generated. Perhaps this was mis-spelled or you didn't qualify the name with any namespaces? Otherwise please report a bug.")]
+    #[error("The 'generate', 'generate_pod' or 'pod_derive' directive for '{0}' did not result in any code being generated. Perhaps this was mis-spelled or you didn't qualify the name with any namespaces? Otherwise please report a bug.")]
     DidNotGenerateAnything(String),
     #[error("Found an attempt at using a forward declaration ({}) inside a templated cxx type such as UniquePtr or CxxVector. If the forward declaration is a typedef, perhaps autocxx wasn't sure whether or not it involved a forward declaration. If you're sure it didn't, then you may be able to solve this by using instantiable!.", .0.to_cpp_name())]
     TypeContainingForwardDeclaration(QualifiedName),
     #[error("Found an attempt at using a type marked as blocked! ({})", .0.to_cpp_name())]
     Blocked(QualifiedName),
+    #[error("std::string_view is only supported as a function parameter passed by value, which is passed from Rust as a &str.")]
+    StringViewNotParam,
     #[error("This function or method uses a type where one of the template parameters was incomprehensible to bindgen/autocxx - probably because it uses template specialization.")]
     UnusedTemplateParam,
     #[error("This item relies on a type not known to autocxx ({})", .0.to_cpp_name())]
@@ -153,6 +159,12 @@ pub enum ConvertErrorFromCpp {
     ReferringToGenericTypeParam,
     #[error("This forward declaration was nested within another struct/class. autocxx is unable to represent inner types if they are forward declarations.")]
     ForwardDeclaredNestedType,
+    #[error("naming!(rust) would call this {0}, which clashes with the name given to {1}.")]
+    NamingPolicyCollision(String, String),
+    #[error("rename!(\"{0}\", ...) would give the same name to every overload of {0}. Rename each overload by its signature instead, e.g. rename!(\"{0}(int)\", ...).")]
+    AmbiguousRename(String),
+    #[error("rename! asked for this to be called {0}, but that name is already used by another function.")]
+    RenameClash(String),
     #[error("Problem handling function argument {arg}: {err}")]
     Argument {
         arg: String,
@@ -6,13 +6,515 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use syn::Attribute;
+use indexmap::map::IndexMap as HashMap;
+use indexmap::set::IndexSet as HashSet;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use syn::{
+    parse_quote, visit_mut::VisitMut, Attribute, Expr, ExprLit, ForeignItem, Item, ItemMod, Lit,
+    Meta,
+};
+
+use crate::types::QualifiedName;
+
+/// Any of the Doxygen commands which we know how to convert.
+static DOXYGEN_COMMAND: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"[\\@](brief|short|details|param|returns?|result|retval|see|sa|note|warning|code|",
+        r"endcode|[cpabe]|em)\b"
+    ))
+    .unwrap()
+});
+
+/// A Doxygen command at the start of a line, e.g. `\param[in] foo` or `\code{.cpp}`.
+static BLOCK_COMMAND: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[\\@](\w+)(?:\[[^\]]*\]|\{[^}]*\})?(?:\s+(.*))?$").unwrap());
+
+/// Doxygen commands which style the following word.
+static INLINE_COMMAND: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[\\@](c|p|a|e|em|b)\s+([\w:~]+)").unwrap());
+
+const SEE_ALSO_HEADING: &str = " # See also";
 
 /// Returns the attribute (if any) which contains a doc comment.
+/// Any Doxygen markup is converted to the conventions used by rustdoc.
 pub(super) fn get_doc_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    let doc_attrs: Vec<Attribute> = attrs.iter().filter(|a| is_doc_attr(a)).cloned().collect();
+    let text: Option<Vec<String>> = doc_attrs.iter().map(get_doc_text).collect();
+    match text {
+        Some(text) if text.iter().any(|t| DOXYGEN_COMMAND.is_match(t)) => {
+            let converted = doxygen_to_markdown(&text.join("\n"));
+            vec![parse_quote! { #[doc = #converted] }]
+        }
+        _ => doc_attrs,
+    }
+}
+
+/// Returns any attributes which affect how rustc lints uses of the item.
+/// Here that's just `#[must_use]`, which bindgen reports for
+/// `[[nodiscard]]`. bindgen doesn't report `[[deprecated]]`, so we add
+/// `#[deprecated]` separately, in `mark_deprecated_apis`.
+pub(super) fn get_lint_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
     attrs
         .iter()
-        .filter(|a| a.path().get_ident().iter().any(|p| *p == "doc"))
+        .filter(|a| a.path().is_ident("must_use"))
         .cloned()
         .collect()
 }
+
+/// Replaces the doc comment within `attrs` with `doc_attrs`, typically
+/// obtained from [`get_doc_attrs`].
+pub(super) fn replace_doc_attrs(attrs: &mut Vec<Attribute>, doc_attrs: &[Attribute]) {
+    attrs.retain(|a| !is_doc_attr(a));
+    attrs.splice(0..0, doc_attrs.iter().cloned());
+}
+
+fn is_doc_attr(attr: &Attribute) -> bool {
+    attr.path().get_ident().iter().any(|p| *p == "doc")
+}
+
+fn get_doc_text(attr: &Attribute) -> Option<String> {
+    match &attr.meta {
+        Meta::NameValue(nv) => match &nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The part of a Doxygen comment to which a line of text belongs.
+enum Section {
+    Body,
+    Param(usize),
+    Returns,
+    RetVal(usize),
+    SeeAlso,
+    Code,
+}
+
+#[derive(Default)]
+struct Sections {
+    body: Vec<String>,
+    params: Vec<(String, String)>,
+    returns: Vec<String>,
+    retvals: Vec<(String, String)>,
+    see_also: Vec<String>,
+}
+
+fn doxygen_to_markdown(text: &str) -> String {
+    let mut sections = Sections::default();
+    let mut current = Section::Body;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Section::Code = current {
+            if is_command(trimmed, "endcode") {
+                sections.body.push("```".into());
+                current = Section::Body;
+            } else {
+                // We'll add back the leading space later.
+                sections
+                    .body
+                    .push(line.strip_prefix(' ').unwrap_or(line).to_string());
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            sections.body.push(String::new());
+            current = Section::Body;
+            continue;
+        }
+        let captures = match BLOCK_COMMAND.captures(trimmed) {
+            Some(captures) => captures,
+            None => {
+                let line = convert_inline_commands(trimmed);
+                match current {
+                    Section::Param(idx) => append(&mut sections.params[idx].1, &line),
+                    Section::RetVal(idx) => append(&mut sections.retvals[idx].1, &line),
+                    Section::Returns => sections.returns.push(line),
+                    Section::SeeAlso => sections.see_also.extend(split_references(&line)),
+                    Section::Body | Section::Code => sections.body.push(line),
+                }
+                continue;
+            }
+        };
+        let rest = captures
+            .get(2)
+            .map(|m| convert_inline_commands(m.as_str()))
+            .unwrap_or_default();
+        current = match &captures[1] {
+            "brief" | "short" | "details" => {
+                sections.body.push(rest);
+                Section::Body
+            }
+            "param" => {
+                let (name, description) = split_first_word(&rest);
+                sections
+                    .params
+                    .push((rust_param_name(name), description.to_string()));
+                Section::Param(sections.params.len() - 1)
+            }
+            "return" | "returns" | "result" => {
+                sections.returns.push(rest);
+                Section::Returns
+            }
+            "retval" => {
+                let (value, description) = split_first_word(&rest);
+                sections
+                    .retvals
+                    .push((value.to_string(), description.to_string()));
+                Section::RetVal(sections.retvals.len() - 1)
+            }
+            "see" | "sa" => {
+                sections.see_also.extend(split_references(&rest));
+                Section::SeeAlso
+            }
+            "note" | "warning" => {
+                let label = if &captures[1] == "note" {
+                    "Note"
+                } else {
+                    "Warning"
+                };
+                sections.body.push(String::new());
+                sections.body.push(format!("**{label}:** {rest}"));
+                Section::Body
+            }
+            "code" => {
+                // Anything else would be treated by rustdoc as a doctest.
+                sections.body.push("```cpp".into());
+                Section::Code
+            }
+            _ => {
+                sections.body.push(convert_inline_commands(trimmed));
+                Section::Body
+            }
+        };
+    }
+    sections.into_markdown()
+}
+
+impl Sections {
+    fn into_markdown(self) -> String {
+        let mut lines = self.body;
+        if !self.params.is_empty() {
+            lines.extend(["".into(), "# Arguments".into(), "".into()]);
+            lines.extend(
+                self.params
+                    .into_iter()
+                    .map(|(name, description)| format!("* `{name}` - {description}")),
+            );
+        }
+        if !self.returns.is_empty() || !self.retvals.is_empty() {
+            lines.extend(["".into(), "# Returns".into(), "".into()]);
+            lines.extend(self.returns);
+            lines.extend(
+                self.retvals
+                    .into_iter()
+                    .map(|(value, description)| format!("* `{value}` - {description}")),
+            );
+        }
+        if !self.see_also.is_empty() {
+            lines.extend(["".into(), SEE_ALSO_HEADING.trim().into(), "".into()]);
+            lines.extend(self.see_also.into_iter().map(|r| format!("* `{r}`")));
+        }
+        // Collapse runs of blank lines, which mostly arise from the
+        // sections we've moved elsewhere.
+        let mut output: Vec<String> = Vec::new();
+        for line in lines {
+            let blank = line.trim().is_empty();
+            if blank && output.last().map(|l| l.is_empty()).unwrap_or(true) {
+                continue;
+            }
+            // Match bindgen, which puts a space after the ///
+            output.push(if blank {
+                String::new()
+            } else {
+                format!(" {line}")
+            });
+        }
+        while output.last().map(|l| l.is_empty()).unwrap_or(false) {
+            output.pop();
+        }
+        output.join("\n")
+    }
+}
+
+fn is_command(line: &str, command: &str) -> bool {
+    BLOCK_COMMAND
+        .captures(line)
+        .map(|c| &c[1] == command)
+        .unwrap_or(false)
+}
+
+fn append(description: &mut String, line: &str) {
+    if !description.is_empty() {
+        description.push(' ');
+    }
+    description.push_str(line);
+}
+
+fn split_first_word(s: &str) -> (&str, &str) {
+    match s.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, rest.trim_start()),
+        None => (s, ""),
+    }
+}
+
+fn split_references(s: &str) -> impl Iterator<Item = String> + '_ {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|r| r.trim_end_matches("()").trim_matches('`'))
+        .filter(|r| !r.is_empty())
+        .map(|r| r.to_string())
+}
+
+fn convert_inline_commands(s: &str) -> String {
+    INLINE_COMMAND
+        .replace_all(s, |captures: &Captures| {
+            let word = &captures[2];
+            match &captures[1] {
+                "c" => format!("`{word}`"),
+                "p" => format!("`{}`", rust_param_name(word)),
+                "b" => format!("**{word}**"),
+                _ => format!("*{word}*"),
+            }
+        })
+        .into_owned()
+}
+
+/// The name which bindgen gives to a parameter, and therefore the name
+/// it has in our generated Rust code. This mirrors bindgen's mangling.
+fn rust_param_name(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "abstract", "alignof", "as", "async", "await", "become", "box", "break", "const",
+        "continue", "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for",
+        "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "offsetof",
+        "override", "priv", "proc", "pub", "pure", "ref", "return", "Self", "self", "sizeof",
+        "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+        "use", "virtual", "where", "while", "yield", "str", "bool", "f32", "f64", "usize", "isize",
+        "u128", "i128", "u64", "i64", "u32", "i32", "u16", "i16", "u8", "i8", "_",
+    ];
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+/// The items which a `\see` reference in a doc comment may link to.
+#[derive(Default)]
+pub(super) struct DocLinkTargets {
+    types: HashSet<QualifiedName>,
+    /// Methods, keyed by their type and C++ name, giving the Rust name.
+    methods: HashMap<(QualifiedName, String), String>,
+}
+
+impl DocLinkTargets {
+    pub(super) fn add_type(&mut self, name: &QualifiedName) {
+        self.types.insert(name.clone());
+    }
+
+    pub(super) fn add_method(&mut self, ty: &QualifiedName, cpp_name: String, rust_name: &str) {
+        // For overloads, link to the first.
+        self.methods
+            .entry((ty.clone(), cpp_name))
+            .or_insert_with(|| rust_name.to_string());
+    }
+
+    /// Find the Rust path (relative to `root`) of the given C++ name,
+    /// looking it up as C++ would from within the given namespace.
+    fn resolve(&self, ns: &[String], reference: &str) -> Option<String> {
+        let (global, reference) = match reference.strip_prefix("::") {
+            Some(reference) => (true, reference),
+            None => (false, reference),
+        };
+        let segments: Vec<&str> = reference.split("::").collect();
+        let outermost = if global { 0 } else { ns.len() };
+        (0..=outermost).rev().find_map(|depth| {
+            let candidate: Vec<String> = ns[..depth]
+                .iter()
+                .cloned()
+                .chain(segments.iter().map(|s| s.to_string()))
+                .collect();
+            if self
+                .types
+                .contains(&QualifiedName::new_from_cpp_name(&candidate.join("::")))
+            {
+                return Some(candidate.join("::"));
+            }
+            let (method, ty) = candidate.split_last()?;
+            let ty_name = QualifiedName::new_from_cpp_name(&ty.join("::"));
+            if ty.is_empty() || !self.types.contains(&ty_name) {
+                return None;
+            }
+            let rust_name = self.methods.get(&(ty_name, method.clone()))?;
+            Some(format!("{}::{rust_name}", ty.join("::")))
+        })
+    }
+}
+
+/// Turn the "See also" entries we generated from Doxygen `\see` commands
+/// into intra-doc links, wherever the item referred to has been bound.
+pub(super) fn link_see_also_references(items: &mut [Item], targets: &DocLinkTargets) {
+    let mut linker = SeeAlsoLinker {
+        targets,
+        mods: Vec::new(),
+        ns: Vec::new(),
+    };
+    for item in items {
+        linker.visit_item_mut(item);
+    }
+}
+
+struct SeeAlsoLinker<'a> {
+    targets: &'a DocLinkTargets,
+    mods: Vec<String>,
+    /// The C++ namespace of the item currently being visited.
+    ns: Vec<String>,
+}
+
+impl SeeAlsoLinker<'_> {
+    /// How to refer to `root` from the current mod. Every mod we generate
+    /// except the cxx::bridge has `root` in scope.
+    fn root_path(&self) -> &'static str {
+        if self.mods.first().map(|m| m == "cxxbridge").unwrap_or(false) {
+            "super::root"
+        } else {
+            "root"
+        }
+    }
+}
+
+impl VisitMut for SeeAlsoLinker<'_> {
+    fn visit_item_mod_mut(&mut self, item: &mut ItemMod) {
+        self.mods.push(item.ident.to_string());
+        let old_ns = std::mem::replace(
+            &mut self.ns,
+            match self.mods.as_slice() {
+                [bindgen, root, ns @ ..] if bindgen == "bindgen" && root == "root" => ns.to_vec(),
+                _ => Vec::new(),
+            },
+        );
+        syn::visit_mut::visit_item_mod_mut(self, item);
+        self.ns = old_ns;
+        self.mods.pop();
+    }
+
+    fn visit_foreign_item_mut(&mut self, item: &mut ForeignItem) {
+        // Within the cxx::bridge, the namespace is given by an attribute.
+        let attrs = match item {
+            ForeignItem::Fn(f) => &f.attrs,
+            ForeignItem::Type(t) => &t.attrs,
+            _ => return syn::visit_mut::visit_foreign_item_mut(self, item),
+        };
+        let ns = attrs
+            .iter()
+            .filter(|a| a.path().is_ident("namespace"))
+            .find_map(get_doc_text)
+            .map(|ns| ns.split("::").map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        let old_ns = std::mem::replace(&mut self.ns, ns);
+        syn::visit_mut::visit_foreign_item_mut(self, item);
+        self.ns = old_ns;
+    }
+
+    fn visit_attribute_mut(&mut self, attr: &mut Attribute) {
+        if !is_doc_attr(attr) {
+            return;
+        }
+        let text = match get_doc_text(attr) {
+            Some(text) if text.contains(SEE_ALSO_HEADING) => text,
+            _ => return,
+        };
+        let mut in_see_also = false;
+        let linked = text
+            .lines()
+            .map(|line| {
+                if line.starts_with(" #") {
+                    in_see_also = line == SEE_ALSO_HEADING;
+                }
+                let reference = line
+                    .strip_prefix(" * `")
+                    .and_then(|l| l.strip_suffix('`'))
+                    .filter(|_| in_see_also);
+                match reference.and_then(|r| self.targets.resolve(&self.ns, r)) {
+                    Some(path) => format!(
+                        " * [`{}`]({}::{path})",
+                        reference.unwrap(),
+                        self.root_path()
+                    ),
+                    None => line.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        *attr = parse_quote! { #[doc = #linked] };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{doxygen_to_markdown, DocLinkTargets};
+    use crate::types::QualifiedName;
+    use indoc::indoc;
+
+    #[test]
+    fn test_doxygen_to_markdown() {
+        let input = indoc! {"
+             \\brief Frobnicates the thing.
+             Uses \\p type to decide how.
+
+             \\param[in] type The kind of
+               frobnication.
+             \\param count How many times.
+             \\return Whether it worked.
+             \\see Thing::unfrob, Other
+        "};
+        let expected = [
+            " Frobnicates the thing.",
+            " Uses `type_` to decide how.",
+            "",
+            " # Arguments",
+            "",
+            " * `type_` - The kind of frobnication.",
+            " * `count` - How many times.",
+            "",
+            " # Returns",
+            "",
+            " Whether it worked.",
+            "",
+            " # See also",
+            "",
+            " * `Thing::unfrob`",
+            " * `Other`",
+        ]
+        .join("\n");
+        assert_eq!(doxygen_to_markdown(input), expected);
+    }
+
+    #[test]
+    fn test_doxygen_code_block() {
+        let input = " Example:\n \\code\n   foo(1);\n \\endcode";
+        assert_eq!(
+            doxygen_to_markdown(input),
+            " Example:\n ```cpp\n   foo(1);\n ```"
+        );
+    }
+
+    #[test]
+    fn test_resolve_see_also() {
+        let mut targets = DocLinkTargets::default();
+        let thing = QualifiedName::new_from_cpp_name("a::Thing");
+        targets.add_type(&thing);
+        targets.add_method(&thing, "unfrob".into(), "unfrob1");
+        let ns = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            targets.resolve(&ns, "Thing::unfrob"),
+            Some("a::Thing::unfrob1".into())
+        );
+        assert_eq!(targets.resolve(&ns, "::a::Thing"), Some("a::Thing".into()));
+        assert_eq!(targets.resolve(&ns, "::Thing"), None);
+        assert_eq!(targets.resolve(&ns, "Thing::frob"), None);
+    }
+}
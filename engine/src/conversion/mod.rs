@@ -16,11 +16,13 @@ mod conversion_tests;
 mod convert_error;
 mod doc_attr;
 mod error_reporter;
+mod naming_policy;
 mod parse;
 mod type_helpers;
 mod utilities;
 
 use analysis::fun::FnAnalyzer;
+pub(crate) use analysis::probe::BindgenProbe;
 use autocxx_parser::IncludeCppConfig;
 pub(crate) use codegen_cpp::CppCodeGenerator;
 pub(crate) use convert_error::ConvertError;
@@ -37,10 +39,12 @@ use self::{
         casts::add_casts,
         check_names,
         constructor_deps::decorate_types_with_constructor_deps,
+        deprecated::mark_deprecated_apis,
         gc::filter_apis_by_following_edges_from_allowlist,
         pod::analyze_pod_apis,
         remove_ignored::filter_apis_by_ignored_dependents,
         replace_hopeless_typedef_targets,
+        std_array::find_std_array_lengths,
         tdef::convert_typedef_targets,
     },
     api::AnalysisPhase,
@@ -109,6 +113,7 @@ impl<'a> BridgeConverter<'a> {
         inclusions: String,
         codegen_options: &CodegenOptions,
         source_file_contents: &str,
+        probe: &BindgenProbe,
     ) -> Result<CodegenResults, ConvertError> {
         match &mut bindgen_mod.content {
             None => Err(ConvertError::NoContent),
@@ -118,6 +123,13 @@ impl<'a> BridgeConverter<'a> {
                 let parser = ParseBindgen::new(self.config);
                 let apis = parser.parse_items(items_to_process, source_file_contents)?;
                 Self::dump_apis("parsing", &apis);
+                // bindgen doesn't tell us the length of any std::array, so
+                // find that out separately.
+                let apis = find_std_array_lengths(apis, probe);
+                Self::dump_apis("finding std::array lengths", &apis);
+                // Nor does it tell us what's deprecated.
+                let apis = mark_deprecated_apis(apis, probe);
+                Self::dump_apis("marking deprecated APIs", &apis);
                 // Inside parse_results, we now have a list of APIs.
                 // We now enter various analysis phases.
                 // Next, convert any typedefs.
@@ -157,7 +169,8 @@ impl<'a> BridgeConverter<'a> {
                 Self::dump_apis("marking abstract", &analyzed_apis);
                 // Annotate structs with a note of any copy/move constructors which
                 // we may want to retain to avoid garbage collecting them later.
-                let analyzed_apis = decorate_types_with_constructor_deps(analyzed_apis);
+                let analyzed_apis =
+                    decorate_types_with_constructor_deps(analyzed_apis, self.config);
                 Self::dump_apis("adding constructor deps", &analyzed_apis);
                 let analyzed_apis = discard_ignored_functions(analyzed_apis);
                 Self::dump_apis("ignoring ignorable fns", &analyzed_apis);
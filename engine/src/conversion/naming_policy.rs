@@ -0,0 +1,79 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `naming!(rust)`, which gives C++ APIs Rust-style names.
+
+use crate::types::validate_ident_ok_for_rust;
+
+/// Converts a C++ function or method name such as `GetHTTPResponse` to
+/// `get_http_response`. If that would be a Rust keyword, we append an
+/// underscore, as bindgen does.
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut output = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map(|n| n.is_lowercase()).unwrap_or(false);
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                output.push('_');
+            }
+        }
+        output.extend(c.to_lowercase());
+    }
+    if validate_ident_ok_for_rust(&output).is_err() {
+        output.push('_');
+    }
+    output
+}
+
+/// Converts a C++ type name such as `http_response` to `HttpResponse`.
+/// Returns the original name if there's no sensible UpperCamelCase
+/// equivalent.
+pub(crate) fn to_upper_camel_case(name: &str) -> String {
+    let output: String = name
+        .split('_')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut chars = segment.chars();
+            let first = chars.next().unwrap();
+            first.to_uppercase().chain(chars).collect::<String>()
+        })
+        .collect();
+    if output.is_empty() || output.starts_with(|c: char| c.is_ascii_digit()) {
+        name.to_string()
+    } else {
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_snake_case, to_upper_camel_case};
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("GetWidth"), "get_width");
+        assert_eq!(to_snake_case("getWidth"), "get_width");
+        assert_eq!(to_snake_case("get_width"), "get_width");
+        assert_eq!(to_snake_case("GetHTTPResponse"), "get_http_response");
+        assert_eq!(to_snake_case("Get2DPoint"), "get2_d_point");
+        assert_eq!(to_snake_case("Type"), "type_");
+    }
+
+    #[test]
+    fn test_to_upper_camel_case() {
+        assert_eq!(to_upper_camel_case("http_response"), "HttpResponse");
+        assert_eq!(to_upper_camel_case("HTTPResponse"), "HTTPResponse");
+        assert_eq!(to_upper_camel_case("point"), "Point");
+        assert_eq!(to_upper_camel_case("_1"), "_1");
+    }
+}
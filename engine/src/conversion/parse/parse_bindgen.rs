@@ -132,6 +132,7 @@ impl<'a> ParseBindgen<'a> {
                         name,
                         cpp_definition: cpp_definition.clone(),
                         rs_definition: None,
+                        container: None,
                     }
                 }),
         );
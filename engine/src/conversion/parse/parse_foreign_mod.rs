@@ -8,7 +8,7 @@
 
 use crate::conversion::api::{ApiName, NullPhase, Provenance};
 use crate::conversion::apivec::ApiVec;
-use crate::conversion::doc_attr::get_doc_attrs;
+use crate::conversion::doc_attr::{get_doc_attrs, get_lint_attrs};
 use crate::conversion::error_reporter::report_any_error;
 use crate::conversion::{
     api::{FuncToConvert, UnanalyzedApi},
@@ -21,7 +21,7 @@ use crate::{
     types::{Namespace, QualifiedName},
 };
 use std::collections::HashMap;
-use syn::{Block, Expr, ExprCall, ForeignItem, Ident, ImplItem, ItemImpl, Stmt, Type};
+use syn::{Block, Expr, ExprCall, ForeignItem, Ident, ImplItem, ItemImpl, ReturnType, Stmt, Type};
 
 use super::bindgen_semantic_attributes::BindgenSemanticAttributes;
 
@@ -70,13 +70,22 @@ impl ParseForeignMod {
             ForeignItem::Fn(item) => {
                 let annotations = BindgenSemanticAttributes::new(&item.attrs);
                 let doc_attrs = get_doc_attrs(&item.attrs);
+                let lint_attrs = get_lint_attrs(&item.attrs);
                 self.funcs_to_convert.push(FuncToConvert {
                     provenance: Provenance::Bindgen,
                     self_ty: None,
                     ident: item.sig.ident.into(),
                     doc_attrs: minisynize_vec(doc_attrs),
+                    lint_attrs: minisynize_vec(lint_attrs),
                     inputs: minisynize_punctuated(item.sig.inputs),
-                    output: item.sig.output.into(),
+                    output: match item.sig.output {
+                        // [[noreturn]] functions are reported by bindgen as
+                        // returning `!`, which cxx doesn't understand.
+                        ReturnType::Type(_, ty) if matches!(*ty, Type::Never(_)) => {
+                            ReturnType::Default.into()
+                        }
+                        output => output.into(),
+                    },
                     vis: item.vis.into(),
                     virtualness: annotations.get_virtualness(),
                     cpp_vis: annotations.get_cpp_visibility(),
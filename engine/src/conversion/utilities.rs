@@ -7,12 +7,13 @@
 // except according to those terms.
 
 use autocxx_parser::IncludeCppConfig;
+use itertools::Itertools;
 
 use super::{
     api::{ApiName, NullPhase, UnanalyzedApi},
     apivec::ApiVec,
 };
-use crate::types::{make_ident, Namespace};
+use crate::types::{make_ident, Namespace, QualifiedName};
 
 /// Adds items which we always add, cos they're useful.
 /// Any APIs or techniques which do not involve actual C++ interop
@@ -28,3 +29,16 @@ pub(crate) fn generate_utilities(apis: &mut ApiVec<NullPhase>, config: &IncludeC
         name: ApiName::new(&Namespace::new(), make_ident(config.get_makestring_name())),
     });
 }
+
+/// The name of a C++ helper function which we generate for a given type,
+/// e.g. to convert a wide string to or from the code units we exchange
+/// with Rust. The namespace is included, since these functions all live
+/// in the global namespace.
+pub(crate) fn type_helper_name(
+    config: &IncludeCppConfig,
+    ty: &QualifiedName,
+    helper: &str,
+) -> String {
+    let ty = ty.segment_iter().join("_");
+    config.uniquify_name_per_mod(&format!("{ty}_autocxx_{helper}"))
+}
@@ -7,6 +7,7 @@
 // except according to those terms.
 
 use crate::types::{make_ident, QualifiedName};
+use crate::CppStandard;
 use indexmap::map::IndexMap as HashMap;
 use indoc::indoc;
 use once_cell::sync::OnceCell;
@@ -17,7 +18,26 @@ use syn::{parse_quote, TypePath};
 enum Behavior {
     CxxContainerPtr,
     CxxContainerVector,
+    /// An associative container which cxx doesn't support, so we
+    /// concretize it and generate our own helper functions.
+    CxxContainerMap,
+    /// `std::pair`, which we concretize and for which we generate
+    /// accessors.
+    CxxContainerPair,
+    /// `std::tuple`, likewise.
+    CxxContainerTuple,
+    /// `std::variant`, for which we generate a Rust enum of references
+    /// to the alternatives.
+    CxxContainerVariant,
+    /// `std::array`. bindgen can't represent its non-type template
+    /// parameter, so we find out the length separately, then make a
+    /// Rust array or an opaque type with accessors.
+    CxxContainerArray,
     CxxString,
+    /// `std::string_view`, which we accept from Rust as a `&str`.
+    CxxStringView,
+    /// `std::wstring`, `std::u16string` or `std::u32string`.
+    CxxWideString(WideStringEncoding),
     RustStr,
     RustString,
     RustByValue,
@@ -29,6 +49,36 @@ enum Behavior {
     RustContainerByValueSafe,
 }
 
+/// How a wide string type is converted to and from Rust strings. These
+/// correspond to marker types in the autocxx crate.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum WideStringEncoding {
+    Utf16,
+    Utf32,
+    /// `wchar_t`, which varies by platform.
+    Wide,
+}
+
+impl WideStringEncoding {
+    /// The name of the marker type in the autocxx crate.
+    pub(crate) fn marker(&self) -> &'static str {
+        match self {
+            WideStringEncoding::Utf16 => "Utf16",
+            WideStringEncoding::Utf32 => "Utf32",
+            WideStringEncoding::Wide => "Wide",
+        }
+    }
+
+    /// The width of the code units we exchange with C++. `wchar_t` is
+    /// always widened to 32 bits.
+    pub(crate) fn code_unit_bits(&self) -> u8 {
+        match self {
+            WideStringEncoding::Utf16 => 16,
+            WideStringEncoding::Utf32 | WideStringEncoding::Wide => 32,
+        }
+    }
+}
+
 /// Details about known special types, mostly primitives.
 #[derive(Debug)]
 struct TypeDetails {
@@ -63,21 +113,48 @@ impl TypeDetails {
         }
     }
 
+    /// The earliest C++ standard whose library includes this type.
+    fn min_standard(&self) -> CppStandard {
+        match self.behavior {
+            Behavior::CxxContainerVariant | Behavior::CxxStringView => CppStandard::Cpp17,
+            _ => CppStandard::Cpp14,
+        }
+    }
+
     /// Whether and how to include this in the prelude given to bindgen.
     fn get_prelude_entry(&self) -> Option<String> {
         match self.behavior {
             Behavior::RustString
             | Behavior::RustStr
             | Behavior::CxxString
+            | Behavior::CxxStringView
+            | Behavior::CxxWideString(_)
             | Behavior::CxxContainerPtr
             | Behavior::CxxContainerVector
+            | Behavior::CxxContainerMap
+            | Behavior::CxxContainerPair
+            | Behavior::CxxContainerTuple
+            | Behavior::CxxContainerVariant
+            | Behavior::CxxContainerArray
             | Behavior::RustContainerByValueSafe => {
                 let tn = QualifiedName::new_from_cpp_name(&self.rs_name);
                 let cxx_name = tn.get_final_item();
                 let (templating, payload) = match self.behavior {
+                    // Leaving out the length of a std::array keeps its
+                    // element type visible to bindgen.
                     Behavior::CxxContainerPtr
                     | Behavior::CxxContainerVector
+                    | Behavior::CxxContainerArray
                     | Behavior::RustContainerByValueSafe => ("template<typename T> ", "T* ptr"),
+                    Behavior::CxxContainerMap => {
+                        ("template<typename K, typename V> ", "K* key; V* value")
+                    }
+                    Behavior::CxxContainerPair => {
+                        ("template<typename A, typename B> ", "A* first; B* second")
+                    }
+                    Behavior::CxxContainerTuple | Behavior::CxxContainerVariant => {
+                        ("template<typename... T> ", "char* ptr")
+                    }
                     _ => ("", "char* ptr"),
                 };
                 Some(format!(
@@ -177,10 +254,14 @@ impl TypeDatabase {
     /// give us the templated types (e.g. when faced with the STL
     /// unique_ptr, bindgen would normally give us std_unique_ptr
     /// as opposed to std_unique_ptr<T>.)
-    pub(crate) fn get_prelude(&self) -> String {
+    /// Types from later versions of the standard library are only
+    /// included if we're parsing with that `cpp_standard`, since otherwise
+    /// their stand-ins might clash with unrelated types of the same name.
+    pub(crate) fn get_prelude(&self, cpp_standard: CppStandard) -> String {
         itertools::join(
             self.by_rs_name
                 .values()
+                .filter(|t| t.min_standard() <= cpp_standard)
                 .filter_map(|t| t.get_prelude_entry()),
             "",
         )
@@ -199,26 +280,45 @@ impl TypeDatabase {
             .map(|tn| {
                 (
                     tn.clone(),
-                    match self.get(tn).unwrap().behavior {
-                        Behavior::CxxContainerPtr
-                        | Behavior::RustStr
-                        | Behavior::RustString
-                        | Behavior::RustByValue
-                        | Behavior::CByValueVecSafe
-                        | Behavior::CByValue
-                        | Behavior::CVariableLengthByValue
-                        | Behavior::CChar16
-                        | Behavior::RustContainerByValueSafe => true,
-                        Behavior::CxxString | Behavior::CxxContainerVector | Behavior::CVoid => {
-                            false
-                        }
-                    },
+                    Self::behavior_is_pod_safe(&self.get(tn).unwrap().behavior),
                 )
             })
             .collect::<HashMap<_, _>>();
         pod_safety.into_iter()
     }
 
+    /// Whether this is a known type which is safe to hold and pass by
+    /// value in Rust.
+    pub(crate) fn is_pod_safe(&self, tn: &QualifiedName) -> bool {
+        self.get(tn)
+            .map(|td| Self::behavior_is_pod_safe(&td.behavior))
+            .unwrap_or(false)
+    }
+
+    fn behavior_is_pod_safe(behavior: &Behavior) -> bool {
+        match behavior {
+            Behavior::CxxContainerPtr
+            | Behavior::RustStr
+            | Behavior::RustString
+            | Behavior::RustByValue
+            | Behavior::CByValueVecSafe
+            | Behavior::CByValue
+            | Behavior::CVariableLengthByValue
+            | Behavior::CChar16
+            | Behavior::RustContainerByValueSafe => true,
+            Behavior::CxxString
+            | Behavior::CxxStringView
+            | Behavior::CxxWideString(_)
+            | Behavior::CxxContainerVector
+            | Behavior::CxxContainerMap
+            | Behavior::CxxContainerPair
+            | Behavior::CxxContainerTuple
+            | Behavior::CxxContainerVariant
+            | Behavior::CxxContainerArray
+            | Behavior::CVoid => false,
+        }
+    }
+
     pub(crate) fn get_constructor_details(
         &self,
         qn: &QualifiedName,
@@ -275,25 +375,41 @@ impl TypeDatabase {
 
     /// Get the list of types to give to bindgen to ask it _not_ to
     /// generate code for.
-    pub(crate) fn get_initial_blocklist(&self) -> impl Iterator<Item = &str> + '_ {
+    pub(crate) fn get_initial_blocklist(
+        &self,
+        cpp_standard: CppStandard,
+    ) -> impl Iterator<Item = &str> + '_ {
         self.by_rs_name
             .iter()
+            .filter(move |(_, td)| td.min_standard() <= cpp_standard)
             .filter_map(|(_, td)| td.get_prelude_entry().map(|_| td.cpp_name.as_str()))
     }
 
-    /// Whether this is one of the ctypes (mostly variable length integers)
-    /// which we need to wrap.
+    /// Whether this is one of the ctypes (mostly variable length integers,
+    /// but also wide strings) which we need to wrap.
     pub(crate) fn is_ctype(&self, ty: &QualifiedName) -> bool {
         self.get(ty)
             .map(|td| {
                 matches!(
                     td.behavior,
-                    Behavior::CVariableLengthByValue | Behavior::CVoid | Behavior::CChar16
+                    Behavior::CVariableLengthByValue
+                        | Behavior::CVoid
+                        | Behavior::CChar16
+                        | Behavior::CxxWideString(_)
                 )
             })
             .unwrap_or(false)
     }
 
+    /// If this is a wide string type, how to convert it to and from Rust
+    /// strings.
+    pub(crate) fn wide_string_encoding(&self, ty: &QualifiedName) -> Option<WideStringEncoding> {
+        self.get(ty).and_then(|td| match td.behavior {
+            Behavior::CxxWideString(encoding) => Some(encoding),
+            _ => None,
+        })
+    }
+
     /// Whether this is a generic type acceptable to cxx. Otherwise,
     /// if we encounter a generic, we'll replace it with a synthesized concrete
     /// type.
@@ -314,12 +430,72 @@ impl TypeDatabase {
             .unwrap_or(true)
     }
 
+    /// Whether this is `std::map` or `std::unordered_map`, for which we
+    /// generate helper functions once it's been concretized.
+    pub(crate) fn is_map(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxContainerMap))
+            .unwrap_or(false)
+    }
+
+    /// Whether this is `std::pair`.
+    pub(crate) fn is_pair(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxContainerPair))
+            .unwrap_or(false)
+    }
+
+    /// Whether this is `std::tuple`.
+    pub(crate) fn is_tuple(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxContainerTuple))
+            .unwrap_or(false)
+    }
+
+    /// Whether this is `std::array`.
+    pub(crate) fn is_array(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxContainerArray))
+            .unwrap_or(false)
+    }
+
+    /// Whether this is `std::variant`.
+    pub(crate) fn is_variant(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxContainerVariant))
+            .unwrap_or(false)
+    }
+
+    /// Whether this type can be a key or value in a map, or an element
+    /// of a pair, tuple or variant, for which we generate helper functions.
+    pub(crate) fn permissible_within_concrete_container(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| {
+                matches!(
+                    x.behavior,
+                    Behavior::CxxString
+                        | Behavior::CByValue
+                        | Behavior::CByValueVecSafe
+                        | Behavior::CVariableLengthByValue
+                )
+            })
+            .unwrap_or(true)
+    }
+
+    /// Whether cxx can pass around a Rust array of this type. It has to be
+    /// one of cxx's own built-in types.
+    pub(crate) fn permissible_within_array(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CByValue | Behavior::CByValueVecSafe))
+            .unwrap_or(false)
+    }
+
     pub(crate) fn permissible_within_unique_ptr(&self, ty: &QualifiedName) -> bool {
         self.get(ty)
             .map(|x| {
                 matches!(
                     x.behavior,
-                    Behavior::CxxString | Behavior::CxxContainerVector
+                    Behavior::CxxString | Behavior::CxxWideString(_) | Behavior::CxxContainerVector
                 )
             })
             .unwrap_or(true)
@@ -329,6 +505,14 @@ impl TypeDatabase {
         self.get(ty).is_some()
     }
 
+    /// Whether this is `std::string_view`, which can only be passed by
+    /// value into C++ functions, as a `&str`.
+    pub(crate) fn is_string_view(&self, ty: &QualifiedName) -> bool {
+        self.get(ty)
+            .map(|x| matches!(x.behavior, Behavior::CxxStringView))
+            .unwrap_or(false)
+    }
+
     pub(crate) fn convertible_from_strs(&self, ty: &QualifiedName) -> bool {
         self.get(ty)
             .map(|x| matches!(x.behavior, Behavior::CxxString))
@@ -355,7 +539,7 @@ impl TypeDatabase {
             .filter(|tn| {
                 !matches!(
                     self.get(tn).unwrap().behavior,
-                    Behavior::CxxString | Behavior::CxxContainerVector
+                    Behavior::CxxString | Behavior::CxxWideString(_) | Behavior::CxxContainerVector
                 )
             })
             .cloned()
@@ -380,6 +564,54 @@ fn create_type_database() -> TypeDatabase {
         false,
         true,
     ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxMap",
+        "std::map",
+        Behavior::CxxContainerMap,
+        None,
+        true,
+        true,
+    ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxUnorderedMap",
+        "std::unordered_map",
+        Behavior::CxxContainerMap,
+        None,
+        true,
+        true,
+    ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxPair",
+        "std::pair",
+        Behavior::CxxContainerPair,
+        None,
+        true,
+        true,
+    ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxTuple",
+        "std::tuple",
+        Behavior::CxxContainerTuple,
+        None,
+        true,
+        true,
+    ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxVariant",
+        "std::variant",
+        Behavior::CxxContainerVariant,
+        None,
+        true,
+        true,
+    ));
+    db.insert(TypeDetails::new(
+        "autocxx::CxxArray",
+        "std::array",
+        Behavior::CxxContainerArray,
+        None,
+        true,
+        true,
+    ));
     db.insert(TypeDetails::new(
         "cxx::SharedPtr",
         "std::shared_ptr",
@@ -404,6 +636,43 @@ fn create_type_database() -> TypeDatabase {
         true,
         true,
     ));
+    db.insert(TypeDetails::new(
+        "std::string_view",
+        "std::string_view",
+        Behavior::CxxStringView,
+        None,
+        true,
+        true,
+    ));
+    // These are declared as opaque types within each cxx::bridge, rather
+    // than in the autocxx crate, so that cxx can generate UniquePtr
+    // support for them.
+    for (rs_name, cpp_name, encoding) in [
+        (
+            "cxxbridge::CxxWString",
+            "std::wstring",
+            WideStringEncoding::Wide,
+        ),
+        (
+            "cxxbridge::CxxU16String",
+            "std::u16string",
+            WideStringEncoding::Utf16,
+        ),
+        (
+            "cxxbridge::CxxU32String",
+            "std::u32string",
+            WideStringEncoding::Utf32,
+        ),
+    ] {
+        db.insert(TypeDetails::new(
+            rs_name,
+            cpp_name,
+            Behavior::CxxWideString(encoding),
+            None,
+            true,
+            true,
+        ));
+    }
     db.insert(TypeDetails::new(
         "str",
         "rust::Str",
@@ -28,7 +28,7 @@ mod types;
 mod builder;
 
 use autocxx_bindgen::BindgenError;
-use autocxx_parser::{IncludeCppConfig, UnsafePolicy};
+use autocxx_parser::{IncludeCppConfig, MultiBindings, MultiBindingsErr, UnsafePolicy};
 use conversion::BridgeConverter;
 use miette::{SourceOffset, SourceSpan};
 use parse_callbacks::AutocxxParseCallbacks;
@@ -79,11 +79,24 @@ pub struct CppFilePair {
     pub header: Vec<u8>,
     /// Implementations to go into a .cpp file.
     pub implementation: Option<Vec<u8>>,
+    /// Further implementations, each to go into its own .cpp file.
+    /// These are only generated if [`CppCodegenOptions::cpp_file_shards`]
+    /// asks for the implementation to be split up.
+    pub extra_implementations: Vec<Vec<u8>>,
     /// The name which should be used for the header file
     /// (important as it may be `#include`d elsewhere)
     pub header_name: String,
 }
 
+impl CppFilePair {
+    /// All the implementation files which should be built.
+    pub fn implementations(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.implementation
+            .iter()
+            .chain(self.extra_implementations.iter())
+    }
+}
+
 /// All generated C++ content which should be written to disk.
 pub struct GeneratedCpp(pub Vec<CppFilePair>);
 
@@ -157,14 +170,14 @@ pub struct CodegenOptions<'a> {
     pub cpp_codegen_options: CppCodegenOptions<'a>,
 }
 
-const AUTOCXX_CLANG_ARGS: &[&str; 4] = &["-x", "c++", "-std=c++14", "-DBINDGEN"];
+const AUTOCXX_CLANG_ARGS: &[&str; 3] = &["-x", "c++", "-DBINDGEN"];
 
 /// Implement to learn of header files which get included
 /// by this build process, such that your build system can choose
 /// to rerun the build process if any such file changes in future.
 pub trait RebuildDependencyRecorder: std::fmt::Debug {
     /// Records that this autocxx build depends on the given
-    /// header file. Full paths will be provided.
+    /// header file (or configuration file). Full paths will be provided.
     fn record_header_file_dependency(&self, filename: &str);
 }
 
@@ -328,10 +341,42 @@ impl IncludeCppEngine {
     fn make_bindgen_builder(
         &self,
         inc_dirs: &[PathBuf],
+        cpp_standard: CppStandard,
+        extra_clang_args: &[&str],
+    ) -> bindgen::Builder {
+        let mut builder = Self::make_base_bindgen_builder(inc_dirs, cpp_standard, extra_clang_args);
+
+        // 3. Passes allowlist and other options to the bindgen::Builder equivalent
+        //    to --output-style=cxx --allowlist=<as passed in>
+        if let Some(allowlist) = self.config.bindgen_allowlist() {
+            for a in allowlist {
+                // TODO - allowlist type/functions/separately
+                builder = builder
+                    .allowlist_type(&a)
+                    .allowlist_function(&a)
+                    .allowlist_var(&a);
+            }
+        }
+
+        log::info!(
+            "Bindgen flags would be: {}",
+            builder
+                .command_line_flags()
+                .into_iter()
+                .map(|f| format!("\"{f}\""))
+                .join(" ")
+        );
+        builder
+    }
+
+    /// The bindgen options we always use, whatever we're asking for.
+    fn make_base_bindgen_builder(
+        inc_dirs: &[PathBuf],
+        cpp_standard: CppStandard,
         extra_clang_args: &[&str],
     ) -> bindgen::Builder {
         let mut builder = bindgen::builder()
-            .clang_args(make_clang_args(inc_dirs, extra_clang_args))
+            .clang_args(make_clang_args(inc_dirs, cpp_standard, extra_clang_args))
             .derive_copy(false)
             .derive_debug(false)
             .default_enum_style(bindgen::EnumVariation::Rust {
@@ -350,31 +395,11 @@ impl IncludeCppEngine {
             .cpp_semantic_attributes(true)
             .represent_cxx_operators(true)
             .use_distinct_char16_t(true)
+            .enable_function_attribute_detection()
             .layout_tests(false); // TODO revisit later
-        for item in known_types().get_initial_blocklist() {
+        for item in known_types().get_initial_blocklist(cpp_standard) {
             builder = builder.blocklist_item(item);
         }
-
-        // 3. Passes allowlist and other options to the bindgen::Builder equivalent
-        //    to --output-style=cxx --allowlist=<as passed in>
-        if let Some(allowlist) = self.config.bindgen_allowlist() {
-            for a in allowlist {
-                // TODO - allowlist type/functions/separately
-                builder = builder
-                    .allowlist_type(&a)
-                    .allowlist_function(&a)
-                    .allowlist_var(&a);
-            }
-        }
-
-        log::info!(
-            "Bindgen flags would be: {}",
-            builder
-                .command_line_flags()
-                .into_iter()
-                .map(|f| format!("\"{f}\""))
-                .join(" ")
-        );
         builder
     }
 
@@ -394,6 +419,19 @@ impl IncludeCppEngine {
         }
     }
 
+    /// Find the Rust bindings for this `include_cpp!` within an archive
+    /// previously generated by `autocxx_gen`. Fails if the archive doesn't
+    /// contain bindings for the current contents of the macro.
+    pub fn get_precompiled_rs(
+        &self,
+        archive: &MultiBindings,
+    ) -> Result<TokenStream2, MultiBindingsErr> {
+        match &self.state {
+            State::ParseOnly => Ok(TokenStream2::new()),
+            _ => archive.get(&self.config),
+        }
+    }
+
     /// Returns the name of the mod which this `include_cpp!` will generate.
     /// Can and should be used to ensure multiple mods in a file don't conflict.
     pub fn get_mod_name(&self) -> String {
@@ -442,14 +480,37 @@ impl IncludeCppEngine {
             return Err(Error::WrappedReferencesButNoArbitrarySelfTypes);
         }
 
+        // Arguments from within the include_cpp! block come last so that
+        // they take precedence over those passed in by the build system.
+        let block_clang_args: Vec<String> = self.config.extra_clang_args().collect();
+        let extra_clang_args: Vec<&str> = extra_clang_args
+            .iter()
+            .copied()
+            .chain(block_clang_args.iter().map(String::as_str))
+            .collect();
+        let extra_clang_args = extra_clang_args.as_slice();
+
+        let cpp_standard = codegen_options.cpp_codegen_options.cpp_standard;
         let mod_name = self.config.get_mod_name();
-        let mut builder = self.make_bindgen_builder(&inc_dirs, extra_clang_args);
+        let mut builder = self.make_bindgen_builder(&inc_dirs, cpp_standard, extra_clang_args);
         if let Some(dep_recorder) = dep_recorder {
+            for config_file in self.config.config_files() {
+                dep_recorder.record_header_file_dependency(&config_file.to_string_lossy());
+            }
             builder = builder.parse_callbacks(Box::new(AutocxxParseCallbacks(dep_recorder)));
         }
         let header_contents = self.build_header();
-        self.dump_header_if_so_configured(&header_contents, &inc_dirs, extra_clang_args);
-        let header_and_prelude = format!("{}\n\n{}", known_types().get_prelude(), header_contents);
+        self.dump_header_if_so_configured(
+            &header_contents,
+            &inc_dirs,
+            cpp_standard,
+            extra_clang_args,
+        );
+        let header_and_prelude = format!(
+            "{}\n\n{}",
+            known_types().get_prelude(cpp_standard),
+            header_contents
+        );
         log::info!("Header and prelude for bindgen:\n{}", header_and_prelude);
         builder = builder.header_contents("example.hpp", &header_and_prelude);
 
@@ -464,6 +525,26 @@ impl IncludeCppEngine {
             .cloned()
             .unwrap_or_else(|| Rc::new("".to_string()));
 
+        // Some things bindgen can't tell us directly, but we can find out by
+        // asking it about some extra C++ declarations.
+        let probe_header_contents = header_contents.clone();
+        let probe = |extra_cpp: &str, vars: &str| {
+            let builder =
+                Self::make_base_bindgen_builder(&inc_dirs, cpp_standard, extra_clang_args)
+                    .header_contents(
+                        "example.hpp",
+                        &format!("{probe_header_contents}\n\n{extra_cpp}"),
+                    )
+                    .allowlist_var(vars)
+                    // We want to hear about every error, not just the first few.
+                    .clang_arg("-ferror-limit=0");
+            let bindings = builder.generate().map_err(|err| match err {
+                BindgenError::ClangDiagnostic(diagnostics) => diagnostics,
+                err => err.to_string(),
+            })?;
+            self.parse_bindings(bindings).map_err(|err| err.to_string())
+        };
+
         let converter = BridgeConverter::new(&self.config.inclusions, &self.config);
 
         let conversion = converter
@@ -473,6 +554,7 @@ impl IncludeCppEngine {
                 header_contents,
                 codegen_options,
                 &source_file_contents,
+                &probe,
             )
             .map_err(Error::Conversion)?;
         let mut items = conversion.rs;
@@ -481,6 +563,7 @@ impl IncludeCppEngine {
             #[allow(dead_code)]
             #[allow(non_upper_case_globals)]
             #[allow(non_camel_case_types)]
+            #[allow(deprecated)]
             mod #mod_name {
             }
         };
@@ -511,6 +594,7 @@ impl IncludeCppEngine {
         &self,
         header: &str,
         inc_dirs: &[PathBuf],
+        cpp_standard: CppStandard,
         extra_clang_args: &[&str],
     ) {
         if let Ok(output_path) = std::env::var("AUTOCXX_PREPROCESS") {
@@ -518,6 +602,7 @@ impl IncludeCppEngine {
                 &PathBuf::from(output_path),
                 header,
                 inc_dirs,
+                cpp_standard,
                 extra_clang_args,
             );
         }
@@ -528,6 +613,7 @@ impl IncludeCppEngine {
                 &PathBuf::from(tf.path()),
                 header,
                 inc_dirs,
+                cpp_standard,
                 extra_clang_args,
             );
             let header = std::fs::read(tf.path()).unwrap();
@@ -548,6 +634,7 @@ impl IncludeCppEngine {
         output_path: &Path,
         header: &str,
         inc_dirs: &[PathBuf],
+        cpp_standard: CppStandard,
         extra_clang_args: &[&str],
     ) {
         // Include a load of system headers at the end of the preprocessed output,
@@ -559,6 +646,7 @@ impl IncludeCppEngine {
         // to refer to local headers on the reduction machine too.
         let suffix = ALL_KNOWN_SYSTEM_HEADERS
             .iter()
+            .chain(cpp_standard.known_system_headers())
             .map(|hdr| format!("#include <{hdr}>\n"))
             .join("\n");
         let input = format!("/*\nautocxx config:\n\n{:?}\n\nend autocxx config.\nautocxx preprocessed input:\n*/\n\n{}\n\n/* autocxx: extra headers added below for completeness. */\n\n{}\n{}\n",
@@ -566,7 +654,14 @@ impl IncludeCppEngine {
         let mut tf = NamedTempFile::new().unwrap();
         write!(tf, "{input}").unwrap();
         let tp = tf.into_temp_path();
-        preprocess(&tp, &PathBuf::from(output_path), inc_dirs, extra_clang_args).unwrap();
+        preprocess(
+            &tp,
+            &PathBuf::from(output_path),
+            inc_dirs,
+            cpp_standard,
+            extra_clang_args,
+        )
+        .unwrap();
     }
 }
 
@@ -616,6 +711,7 @@ pub fn do_cxx_cpp_generation(
             cxx_generated.implementation,
             cpp_codegen_options.suppress_system_headers,
         )),
+        extra_implementations: Vec::new(),
     })
 }
 
@@ -667,13 +763,15 @@ impl CppBuildable for IncludeCppEngine {
 /// bindgen.
 pub fn make_clang_args<'a>(
     incs: &'a [PathBuf],
+    cpp_standard: CppStandard,
     extra_args: &'a [&str],
 ) -> impl Iterator<Item = String> + 'a {
-    // AUTOCXX_CLANG_ARGS come first so that any defaults defined there(e.g. for the `-std`
-    // argument) can be overridden by extra_args.
+    // AUTOCXX_CLANG_ARGS and the C++ standard come first so that they can
+    // be overridden by extra_args.
     AUTOCXX_CLANG_ARGS
         .iter()
         .map(|s| s.to_string())
+        .chain(std::iter::once(cpp_standard.clang_arg().to_string()))
         .chain(incs.iter().map(|i| format!("-I{}", i.to_str().unwrap())))
         .chain(extra_args.iter().map(|s| s.to_string()))
 }
@@ -684,12 +782,13 @@ pub fn preprocess(
     listing_path: &Path,
     preprocess_path: &Path,
     incs: &[PathBuf],
+    cpp_standard: CppStandard,
     extra_clang_args: &[&str],
 ) -> Result<(), std::io::Error> {
     let mut cmd = Command::new(get_clang_path());
     cmd.arg("-E");
     cmd.arg("-C");
-    cmd.args(make_clang_args(incs, extra_clang_args));
+    cmd.args(make_clang_args(incs, cpp_standard, extra_clang_args));
     cmd.arg(listing_path.to_str().unwrap());
     cmd.stderr(Stdio::inherit());
     let result = cmd.output().expect("failed to execute clang++");
@@ -788,6 +887,116 @@ pub struct CppCodegenOptions<'a> {
     /// An annotation optionally to include on each C++ function.
     /// For example to export the symbol from a library.
     pub cxx_impl_annotations: Option<String>,
+    /// If set, the wrapper functions which autocxx generates are not
+    /// defined inline in the autocxxgen.h header but are instead
+    /// divided between this number of implementation files, such that
+    /// they can be compiled in parallel. This can substantially speed up
+    /// builds of large bindings, e.g. those using `generate_all!`.
+    /// The same functions are always assigned to the same file. If there
+    /// are fewer functions than files, fewer files are generated.
+    pub cpp_file_shards: Option<usize>,
+    /// The version of the C++ standard to use when parsing headers.
+    /// The generated C++ will refuse to compile with any earlier version.
+    pub cpp_standard: CppStandard,
+}
+
+/// A version of the C++ standard. C++14 is the earliest which can be
+/// used, because it's required by cxx.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum CppStandard {
+    #[default]
+    Cpp14,
+    Cpp17,
+    Cpp20,
+}
+
+impl CppStandard {
+    /// The argument to pass to clang or gcc to select this standard.
+    pub fn clang_arg(&self) -> &'static str {
+        match self {
+            Self::Cpp14 => "-std=c++14",
+            Self::Cpp17 => "-std=c++17",
+            Self::Cpp20 => "-std=c++20",
+        }
+    }
+
+    /// The argument to pass to MSVC to select this standard.
+    pub fn msvc_arg(&self) -> &'static str {
+        match self {
+            Self::Cpp14 => "/std:c++14",
+            Self::Cpp17 => "/std:c++17",
+            Self::Cpp20 => "/std:c++20",
+        }
+    }
+
+    /// The value of `__cplusplus` defined by compilers for this standard.
+    fn cplusplus_value(&self) -> &'static str {
+        match self {
+            Self::Cpp14 => "201402L",
+            Self::Cpp17 => "201703L",
+            Self::Cpp20 => "202002L",
+        }
+    }
+
+    /// Standard library headers which cxx may include only when
+    /// building with this standard or later.
+    fn known_system_headers(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cpp14 => &[],
+            Self::Cpp17 => &["string_view"],
+            Self::Cpp20 => &["string_view", "span", "version"],
+        }
+    }
+
+    /// A preprocessor check that the generated C++ is being built with
+    /// at least this standard. If it's not, types from the standard
+    /// library might not match those which we saw when parsing the headers.
+    pub(crate) fn cpp_check(&self) -> String {
+        if *self == Self::default() {
+            // cxx already insists on C++14.
+            return String::new();
+        }
+        // MSVC only sets __cplusplus correctly when given /Zc:__cplusplus,
+        // so we use _MSVC_LANG there instead.
+        format!(
+            indoc::indoc! {"
+                #if (defined(_MSVC_LANG) ? _MSVC_LANG : __cplusplus) < {value}
+                #error \"These bindings were generated for {name} or later. Please compile with {clang_arg} or {msvc_arg}.\"
+                #endif
+            "},
+            value = self.cplusplus_value(),
+            name = self,
+            clang_arg = self.clang_arg(),
+            msvc_arg = self.msvc_arg(),
+        )
+    }
+}
+
+impl std::fmt::Display for CppStandard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Cpp14 => "C++14",
+            Self::Cpp17 => "C++17",
+            Self::Cpp20 => "C++20",
+        })
+    }
+}
+
+impl std::str::FromStr for CppStandard {
+    type Err = String;
+
+    /// Accepts forms such as `c++17`, `C++17` or simply `17`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        match lower.strip_prefix("c++").unwrap_or(&lower) {
+            "14" => Ok(Self::Cpp14),
+            "17" => Ok(Self::Cpp17),
+            "20" => Ok(Self::Cpp20),
+            _ => Err(format!(
+                "Unsupported C++ standard '{s}'; expected one of c++14, c++17 or c++20"
+            )),
+        }
+    }
 }
 
 fn proc_macro_span_to_miette_span(span: &proc_macro2::Span) -> SourceSpan {
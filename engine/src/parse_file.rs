@@ -14,7 +14,7 @@ use crate::{
 };
 use crate::{proc_macro_span_to_miette_span, CodegenOptions, CppCodegenOptions, LocatedSynError};
 use autocxx_parser::directive_names::SUBCLASS;
-use autocxx_parser::{AllowlistEntry, RustPath, Subclass, SubclassAttrs};
+use autocxx_parser::{AllowlistEntry, ConfigFileErr, RustPath, Subclass, SubclassAttrs};
 use indexmap::set::IndexSet as HashSet;
 use miette::{Diagnostic, SourceSpan};
 use quote::ToTokens;
@@ -59,6 +59,8 @@ pub enum ParseError {
     MultipleModsForDynamicDiscovery,
     #[error("a problem occurred while discovering C++ APIs used within the Rust: {0}")]
     Discovery(DiscoveryErr),
+    #[error(transparent)]
+    ConfigFile(ConfigFileErr),
 }
 
 /// Parse a Rust file, and spot any include_cpp macros within it.
@@ -358,6 +360,18 @@ impl ParsedFile {
         do_get_autocxxes_mut(&mut self.0)
     }
 
+    /// Merges the directives in a TOML configuration file into every
+    /// `include_cpp!` in this file.
+    pub fn load_config_file(&mut self, path: &Path) -> Result<(), ParseError> {
+        for include_cpp in self.get_autocxxes_mut() {
+            include_cpp
+                .config_mut()
+                .load_external_config_file(path)
+                .map_err(ParseError::ConfigFile)?;
+        }
+        Ok(())
+    }
+
     /// Determines the include dirs that were set for each include_cpp, so they can be
     /// used as input to a `cc::Build`.
     #[cfg(any(test, feature = "build"))]
@@ -14,6 +14,8 @@ use std::{io::Write, sync::Mutex};
 
 pub type Builder = autocxx_engine::Builder<'static, CargoBuilderContext>;
 
+pub use autocxx_engine::CppStandard;
+
 #[doc(hidden)]
 pub struct CargoBuilderContext;
 
@@ -18,6 +18,7 @@ use clap::{crate_authors, crate_version, Arg, ArgGroup, Command};
 use depfile::Depfile;
 use indexmap::IndexSet;
 use miette::IntoDiagnostic;
+use similar::TextDiff;
 use std::cell::RefCell;
 use std::io::{Read, Write};
 use std::path::PathBuf;
@@ -82,6 +83,14 @@ opened will be used.
 
 This teaches rustc (and the autocxx macro) that all the different Rust bindings
 for multiple different autocxx macros have been archived into this single file.
+
+If you check generated bindings into your source tree (for instance because
+some of your builders can't run libclang) you can pass --check alongside the
+usual options. Instead of writing anything, the tool will regenerate all the
+outputs in memory and compare them against the files already in the output
+directory. If any file is missing or differs, a diff is printed and the tool
+exits with a failure status, so CI can ensure that committed bindings match
+the headers.
 ";
 
 fn main() -> miette::Result<()> {
@@ -116,6 +125,15 @@ fn main() -> miette::Result<()> {
                 .help("include path")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .multiple_occurrences(true)
+                .number_of_values(1)
+                .value_name("CONFIG FILE")
+                .help("TOML file of extra directives to apply to every include_cpp! (see include_config!)")
+                .takes_value(true),
+        )
         .arg(
             Arg::new("cpp-extension")
                 .long("cpp-extension")
@@ -176,6 +194,26 @@ fn main() -> miette::Result<()> {
                 .help("prefix for symbols to be exported from C++ bindings, e.g. __attribute__ ((visibility (\"default\")))")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("cpp-shards")
+                .long("cpp-shards")
+                .value_name("NUM")
+                .help("split the C++ wrapper functions generated for each include_cpp! across NUM implementation files, so they can be compiled in parallel")
+                .validator(|s| match s.parse::<usize>() {
+                    Ok(shards) if shards >= 1 => Ok(()),
+                    _ => Err("must be a whole number of at least 1"),
+                })
+                .takes_value(true),
+        )
+        .arg(
+            Arg::new("std")
+                .long("std")
+                .value_name("STANDARD")
+                .help("version of the C++ standard to use when parsing headers and in the generated code. You must build the generated C++ with at least this version")
+                .possible_values(["c++14", "c++17", "c++20"])
+                .default_value("c++14")
+                .takes_value(true),
+        )
         .arg(
             Arg::new("generate-cxx-h")
                 .long("generate-cxx-h")
@@ -195,6 +233,11 @@ fn main() -> miette::Result<()> {
                 .help("prefix for path to cxxgen.h (which we generate into the output directory) within #include statements. Must end in /")
                 .takes_value(true),
         )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .help("don't write any files; instead, fail and print a diff if any existing output file differs from what would be generated")
+        )
         .arg(
             Arg::new("depfile")
                 .long("depfile")
@@ -216,6 +259,11 @@ fn main() -> miette::Result<()> {
         .unwrap_or_default()
         .map(PathBuf::from)
         .collect::<Vec<_>>();
+    let config_files = matches
+        .values_of("config")
+        .unwrap_or_default()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
     let extra_clang_args: Vec<_> = matches
         .values_of("clang-args")
         .unwrap_or_default()
@@ -251,6 +299,11 @@ fn main() -> miette::Result<()> {
         path_to_cxxgen_h: get_option_string("cxxgen-h-path", &matches),
         autocxxgen_header_namer,
         cxxgen_header_namer,
+        // Already validated by clap.
+        cpp_file_shards: matches
+            .value_of("cpp-shards")
+            .map(|s| s.parse::<usize>().unwrap()),
+        cpp_standard: matches.value_of("std").unwrap().parse().unwrap(),
     };
     let codegen_options = autocxx_engine::CodegenOptions {
         cpp_codegen_options,
@@ -266,13 +319,17 @@ fn main() -> miette::Result<()> {
         }
     };
     let auto_allowlist = matches.is_present("auto-allowlist");
+    let check_only = matches.is_present("check");
 
     let mut parsed_files = Vec::new();
     for input in matches.values_of("INPUT").expect("No INPUT was provided") {
         // Parse all the .rs files we're asked to process, first.
         // Spot any fundamental parsing or command line problems before we start
         // to do the complex processing.
-        let parsed_file = parse_file(input, auto_allowlist)?;
+        let mut parsed_file = parse_file(input, auto_allowlist)?;
+        for config_file in &config_files {
+            parsed_file.load_config_file(config_file)?;
+        }
         parsed_files.push(parsed_file);
     }
 
@@ -293,7 +350,7 @@ fn main() -> miette::Result<()> {
     // Finally start to write the C++ and Rust out.
     let outdir: PathBuf = matches.value_of_os("outdir").unwrap().into();
 
-    if !outdir.exists() {
+    if !check_only && !outdir.exists() {
         use miette::WrapErr as _;
         std::fs::create_dir_all(&outdir)
             .into_diagnostic()
@@ -304,6 +361,8 @@ fn main() -> miette::Result<()> {
         depfile: &depfile,
         outdir: &outdir,
         written: IndexSet::new(),
+        check_only,
+        stale: Vec::new(),
     };
     if matches.is_present("gen-cpp") {
         let cpp = matches.value_of("cpp-extension").unwrap();
@@ -317,10 +376,18 @@ fn main() -> miette::Result<()> {
                 .generate_h_and_cxx(&codegen_options.cpp_codegen_options)
                 .expect("Unable to generate header and C++ code");
             for pair in generations.0 {
-                let cppname = name_cc_file(counter);
-                writer.write_to_file(cppname, &pair.implementation.unwrap_or_default())?;
+                // Always write at least one (possibly empty) implementation
+                // file per header.
+                let mut implementations: Vec<&[u8]> =
+                    pair.implementations().map(|i| i.as_slice()).collect();
+                if implementations.is_empty() {
+                    implementations.push(&[]);
+                }
+                for implementation in implementations {
+                    writer.write_to_file(name_cc_file(counter), implementation)?;
+                    counter += 1;
+                }
                 writer.write_to_file(pair.header_name, &pair.header)?;
-                counter += 1;
             }
         }
         drop(codegen_options);
@@ -374,6 +441,9 @@ fn main() -> miette::Result<()> {
         let json = generate_rs_archive(rust_buildables);
         writer.write_to_file("gen.rs.json".into(), json.as_bytes())?;
     }
+    if check_only {
+        return writer.report_stale();
+    }
     if let Some(depfile) = depfile {
         depfile.borrow_mut().write().into_diagnostic()?;
     }
@@ -405,6 +475,10 @@ struct FileWriter<'a> {
     depfile: &'a Option<Rc<RefCell<Depfile>>>,
     outdir: &'a Path,
     written: IndexSet<String>,
+    /// If set, compare against existing files instead of writing them.
+    check_only: bool,
+    /// Diffs for each file which doesn't match what we would have written.
+    stale: Vec<String>,
 }
 
 impl<'a> FileWriter<'a> {
@@ -432,24 +506,70 @@ impl<'a> FileWriter<'a> {
         if let Some(depfile) = self.depfile {
             depfile.borrow_mut().add_output(&path);
         }
-        {
+        let existing_content = {
             let f = File::open(&path);
-            if let Ok(mut f) = f {
+            f.ok().and_then(|mut f| {
                 let mut existing_content = Vec::new();
-                let r = f.read_to_end(&mut existing_content);
-                if r.is_ok() && existing_content == content {
-                    return Ok(()); // don't change timestamp on existing file unnecessarily
-                }
+                f.read_to_end(&mut existing_content)
+                    .ok()
+                    .map(|_| existing_content)
+            })
+        };
+        if self.check_only {
+            if existing_content.as_deref() != Some(content) {
+                self.stale.push(describe_difference(
+                    &path,
+                    existing_content.as_deref(),
+                    content,
+                ));
+            }
+        } else {
+            if existing_content.as_deref() == Some(content) {
+                return Ok(()); // don't change timestamp on existing file unnecessarily
             }
+            let mut f = File::create(&path).into_diagnostic()?;
+            f.write_all(content).into_diagnostic()?;
         }
-        let mut f = File::create(&path).into_diagnostic()?;
-        f.write_all(content).into_diagnostic()?;
         if self.written.contains(&filename) {
             return Err(miette::Report::msg(format!("autocxx_gen would write two files entitled '{filename}' which would have conflicting contents. Consider using --generate-exact.")));
         }
         self.written.insert(filename);
         Ok(())
     }
+
+    /// In `--check` mode, print out the differences we found and fail
+    /// if there were any.
+    fn report_stale(self) -> miette::Result<()> {
+        if self.stale.is_empty() {
+            return Ok(());
+        }
+        for diff in &self.stale {
+            eprintln!("{diff}");
+        }
+        Err(miette::Report::msg(format!(
+            "{} generated file(s) in '{}' are out of date. Rerun autocxx-gen without --check to regenerate them.",
+            self.stale.len(),
+            self.outdir.display()
+        )))
+    }
+}
+
+fn describe_difference(path: &Path, existing: Option<&[u8]>, generated: &[u8]) -> String {
+    let path = path.display().to_string();
+    match existing {
+        None => format!("{path}: file does not exist but would be generated"),
+        Some(existing) => {
+            let existing = String::from_utf8_lossy(existing);
+            let generated = String::from_utf8_lossy(generated);
+            TextDiff::from_lines(&*existing, &*generated)
+                .unified_diff()
+                .header(
+                    &format!("{path} (existing)"),
+                    &format!("{path} (generated)"),
+                )
+                .to_string()
+        }
+    }
 }
 
 struct RecordIntoDepfile(Rc<RefCell<Depfile>>);
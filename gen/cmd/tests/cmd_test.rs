@@ -278,6 +278,27 @@ fn test_gen_fixed_num() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_gen_config_file() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let config_path = tmp_dir.path().join("bindings.toml");
+    write_to_file(
+        tmp_dir.path(),
+        "bindings.toml",
+        b"[rename]\n\"DoMath\" = \"do_math\"\n",
+    );
+    let depfile = tmp_dir.path().join("test.d");
+    base_test(&tmp_dir, RsGenMode::Single, |cmd| {
+        cmd.arg("--config")
+            .arg(config_path)
+            .arg("--depfile")
+            .arg(depfile);
+    })?;
+    assert_contains(&tmp_dir, "autocxx-ffi-default-gen.rs", "do_math");
+    assert_contains(&tmp_dir, "test.d", "bindings.toml");
+    Ok(())
+}
+
 #[test]
 fn test_gen_preprocess() -> Result<(), Box<dyn std::error::Error>> {
     let tmp_dir = tempdir()?;
@@ -306,6 +327,60 @@ fn test_gen_repro() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_gen_check() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    base_test(&tmp_dir, RsGenMode::Archive, |_| {})?;
+    let demo_code_dir = tmp_dir.path().join("demo");
+    let check_cmd = || -> Result<Command, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("autocxx-gen")?;
+        cmd.arg("--inc")
+            .arg(demo_code_dir.to_str().unwrap())
+            .arg("--outdir")
+            .arg(tmp_dir.path().to_str().unwrap())
+            .arg("--gen-cpp")
+            .arg("--generate-cxx-h")
+            .arg("--gen-rs-archive")
+            .arg("--check")
+            .arg(demo_code_dir.join("main.rs"));
+        Ok(cmd)
+    };
+    // Freshly generated outputs should be up to date.
+    check_cmd()?.assert().success();
+    // Tamper with an output and ensure we notice, without rewriting it.
+    write_to_file(tmp_dir.path(), "gen0.cc", b"// stale\n");
+    let output = check_cmd()?.output()?;
+    assert!(!output.status.success());
+    assert!(std::str::from_utf8(&output.stderr)?.contains("-// stale"));
+    assert_contains(&tmp_dir, "gen0.cc", "// stale");
+    // Likewise, a missing output should be reported.
+    std::fs::remove_file(tmp_dir.path().join("gen.rs.json"))?;
+    check_cmd()?.assert().failure();
+    assert!(!tmp_dir.path().join("gen.rs.json").exists());
+    Ok(())
+}
+
+#[test]
+fn test_gen_bad_cpp_shards() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    for shards in ["0", "many"] {
+        let mut cmd = Command::cargo_bin("autocxx-gen")?;
+        let output = cmd
+            .arg("--outdir")
+            .arg(tmp_dir.path().to_str().unwrap())
+            .arg("--gen-cpp")
+            .arg("--cpp-shards")
+            .arg(shards)
+            .arg("main.rs")
+            .output()?;
+        assert!(!output.status.success());
+        let stderr = std::str::from_utf8(&output.stderr)?;
+        assert!(stderr.contains("--cpp-shards"), "{stderr}");
+        assert!(!stderr.contains("panicked"), "{stderr}");
+    }
+    Ok(())
+}
+
 fn write_to_file(dir: &Path, filename: &str, content: &[u8]) {
     let path = dir.join(filename);
     let mut f = File::create(path).expect("Unable to create file");
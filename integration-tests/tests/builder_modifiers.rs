@@ -6,12 +6,29 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use autocxx_engine::Builder;
+use autocxx_engine::{Builder, CppStandard};
 
 use autocxx_integration_tests::{BuilderModifier, BuilderModifierFns, TestBuilderContext};
 
 pub(crate) fn make_cpp17_adder() -> Option<BuilderModifier> {
-    make_clang_arg_adder(&["-std=c++17"])
+    Some(Box::new(SetCppStandard(CppStandard::Cpp17)))
+}
+
+pub(crate) struct SetCppStandard(pub(crate) CppStandard);
+
+impl BuilderModifierFns for SetCppStandard {
+    fn modify_autocxx_builder<'a>(
+        &self,
+        builder: Builder<'a, TestBuilderContext>,
+    ) -> Builder<'a, TestBuilderContext> {
+        builder.cpp_standard(self.0)
+    }
+
+    fn modify_cc_builder<'a>(&self, builder: &'a mut cc::Build) -> &'a mut cc::Build {
+        // The test harness explicitly asks for C++14, which would otherwise
+        // override the flag added by the autocxx builder.
+        builder.flag(self.0.clang_arg())
+    }
 }
 
 struct ClangArgAdder(Vec<String>, Vec<String>);
@@ -70,3 +87,14 @@ impl BuilderModifierFns for EnableAutodiscover {
         builder.auto_allowlist(true)
     }
 }
+
+pub(crate) struct SetCppFileShards(pub(crate) usize);
+
+impl BuilderModifierFns for SetCppFileShards {
+    fn modify_autocxx_builder<'a>(
+        &self,
+        builder: Builder<'a, TestBuilderContext>,
+    ) -> Builder<'a, TestBuilderContext> {
+        builder.cpp_file_shards(self.0)
+    }
+}
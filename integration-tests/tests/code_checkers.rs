@@ -178,3 +178,20 @@ impl CodeCheckerFns for NoSystemHeadersChecker {
         true
     }
 }
+
+/// Checks that the expected number of C++ implementation files were generated.
+pub(crate) struct CppImplementationFileCounter(pub(crate) usize);
+
+impl CodeCheckerFns for CppImplementationFileCounter {
+    fn check_cpp(&self, cpp: &[PathBuf]) -> Result<(), TestError> {
+        let implementation_count = cpp
+            .iter()
+            .filter(|filename| filename.extension().is_some_and(|ext| ext == "cxx"))
+            .count();
+        if implementation_count == self.0 {
+            Ok(())
+        } else {
+            Err(TestError::CppCodeExaminationFail)
+        }
+    }
+}
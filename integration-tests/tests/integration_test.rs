@@ -9,11 +9,11 @@
 use crate::{
     builder_modifiers::{
         make_clang_arg_adder, make_clang_optional_arg_adder, make_cpp17_adder, EnableAutodiscover,
-        SetSuppressSystemHeaders,
+        SetCppFileShards, SetSuppressSystemHeaders,
     },
     code_checkers::{
-        make_error_finder, make_rust_code_finder, make_string_finder, CppMatcher,
-        NoSystemHeadersChecker,
+        make_error_finder, make_rust_code_finder, make_string_finder, CppImplementationFileCounter,
+        CppMatcher, NoSystemHeadersChecker,
     },
 };
 use autocxx_integration_tests::{
@@ -478,6 +478,95 @@ fn test_negative_take_as_pod_with_destructor() {
     run_test_expect_fail(cxx, hdr, rs, &["take_bob"], &["Bob"]);
 }
 
+#[test]
+fn test_pod_derive() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        enum class Color { Red, Green };
+        struct Point {
+            int x;
+            uint32_t y;
+            Color color;
+            uint8_t tag[4];
+        };
+        struct Line {
+            Point start;
+            Point end;
+        };
+    "};
+    let rs = quote! {
+        let a = ffi::Line::default();
+        let b = ffi::Line {
+            start: ffi::Point::default(),
+            end: ffi::Point { x: autocxx::c_int(1), y: 2, color: ffi::Color::Green, tag: [0; 4] },
+        };
+        assert_ne!(a, b);
+        assert_eq!(a.start, b.start);
+        let mut set = std::collections::HashSet::new();
+        set.insert(b.end);
+        assert!(format!("{:?}", a).starts_with("Line"));
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Point")
+            generate_pod!("Line")
+            pod_derive!("Point", Debug, PartialEq, Eq, Hash, Default)
+            pod_derive!("Line", Debug, PartialEq, Default)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_negative_pod_derive_unsupported_field() {
+    let hdr = indoc! {"
+        struct Measurement {
+            double value;
+        };
+    "};
+    let rs = quote! {};
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Measurement")
+            pod_derive!("Measurement", Hash)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_negative_pod_derive_unknown_type() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+        };
+    "};
+    let rs = quote! {};
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Point")
+            pod_derive!("Pointt", Debug)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
 #[test]
 fn test_negative_take_as_pod_with_move_constructor() {
     let cxx = indoc! {"
@@ -2629,6 +2718,115 @@ fn test_destructor_no_safety() {
     do_run_test_manual(cxx, hdr, unexpanded_rust, None, None).unwrap();
 }
 
+#[test]
+fn test_safe_override() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct A {
+            uint32_t get() const { return 4; }
+        };
+        inline uint32_t add(uint32_t a, uint32_t b) { return a + b; }
+        inline uint32_t sub(uint32_t a, uint32_t b) { return a - b; }
+    "};
+    let hexathorpe = Token![#](Span::call_site());
+    let unexpanded_rust = quote! {
+        use autocxx::prelude::*;
+
+        include_cpp!(
+            #hexathorpe include "input.h"
+            generate!("A")
+            generate!("add")
+            generate!("sub")
+            safe!("A")
+            safe!("add")
+        );
+
+        fn main() {
+            let a = ffi::A::new().within_unique_ptr();
+            assert_eq!(a.get(), 4);
+            assert_eq!(ffi::add(1, 2), 3);
+            assert_eq!(unsafe { ffi::sub(3, 2) }, 1);
+        }
+    };
+
+    do_run_test_manual("", hdr, unexpanded_rust, None, None).unwrap();
+}
+
+#[test]
+fn test_unsafe_fn_override() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t dangerous() { return 1; }
+        inline uint32_t harmless() { return 2; }
+    "};
+    let rs = quote! {
+        assert_eq!(unsafe { ffi::dangerous() }, 1);
+        assert_eq!(ffi::harmless(), 2);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("dangerous")
+            generate!("harmless")
+            unsafe_fn!("dangerous")
+        },
+        None,
+        Some(make_rust_code_finder(vec![
+            quote! { pub unsafe fn dangerous },
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_thread_safe() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        class Cache {
+        public:
+            uint32_t size() const { return 3; }
+        private:
+            std::string contents;
+        };
+        class Handle {
+        public:
+            uint32_t id() const { return 4; }
+        private:
+            std::string name;
+        };
+    "};
+    let rs = quote! {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        let cache = ffi::Cache::new().within_unique_ptr();
+        assert_send_sync(&cache);
+        let handle = ffi::Handle::new().within_unique_ptr();
+        let size = std::thread::spawn(move || cache.size()).join().unwrap();
+        let id = std::thread::spawn(move || handle.id()).join().unwrap();
+        assert_eq!(size + id, 7);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Cache")
+            generate!("Handle")
+            thread_safe!("Cache", Send, Sync)
+            thread_safe!("Handle", Send)
+        },
+        None,
+        Some(make_rust_code_finder(vec![
+            quote! { unsafe impl Send for Cache {} },
+            quote! { unsafe impl Sync for Cache {} },
+            quote! { unsafe impl Send for Handle {} },
+        ])),
+        None,
+    );
+}
+
 #[test]
 fn test_static_func() {
     let hdr = indoc! {"
@@ -3080,6 +3278,54 @@ fn test_string_let_cxx_string() {
     run_test("", hdr, rs, &["take_string"], &[]);
 }
 
+#[test]
+fn test_wstring() {
+    let hdr = indoc! {"
+        #include <cstddef>
+        #include <string>
+        inline size_t count_chars(const std::wstring& str) {
+            return str.size();
+        }
+        inline void shout(std::wstring& str) {
+            str += L\"!\";
+        }
+    "};
+    let rs = quote! {
+        use ffi::ToRustString;
+        let mut s = ffi::make_wstring("héllo");
+        assert_eq!(ffi::count_chars(&s), 5);
+        ffi::shout(s.pin_mut());
+        assert_eq!(s.to_rust_string().unwrap(), "héllo!");
+    };
+    run_test("", hdr, rs, &["count_chars", "shout"], &[]);
+}
+
+#[test]
+fn test_u16string_and_u32string() {
+    let hdr = indoc! {"
+        #include <cstddef>
+        #include <memory>
+        #include <string>
+        inline std::unique_ptr<std::u16string> unpaired_surrogate() {
+            return std::make_unique<std::u16string>(1, char16_t(0xD800));
+        }
+        inline size_t length(const std::u32string& str) {
+            return str.length();
+        }
+    "};
+    let rs = quote! {
+        use ffi::ToRustString;
+        let bad = ffi::unpaired_surrogate();
+        assert_eq!(bad.to_rust_string(), Err(autocxx::WideStringError));
+        assert_eq!(bad.to_string_lossy(), "\u{FFFD}");
+        let s = ffi::make_u32string("🦀🦀");
+        assert_eq!(ffi::length(&s), 2);
+        assert_eq!(s.to_string_lossy(), "🦀🦀");
+        assert_eq!(ffi::make_u16string("🦀").to_rust_string().unwrap(), "🦀");
+    };
+    run_test("", hdr, rs, &["unpaired_surrogate", "length"], &[]);
+}
+
 #[test]
 fn test_pod_constant_harmless_inside_type() {
     // Check that the presence of this constant doesn't break anything.
@@ -5446,13 +5692,13 @@ fn test_vec_and_up_of_primitives() {
         public:
             Value(std::vector<uint32_t>) {} // OK
             Value(std::unique_ptr<uint32_t>) {} // should be ignored
-            Value(std::vector<int>) {} // should be ignored
+            Value(std::vector<int>) {} // opaque, with accessors
             Value(std::unique_ptr<int>) {} // should be ignored
-            Value(std::vector<char>) {} // should be ignored
+            Value(std::vector<char>) {} // opaque, with accessors
             Value(std::unique_ptr<char>) {} // should be ignored
             Value(std::vector<float>) {} // OK
             Value(std::unique_ptr<float>) {} // should be ignored
-            Value(std::vector<bool>) {} // should be ignored
+            Value(std::vector<bool>) {} // opaque, with accessors
             Value(std::unique_ptr<bool>) {} // should be ignored
             Value(std::vector<size_t>) {} // OK
             Value(std::unique_ptr<size_t>) {} // should be ignored
@@ -5528,6 +5774,33 @@ fn test_defines_effective() {
     );
 }
 
+#[test]
+fn test_define_directive_effective() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #ifdef FOO
+        inline uint32_t a() { return FOO; }
+        #endif
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::a(), 4);
+    };
+    // The define! only affects autocxx's parsing, so the C++ compiler
+    // needs to be told separately.
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            define!("FOO", "4")
+            generate!("a")
+        },
+        make_clang_optional_arg_adder(&[], &["-DFOO=4"]),
+        None,
+        None,
+    );
+}
+
 #[test]
 #[ignore] // https://github.com/google/autocxx/issues/227
 fn test_function_pointer_template() {
@@ -6135,255 +6408,1586 @@ fn test_doc_passthru() {
 }
 
 #[test]
-fn test_closure() {
-    // Ensuring presence of this closure doesn't break other things
+fn test_doxygen_comments() {
     let hdr = indoc! {"
-    #include <functional>
-    #include <cstdint>
-
-    inline bool take_closure(std::function<bool(const uint32_t number)> fn) {
-        return fn(5);
-    }
-    inline uint32_t get_a() {
-        return 3;
-    }
+        #include <cstdint>
+        struct A {
+            /// \\brief Does a thing.
+            /// \\param type The type of thing.
+            /// \\return The result.
+            /// \\see A::bar
+            uint32_t foo(uint32_t type) const { return type; }
+            void bar() const {}
+        };
     "};
-    let rs = quote! {
-        assert_eq!(ffi::get_a(), 3);
-    };
-    run_test("", hdr, rs, &["get_a"], &[]);
+    let rs = quote! {};
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["A"], &[], None),
+        None,
+        Some(make_string_finder(
+            [
+                "# Arguments",
+                "* `type_` - The type of thing.",
+                "# Returns",
+                "* [`A::bar`](root::A::bar)",
+            ]
+            .map(|s| s.to_string())
+            .to_vec(),
+        )),
+        None,
+    );
 }
 
 #[test]
-fn test_multiply_nested_inner_type() {
+fn test_nodiscard() {
     let hdr = indoc! {"
-        struct Turkey {
-            struct Duck {
-                struct Hen {
-                    int wings;
-                };
-                struct HenWithDefault {
-                    HenWithDefault() = default;
-                    int wings;
-                };
-                struct HenWithDestructor {
-                    ~HenWithDestructor() = default;
-                    int wings;
-                };
-                struct HenWithCopy {
-                    HenWithCopy() = default;
-                    HenWithCopy(const HenWithCopy&) = default;
-                    int wings;
-                };
-                struct HenWithMove {
-                    HenWithMove() = default;
-                    HenWithMove(HenWithMove&&) = default;
-                    int wings;
-                };
-            };
+        #include <cstdint>
+        [[nodiscard]] inline uint32_t give_int() { return 5; }
+        struct A {
+            [[nodiscard]] uint32_t get() const { return 4; }
         };
-        "};
+    "};
     let rs = quote! {
-        ffi::Turkey_Duck_Hen::new().within_unique_ptr();
-        ffi::Turkey_Duck_HenWithDefault::new().within_unique_ptr();
-        ffi::Turkey_Duck_HenWithDestructor::new().within_unique_ptr();
-        ffi::Turkey_Duck_HenWithCopy::new().within_unique_ptr();
-        ffi::Turkey_Duck_HenWithMove::new().within_unique_ptr();
-
-        moveit! {
-            let hen = ffi::Turkey_Duck_Hen::new();
-            let moved_hen = autocxx::moveit::new::mov(hen);
-            let _copied_hen = autocxx::moveit::new::copy(moved_hen);
-
-            let hen = ffi::Turkey_Duck_HenWithDefault::new();
-            let moved_hen = autocxx::moveit::new::mov(hen);
-            let _copied_hen = autocxx::moveit::new::copy(moved_hen);
-
-            let _hen = ffi::Turkey_Duck_HenWithDestructor::new();
-
-            let hen = ffi::Turkey_Duck_HenWithCopy::new();
-            let _copied_hen = autocxx::moveit::new::copy(hen);
-
-            let hen = ffi::Turkey_Duck_HenWithMove::new();
-            let _moved_hen = autocxx::moveit::new::mov(hen);
-        }
+        assert_eq!(ffi::give_int(), 5);
+        let a = ffi::A::new().within_unique_ptr();
+        assert_eq!(a.get(), 4);
     };
-    run_test(
+    run_test_ex(
         "",
         hdr,
         rs,
-        &[],
-        &[
-            "Turkey_Duck_Hen",
-            "Turkey_Duck_HenWithDefault",
-            "Turkey_Duck_HenWithDestructor",
-            "Turkey_Duck_HenWithCopy",
+        directives_from_lists(&["give_int", "A"], &[], None),
+        make_cpp17_adder(),
+        Some(make_string_finder(vec!["must_use".to_string()])),
+        None,
+    );
+}
+
+#[test]
+fn test_deprecated() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        [[deprecated(\"use give_int2\")]] inline uint32_t give_int() { return 5; }
+        struct [[deprecated]] A {
+            uint32_t a;
+        };
+        struct B {
+            [[deprecated(\"use get2\")]] uint32_t get() const { return 4; }
+        };
+    "};
+    let rs = quote! {
+        #[allow(deprecated)]
+        fn check() {
+            assert_eq!(ffi::give_int(), 5);
+            let a = ffi::A { a: 3 };
+            assert_eq!(a.a, 3);
+            let b = ffi::B::new().within_unique_ptr();
+            assert_eq!(b.get(), 4);
+        }
+        check();
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["give_int", "B"], &["A"], None),
+        make_cpp17_adder(),
+        Some(make_string_finder(vec![
+            "use give_int2".to_string(),
+            "use get2".to_string(),
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_naming_policy_rust() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct drawing_surface {
+            uint32_t GetWidth() const { return 640; }
+            static uint32_t GetMaxWidth() { return 1024; }
+        };
+        inline uint32_t GetDefaultDepth() { return 24; }
+    "};
+    let rs = quote! {
+        let surface = ffi::DrawingSurface::new().within_unique_ptr();
+        assert_eq!(surface.get_width(), 640);
+        assert_eq!(ffi::DrawingSurface::get_max_width(), 1024);
+        assert_eq!(ffi::get_default_depth(), 24);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            naming!(rust)
+            generate!("drawing_surface")
+            generate!("GetDefaultDepth")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_naming_policy_collision() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct A {
+            uint32_t get_width() const { return 1; }
+            uint32_t GetWidth() const { return 2; }
+        };
+    "};
+    let rs = quote! {
+        let a = ffi::A::new().within_unique_ptr();
+        assert_eq!(a.get_width(), 1);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            naming!(rust)
+            generate!("A")
+        },
+        None,
+        Some(make_string_finder(vec![
+            "would call this get_width".to_string()
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_naming_policy_collision_existing_name_declared_later() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct A {
+            uint32_t GetWidth() const { return 2; }
+            uint32_t get_width() const { return 1; }
+        };
+        inline uint32_t GetDepth() { return 4; }
+        inline uint32_t get_depth() { return 3; }
+    "};
+    let rs = quote! {
+        let a = ffi::A::new().within_unique_ptr();
+        assert_eq!(a.get_width(), 1);
+        assert_eq!(ffi::get_depth(), 3);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            naming!(rust)
+            generate!("A")
+            generate!("GetDepth")
+            generate!("get_depth")
+        },
+        None,
+        Some(make_string_finder(vec![
+            "would call this get_width".to_string(),
+            "would call this get_depth".to_string(),
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_rename_overload() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        namespace ns {
+        struct A {
+            uint32_t Measure(uint32_t a) const { return a; }
+            uint32_t Measure(const std::string& a) const { return a.size(); }
+        };
+        inline uint32_t Count(uint32_t a) { return a; }
+        inline uint32_t Count(const std::string& a) { return a.size(); }
+        struct b {
+            uint32_t c;
+        };
+        }
+    "};
+    let rs = quote! {
+        let a = ffi::ns::A::new().within_unique_ptr();
+        assert_eq!(a.Measure(3), 3);
+        assert_eq!(a.measure_str(&ffi::make_string("hello")), 5);
+        assert_eq!(ffi::ns::Count(4), 4);
+        assert_eq!(ffi::ns::count_str(&ffi::make_string("ab")), 2);
+        let b = ffi::ns::B { c: 6 };
+        assert_eq!(b.c, 6);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("ns::A")
+            generate!("ns::Count")
+            generate_pod!("ns::b")
+            rename!("ns::A::Measure(const std::string &)", measure_str)
+            rename!("ns::Count(const std::string&)", count_str)
+            rename!("ns::b", B)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_rename_overload_by_name_only() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        namespace ns {
+        inline uint32_t Count(uint32_t a) { return a; }
+        inline uint32_t Count(const std::string& a) { return a.size(); }
+        inline uint32_t Total() { return 3; }
+        }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::ns::total(), 3);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("ns::Count")
+            generate!("ns::Total")
+            rename!("ns::Count", count)
+            rename!("ns::Total", total)
+        },
+        None,
+        Some(make_string_finder(vec![
+            "would give the same name to every overload".to_string(),
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_rename_clash() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t first() { return 1; }
+        inline uint32_t second() { return 2; }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::first(), 1);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("first")
+            generate!("second")
+            rename!("second", first)
+        },
+        None,
+        Some(make_string_finder(vec![
+            "already used by another function".to_string()
+        ])),
+        None,
+    );
+}
+
+#[test]
+fn test_overload_naming_types() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        struct View {
+            uint32_t size;
+        };
+        class A {
+        public:
+            A() {}
+            A(uint32_t) {}
+            uint32_t set(int a) const { return a; }
+            uint32_t set(const std::string& a) const { return a.size(); }
+            uint32_t set(const View& a) const { return a.size; }
+            uint32_t get() const { return 7; }
+        };
+        inline uint32_t count(uint32_t a, uint32_t b) { return a + b; }
+        inline uint32_t count(uint32_t a) { return a; }
+        inline uint32_t count() { return 0; }
+    "};
+    let rs = quote! {
+        let a = ffi::A::new().within_unique_ptr();
+        let _b = ffi::A::new_u32(3).within_unique_ptr();
+        assert_eq!(a.set_i32(autocxx::c_int(3)), 3);
+        assert_eq!(a.set_str(&ffi::make_string("hello")), 5);
+        assert_eq!(a.set_view(&ffi::View { size: 9 }), 9);
+        assert_eq!(a.get(), 7);
+        assert_eq!(ffi::count_u32_u32(1, 2), 3);
+        assert_eq!(ffi::count_u32(4), 4);
+        assert_eq!(ffi::count(), 0);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            overload_naming!(types)
+            generate!("A")
+            generate_pod!("View")
+            generate!("count")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_overload_naming_count() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t count(uint32_t a, uint32_t b) { return a + b; }
+        inline uint32_t count(uint32_t a) { return a; }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::count_2(1, 2), 3);
+        assert_eq!(ffi::count_1(4), 4);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            overload_naming!(count)
+            generate!("count")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_closure() {
+    // Ensuring presence of this closure doesn't break other things
+    let hdr = indoc! {"
+    #include <functional>
+    #include <cstdint>
+
+    inline bool take_closure(std::function<bool(const uint32_t number)> fn) {
+        return fn(5);
+    }
+    inline uint32_t get_a() {
+        return 3;
+    }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::get_a(), 3);
+    };
+    run_test("", hdr, rs, &["get_a"], &[]);
+}
+
+#[test]
+fn test_multiply_nested_inner_type() {
+    let hdr = indoc! {"
+        struct Turkey {
+            struct Duck {
+                struct Hen {
+                    int wings;
+                };
+                struct HenWithDefault {
+                    HenWithDefault() = default;
+                    int wings;
+                };
+                struct HenWithDestructor {
+                    ~HenWithDestructor() = default;
+                    int wings;
+                };
+                struct HenWithCopy {
+                    HenWithCopy() = default;
+                    HenWithCopy(const HenWithCopy&) = default;
+                    int wings;
+                };
+                struct HenWithMove {
+                    HenWithMove() = default;
+                    HenWithMove(HenWithMove&&) = default;
+                    int wings;
+                };
+            };
+        };
+        "};
+    let rs = quote! {
+        ffi::Turkey_Duck_Hen::new().within_unique_ptr();
+        ffi::Turkey_Duck_HenWithDefault::new().within_unique_ptr();
+        ffi::Turkey_Duck_HenWithDestructor::new().within_unique_ptr();
+        ffi::Turkey_Duck_HenWithCopy::new().within_unique_ptr();
+        ffi::Turkey_Duck_HenWithMove::new().within_unique_ptr();
+
+        moveit! {
+            let hen = ffi::Turkey_Duck_Hen::new();
+            let moved_hen = autocxx::moveit::new::mov(hen);
+            let _copied_hen = autocxx::moveit::new::copy(moved_hen);
+
+            let hen = ffi::Turkey_Duck_HenWithDefault::new();
+            let moved_hen = autocxx::moveit::new::mov(hen);
+            let _copied_hen = autocxx::moveit::new::copy(moved_hen);
+
+            let _hen = ffi::Turkey_Duck_HenWithDestructor::new();
+
+            let hen = ffi::Turkey_Duck_HenWithCopy::new();
+            let _copied_hen = autocxx::moveit::new::copy(hen);
+
+            let hen = ffi::Turkey_Duck_HenWithMove::new();
+            let _moved_hen = autocxx::moveit::new::mov(hen);
+        }
+    };
+    run_test(
+        "",
+        hdr,
+        rs,
+        &[],
+        &[
+            "Turkey_Duck_Hen",
+            "Turkey_Duck_HenWithDefault",
+            "Turkey_Duck_HenWithDestructor",
+            "Turkey_Duck_HenWithCopy",
             "Turkey_Duck_HenWithMove",
         ],
     );
 }
 
 #[test]
-fn test_underscored_namespace_for_inner_type() {
+fn test_underscored_namespace_for_inner_type() {
+    let hdr = indoc! {"
+        namespace __foo {
+            struct daft {
+                struct bob {
+                    int a;
+                };
+                int a;
+            };
+        }
+        inline void bar(__foo::daft::bob) {}
+    "};
+    let rs = quote! {};
+    run_test("", hdr, rs, &["bar"], &[]);
+}
+
+#[test]
+fn test_blocklist_not_overly_broad() {
+    // This is a regression test. We used to block anything that starts with "rust" or "std",
+    // not just items in the "rust" and "std" namespaces. We therefore test that functions starting
+    // with "rust" or "std" get imported.
+    let hdr = indoc! {"
+    inline void rust_func() { }
+    inline void std_func() { }
+    "};
+    let rs = quote! {
+        ffi::rust_func();
+        ffi::std_func();
+    };
+    run_test("", hdr, rs, &["rust_func", "std_func"], &[]);
+}
+
+#[test]
+#[ignore] // https://github.com/google/autocxx/issues/837
+fn test_ref_qualified_method() {
+    let hdr = indoc! {"
+        struct A {
+            void foo() & {}
+        };
+    "};
+    let rs = quote! {
+        A::new().within_unique_ptr().foo();
+    };
+    run_test("", hdr, rs, &["A"], &[]);
+}
+
+#[cfg_attr(skip_windows_msvc_failing_tests, ignore)]
+#[cfg_attr(skip_windows_gnu_failing_tests, ignore)]
+#[test]
+fn test_stringview() {
+    // Test that APIs using std::string_view do not otherwise cause errors.
+    let hdr = indoc! {"
+        #include <string_view>
+        #include <string>
+        void take_string_view(std::string_view) {}
+        std::string_view return_string_view(const std::string& a) { return std::string_view(a); }
+    "};
+    let rs = quote! {};
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["take_string_view", "return_string_view"], &[], None),
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_include_cpp_alone() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t give_int() {
+            return 5;
+        }
+    "};
+    let hexathorpe = Token![#](Span::call_site());
+    let rs = quote! {
+        use autocxx::include_cpp;
+        include_cpp! {
+            #hexathorpe include "input.h"
+            safety!(unsafe_ffi)
+            generate!("give_int")
+        }
+        fn main() {
+            assert_eq!(ffi::give_int(), 5);
+        }
+    };
+    do_run_test_manual("", hdr, rs, None, None).unwrap();
+}
+
+#[test]
+fn test_include_cpp_in_path() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        inline uint32_t give_int() {
+            return 5;
+        }
+    "};
+    let hexathorpe = Token![#](Span::call_site());
+    let rs = quote! {
+            autocxx::include_cpp! {
+                #hexathorpe include "input.h"
+                safety!(unsafe_ffi)
+                generate!("give_int")
+            }
+            fn main() {
+                assert_eq!(ffi::give_int(), 5);
+            }
+    };
+    do_run_test_manual("", hdr, rs, None, None).unwrap();
+}
+
+#[test]
+fn test_bitset() {
+    let hdr = indoc! {"
+        #include <cstddef>
+        template <size_t _N_words, size_t _Size>
+        class __bitset
+        {
+        public:
+            typedef size_t              __storage_type;
+            __storage_type __first_[_N_words];
+            inline bool all() {
+                return false;
+            }
+        };
+
+        template <size_t _Size>
+        class bitset
+            : private __bitset<_Size == 0 ? 0 : (_Size - 1) / (sizeof(size_t) * 8) + 1, _Size>
+        {
+        public:
+            static const unsigned __n_words = _Size == 0 ? 0 : (_Size - 1) / (sizeof(size_t) * 8) + 1;
+            typedef __bitset<__n_words, _Size> base;
+            bool all() const noexcept;
+        };
+
+
+        typedef bitset<1> mybitset;
+    "};
+    run_generate_all_test(hdr);
+}
+
+#[test]
+fn test_cint_vector() {
+    let hdr = indoc! {"
+        #include <vector>
+        #include <cstdint>
+        inline std::vector<int32_t> give_vec() {
+            return std::vector<int32_t> {1,2};
+        }
+    "};
+
+    let rs = quote! {
+        assert_eq!(ffi::give_vec().as_ref().unwrap().as_slice(), &[1,2]);
+    };
+
+    run_test("", hdr, rs, &["give_vec"], &[]);
+}
+
+#[test]
+fn test_std_map() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <cstddef>
+        #include <map>
+        #include <memory>
+        #include <string>
+        inline std::unique_ptr<std::map<uint32_t, std::string>> make_map() {
+            auto map = std::make_unique<std::map<uint32_t, std::string>>();
+            (*map)[1] = \"one\";
+            (*map)[2] = \"two\";
+            return map;
+        }
+        inline size_t count_entries(const std::map<uint32_t, std::string>& map) {
+            return map.size();
+        }
+    "};
+    let rs = quote! {
+        let mut map = ffi::make_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1).unwrap().to_str().unwrap(), "one");
+        assert!(map.get(&3).is_none());
+        autocxx::cxx::let_cxx_string!(three = "three");
+        assert!(map.pin_mut().insert(&3, &three));
+        assert!(map.pin_mut().remove(&1));
+        assert!(!map.pin_mut().remove(&1));
+        let entries: Vec<_> = map
+            .iter()
+            .map(|(k, v)| (*k, v.to_str().unwrap().to_string()))
+            .collect();
+        assert_eq!(entries, vec![(2, "two".to_string()), (3, "three".to_string())]);
+        assert_eq!(ffi::count_entries(&map), 2);
+    };
+    run_test("", hdr, rs, &["make_map", "count_entries"], &[]);
+}
+
+#[test]
+fn test_std_unordered_map() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <unordered_map>
+        inline std::unique_ptr<std::unordered_map<int32_t, double>> make_weights() {
+            auto map = std::make_unique<std::unordered_map<int32_t, double>>();
+            (*map)[4] = 0.5;
+            (*map)[7] = 1.5;
+            return map;
+        }
+    "};
+    let rs = quote! {
+        let mut weights = ffi::make_weights();
+        assert!(!weights.pin_mut().insert(&4, &2.0));
+        assert_eq!(weights.get(&4), Some(&2.0));
+        let mut entries: Vec<_> = (&*weights).into_iter().map(|(k, v)| (*k, *v)).collect();
+        entries.sort_by_key(|(k, _)| *k);
+        assert_eq!(entries, vec![(4, 2.0), (7, 1.5)]);
+    };
+    run_test("", hdr, rs, &["make_weights"], &[]);
+}
+
+#[test]
+fn test_std_map_move_only_value() {
+    // There's no `insert`, because it would need to copy the value, but
+    // everything else should work.
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <map>
+        #include <memory>
+        struct Token {
+            Token(uint32_t id) : id(id) {}
+            Token(const Token&) = delete;
+            Token(Token&&) = default;
+            uint32_t id;
+        };
+        inline std::unique_ptr<std::map<uint32_t, Token>> make_tokens() {
+            auto map = std::make_unique<std::map<uint32_t, Token>>();
+            map->emplace(1, Token(10));
+            return map;
+        }
+        inline uint32_t get_id(const Token& token) { return token.id; }
+    "};
+    let rs = quote! {
+        let tokens = ffi::make_tokens();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(ffi::get_id(tokens.get(&1).unwrap()), 10);
+        assert_eq!(tokens.iter().count(), 1);
+    };
+    run_test("", hdr, rs, &["make_tokens", "get_id", "Token"], &[]);
+}
+
+#[test]
+fn test_std_pair() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <utility>
+        inline std::pair<int32_t, std::string> get_pair() {
+            return std::make_pair(7, std::string(\"seven\"));
+        }
+    "};
+    let rs = quote! {
+        let pair = ffi::get_pair().within_unique_ptr();
+        assert_eq!(*pair.first(), 7);
+        assert_eq!(pair.second().to_str().unwrap(), "seven");
+        let (num, name): (&i32, &autocxx::cxx::CxxString) = pair.as_ref().unwrap().into();
+        assert_eq!((*num, name.to_str().unwrap()), (7, "seven"));
+    };
+    run_test("", hdr, rs, &["get_pair"], &[]);
+}
+
+#[test]
+fn test_std_tuple() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <tuple>
+        inline std::unique_ptr<std::tuple<uint8_t, double, bool>> make_tuple() {
+            return std::make_unique<std::tuple<uint8_t, double, bool>>(3, 0.25, true);
+        }
+    "};
+    let rs = quote! {
+        let tuple = ffi::make_tuple();
+        assert_eq!(*tuple.get0(), 3);
+        assert_eq!(*tuple.get1(), 0.25);
+        assert!(*tuple.get2());
+        assert_eq!(tuple.to_tuple(), (&3, &0.25, &true));
+        let owned: (u8, f64, bool) = tuple.as_ref().unwrap().into();
+        assert_eq!(owned, (3, 0.25, true));
+    };
+    run_test("", hdr, rs, &["make_tuple"], &[]);
+}
+
+#[test]
+fn test_std_pair_of_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <utility>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        inline std::pair<Point, int32_t> get_pair() {
+            return std::make_pair(Point { 3, 4 }, 5);
+        }
+    "};
+    let rs = quote! {
+        let pair = ffi::get_pair().within_unique_ptr();
+        let pod = pair.to_pod();
+        assert_eq!((pod.0.x, pod.0.y, pod.1), (3, 4, 5));
+        let (point, num): (ffi::Point, i32) = pair.as_ref().unwrap().into();
+        assert_eq!((point.x, point.y, num), (3, 4, 5));
+    };
+    run_test("", hdr, rs, &["get_pair"], &["Point"]);
+}
+
+#[test]
+fn test_std_variant() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <string>
+        #include <variant>
+        struct Ping {
+            uint32_t seq;
+        };
+        typedef std::variant<Ping, uint32_t, std::string> Message;
+        // The Rust enum is named after the synthesized concrete type,
+        // not the typedef.
+        inline std::unique_ptr<Message> make_ping() {
+            return std::make_unique<Message>(Ping { 12 });
+        }
+        inline std::unique_ptr<Message> make_text() {
+            return std::make_unique<Message>(std::string(\"hello\"));
+        }
+    "};
+    let rs = quote! {
+        use ffi::std_variant_Ping_uint32_t_std_string_AutocxxConcreteRef as MessageRef;
+        let ping = ffi::make_ping();
+        assert_eq!(ping.index(), 0);
+        match ping.as_enum().unwrap() {
+            MessageRef::Ping(p) => assert_eq!(p.seq, 12),
+            _ => panic!("wrong alternative"),
+        }
+        let text = ffi::make_text();
+        match text.as_enum().unwrap() {
+            MessageRef::CxxString(s) => assert_eq!(s.to_str().unwrap(), "hello"),
+            _ => panic!("wrong alternative"),
+        }
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["make_ping", "make_text"], &["Ping"], None),
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_string_view_param() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <string_view>
+        inline size_t count_chars(std::string_view s) { return s.size(); }
+        class Greeter {
+        public:
+            Greeter() : greeting(\"hello\") {}
+            bool is_greeting(std::string_view s) const { return s == greeting; }
+        private:
+            std::string greeting;
+        };
+        // Not bound: we can't tell how long the characters will live.
+        inline std::string_view get_name() { return \"name\"; }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        assert_eq!(ffi::count_chars("hello"), 5);
+        let greeter = ffi::Greeter::new().within_unique_ptr();
+        assert!(greeter.is_greeting("hello"));
+        assert!(!greeter.is_greeting("goodbye"));
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["count_chars", "Greeter", "get_name"], &[], None),
+        make_cpp17_adder(),
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_std_array_pod_field() {
+    let hdr = indoc! {"
+        #include <array>
+        #include <cstdint>
+        struct Samples {
+            std::array<uint32_t, 4> values;
+            uint32_t count;
+        };
+        inline uint32_t total(Samples s) {
+            uint32_t sum = 0;
+            for (uint32_t i = 0; i < s.count; ++i) {
+                sum += s.values[i];
+            }
+            return sum;
+        }
+    "};
+    let rs = quote! {
+        let samples = ffi::Samples {
+            values: [1, 2, 3, 4],
+            count: 3,
+        };
+        assert_eq!(ffi::total(samples), 6);
+    };
+    run_test("", hdr, rs, &["total"], &["Samples"]);
+}
+
+#[test]
+fn test_nested_vector() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <vector>
+        inline std::unique_ptr<std::vector<std::vector<int32_t>>> make_grid() {
+            return std::make_unique<std::vector<std::vector<int32_t>>>(
+                std::vector<std::vector<int32_t>> { { 1, 2 }, { 3 } });
+        }
+    "};
+    let rs = quote! {
+        let grid = ffi::make_grid();
+        assert_eq!(grid.len(), 2);
+        let rows: Vec<Vec<i32>> = grid.iter().map(|row| row.iter().copied().collect()).collect();
+        assert_eq!(rows, vec![vec![1, 2], vec![3]]);
+        assert!(grid.get(2).is_none());
+    };
+    run_test("", hdr, rs, &["make_grid"], &[]);
+}
+
+#[test]
+fn test_vector_of_bool() {
+    let hdr = indoc! {"
+        #include <cstddef>
+        #include <vector>
+        inline const std::vector<bool>& flags() {
+            static std::vector<bool> flags { true, false, true };
+            return flags;
+        }
+    "};
+    let rs = quote! {
+        let flags = ffi::flags();
+        assert_eq!(flags.iter().collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(flags.get(1), Some(false));
+    };
+    run_test("", hdr, rs, &["flags"], &[]);
+}
+
+#[test]
+fn test_vector_of_unique_ptr() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <vector>
+        struct Widget {
+            uint32_t id;
+        };
+        inline std::unique_ptr<std::vector<std::unique_ptr<Widget>>> make_widgets() {
+            auto widgets = std::make_unique<std::vector<std::unique_ptr<Widget>>>();
+            widgets->push_back(std::make_unique<Widget>(Widget { 4 }));
+            widgets->push_back(std::make_unique<Widget>(Widget { 5 }));
+            return widgets;
+        }
+    "};
+    let rs = quote! {
+        let widgets = ffi::make_widgets();
+        let ids: Vec<u32> = widgets.iter().map(|w| w.as_ref().unwrap().id).collect();
+        assert_eq!(ids, vec![4, 5]);
+    };
+    run_test("", hdr, rs, &["make_widgets"], &["Widget"]);
+}
+
+#[test]
+fn test_collect_vector_of_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <vector>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        inline uint32_t sum_points(const std::vector<Point>& points) {
+            uint32_t total = 0;
+            for (const auto& point : points) {
+                total += point.x * point.y;
+            }
+            return total;
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let points = vec![ffi::Point { x: 2, y: 3 }, ffi::Point { x: 4, y: 5 }];
+        let vec: UniquePtr<cxx::CxxVector<ffi::Point>> = points.iter().collect();
+        assert_eq!(vec.len(), 2);
+        assert_eq!(ffi::sum_points(&vec), 26);
+        let vec: UniquePtr<cxx::CxxVector<ffi::Point>> =
+            (1..4).map(|x| ffi::Point { x, y: 1 }).collect();
+        assert_eq!(ffi::sum_points(&vec), 6);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("sum_points")
+            generate_pod!("Point")
+            pod_derive!("Point", Clone, Copy)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_collect_vector_of_pod_references_needs_copy() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <vector>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        inline size_t count_points(const std::vector<Point>& points) {
+            return points.size();
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let points = vec![ffi::Point { x: 2, y: 3 }];
+        let _: UniquePtr<cxx::CxxVector<ffi::Point>> = points.iter().collect();
+    };
+    run_test_expect_fail("", hdr, rs, &["count_points"], &["Point"]);
+}
+
+#[test]
+fn test_negative_pod_derive_copy_not_trivially_copyable() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Counter {
+            Counter(const Counter& other) : count(other.count + 1) {}
+            Counter(Counter&&) = default;
+            uint32_t count;
+        };
+    "};
+    let rs = quote! {};
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("Counter")
+            pod_derive!("Counter", Clone, Copy)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_collect_vector_of_non_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        #include <vector>
+        class Goat {
+        public:
+            Goat(uint32_t horns) : horns(horns), name(\"goat\") {}
+            uint32_t get_horns() const { return horns; }
+        private:
+            uint32_t horns;
+            std::string name;
+        };
+        inline uint32_t count_horns(const std::vector<Goat>& goats) {
+            uint32_t total = 0;
+            for (const auto& goat : goats) {
+                total += goat.get_horns();
+            }
+            return total;
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let goats: UniquePtr<cxx::CxxVector<ffi::Goat>> = autocxx::collect_vector(
+            (1..4).map(|horns| ffi::Goat::new(horns).within_unique_ptr()),
+        );
+        assert_eq!(goats.len(), 3);
+        assert_eq!(ffi::count_horns(&goats), 6);
+    };
+    run_test("", hdr, rs, &["count_horns", "Goat"], &[]);
+}
+
+#[test]
+fn test_collect_vector_only_for_vector_elements() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        inline uint32_t area(const Point& point) {
+            return point.x * point.y;
+        }
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let _: UniquePtr<cxx::CxxVector<ffi::Point>> =
+            vec![ffi::Point { x: 2, y: 3 }].iter().collect();
+    };
+    run_test_expect_fail("", hdr, rs, &["area"], &["Point"]);
+}
+
+#[test]
+fn test_make_shared() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        #include <string>
+        class Goat {
+        public:
+            Goat(uint32_t horns) : horns(horns), name(\"goat\") {}
+            Goat(uint32_t horns, uint32_t extra) : horns(horns + extra), name(\"goat\") {}
+            uint32_t get_horns() const { return horns; }
+        private:
+            uint32_t horns;
+            std::string name;
+        };
+        inline uint32_t count_shared_horns(std::shared_ptr<Goat> goat) {
+            return goat->get_horns();
+        }
+    "};
+    let rs = quote! {
+        let goat = ffi::Goat::make_shared(3);
+        assert_eq!(goat.get_horns(), 3);
+        assert_eq!(ffi::count_shared_horns(goat.clone()), 3);
+        let weak = goat.downgrade();
+        assert_eq!(weak.upgrade().as_ref().unwrap().get_horns(), 3);
+        drop(goat);
+        assert!(weak.upgrade().is_null());
+        let goat = ffi::Goat::make_shared1(3, 4);
+        assert_eq!(ffi::count_shared_horns(goat), 7);
+    };
+    run_test("", hdr, rs, &["count_shared_horns", "Goat"], &[]);
+}
+
+#[test]
+fn test_make_shared_immovable() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        class Goat {
+        public:
+            Goat() : horns(2) {}
+            Goat(Goat&&) = delete;
+            uint32_t get_horns() const { return horns; }
+        private:
+            uint32_t horns;
+        };
+        inline uint32_t count_weak_horns(std::weak_ptr<Goat> goat) {
+            return goat.lock()->get_horns();
+        }
+    "};
+    let rs = quote! {
+        let goat = ffi::Goat::make_shared();
+        assert_eq!(goat.get_horns(), 2);
+        assert_eq!(ffi::count_weak_horns(goat.downgrade()), 2);
+    };
+    run_test("", hdr, rs, &["count_weak_horns", "Goat"], &[]);
+}
+
+#[test]
+fn test_make_shared_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        struct Horns {
+            Horns(uint32_t count) : count(count) {}
+            uint32_t count;
+        };
+        inline uint32_t count_shared_horns(std::shared_ptr<Horns> horns) {
+            return horns->count;
+        }
+    "};
+    let rs = quote! {
+        let horns = ffi::Horns::make_shared(2);
+        assert_eq!(horns.count, 2);
+        assert_eq!(ffi::count_shared_horns(horns), 2);
+    };
+    run_test("", hdr, rs, &["count_shared_horns"], &["Horns"]);
+}
+
+#[test]
+fn test_within_shared_ptr() {
+    // Goat isn't used within a std::shared_ptr, so has no make_shared
+    // functions, but can still be moved into one.
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <string>
+        class Goat {
+        public:
+            Goat(uint32_t horns) : horns(horns), name(\"goat\") {}
+            uint32_t get_horns() const { return horns; }
+        private:
+            uint32_t horns;
+            std::string name;
+        };
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let goat = ffi::Goat::new(3).within_shared_ptr();
+        assert_eq!(goat.get_horns(), 3);
+        let weak = goat.downgrade();
+        assert_eq!(weak.upgrade().as_ref().unwrap().get_horns(), 3);
+        drop(goat);
+        assert!(weak.upgrade().is_null());
+    };
+    run_test("", hdr, rs, &["Goat"], &[]);
+}
+
+#[test]
+fn test_within_shared_ptr_pod() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Horns {
+            uint32_t count;
+        };
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let horns = ffi::Horns { count: 2 }.within_shared_ptr();
+        assert_eq!(horns.count, 2);
+    };
+    run_test("", hdr, rs, &[], &["Horns"]);
+}
+
+#[test]
+fn test_collect_string() {
+    let hdr = indoc! {"
+        #include <string>
+        inline size_t get_len(const std::string& str) { return str.size(); }
+    "};
+    let rs = quote! {
+        let str_ = ffi::collect_string(["Hello", ", ", "world"]);
+        assert_eq!(str_.to_str().unwrap(), "Hello, world");
+        assert_eq!(ffi::get_len(&str_), 12);
+    };
+    run_test("", hdr, rs, &["get_len"], &[]);
+}
+
+#[test]
+fn test_iterate_begin_end_value_type() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <vector>
+        class Bag {
+        public:
+            typedef uint32_t value_type;
+            Bag() : items { 3, 4, 5 } {}
+            std::vector<uint32_t>::const_iterator begin() const { return items.begin(); }
+            std::vector<uint32_t>::const_iterator end() const { return items.end(); }
+        private:
+            std::vector<uint32_t> items;
+        };
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let bag = ffi::Bag::new().within_unique_ptr();
+        let items: Vec<u32> = bag.iter().copied().collect();
+        assert_eq!(items, vec![3, 4, 5]);
+        let mut total = 0;
+        for item in bag.as_ref().unwrap() {
+            total += item;
+        }
+        assert_eq!(total, 12);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Bag")
+            iterable!("Bag")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_iterate_begin_end_value_type_needs_iterable() {
+    // Dereferencing a `std::vector<bool>` iterator gives a proxy, so we
+    // mustn't generate `iter()` unless asked to.
+    let hdr = indoc! {"
+        #include <vector>
+        class Flags {
+        public:
+            typedef bool value_type;
+            Flags() : flags { true, false } {}
+            std::vector<bool>::const_iterator begin() const { return flags.begin(); }
+            std::vector<bool>::const_iterator end() const { return flags.end(); }
+            bool first() const { return flags[0]; }
+        private:
+            std::vector<bool> flags;
+        };
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let flags = ffi::Flags::new().within_unique_ptr();
+        assert!(flags.first());
+    };
+    run_test("", hdr, rs, &["Flags"], &[]);
+}
+
+#[test]
+fn test_iterate_begin_end_proxy_iterable() {
+    let hdr = indoc! {"
+        #include <vector>
+        class Flags {
+        public:
+            typedef bool value_type;
+            Flags() : flags { true, false } {}
+            std::vector<bool>::const_iterator begin() const { return flags.begin(); }
+            std::vector<bool>::const_iterator end() const { return flags.end(); }
+        private:
+            std::vector<bool> flags;
+        };
+    "};
+    let rs = quote! {};
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("Flags")
+            iterable!("Flags")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_iterate_begin_end_pointers() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        class Path {
+        public:
+            Path() : points { { 1, 2 }, { 3, 4 } } {}
+            const Point* begin() const { return points; }
+            const Point* end() const { return points + 2; }
+        private:
+            Point points[2];
+        };
+    "};
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let path = ffi::Path::new().within_unique_ptr();
+        let xs: Vec<u32> = path.iter().map(|point| point.x).collect();
+        assert_eq!(xs, vec![1, 3]);
+    };
+    run_test("", hdr, rs, &["Path"], &["Point"]);
+}
+
+#[test]
+fn test_unique_ptr_custom_deleter() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        #include <memory>
+        struct Handle {
+            uint32_t id;
+        };
+        inline uint32_t& handles_closed() {
+            static uint32_t count = 0;
+            return count;
+        }
+        struct HandleCloser {
+            void operator()(Handle* handle) const {
+                handles_closed()++;
+                delete handle;
+            }
+        };
+        inline std::unique_ptr<Handle, HandleCloser> open_handle(uint32_t id) {
+            return std::unique_ptr<Handle, HandleCloser>(new Handle { id });
+        }
+        inline uint32_t get_handles_closed() { return handles_closed(); }
+    "};
+    let rs = quote! {
+        let handle = ffi::open_handle(7);
+        assert!(!handle.as_ref().unwrap().is_null());
+        assert_eq!(handle.as_ref().unwrap().get().unwrap().id, 7);
+        assert_eq!(ffi::get_handles_closed(), 0);
+        drop(handle);
+        assert_eq!(ffi::get_handles_closed(), 1);
+    };
+    run_test(
+        "",
+        hdr,
+        rs,
+        &["open_handle", "get_handles_closed"],
+        &["Handle"],
+    );
+}
+
+#[test]
+fn test_unique_ptr_array() {
     let hdr = indoc! {"
-        namespace __foo {
-            struct daft {
-                struct bob {
-                    int a;
-                };
-                int a;
-            };
+        #include <cstdint>
+        #include <memory>
+        inline std::unique_ptr<uint32_t[]> make_squares(uint32_t count) {
+            std::unique_ptr<uint32_t[]> squares(new uint32_t[count]);
+            for (uint32_t i = 0; i < count; i++) {
+                squares[i] = i * i;
+            }
+            return squares;
+        }
+        inline std::unique_ptr<uint32_t[]> make_squares_null() {
+            return nullptr;
         }
-        inline void bar(__foo::daft::bob) {}
     "};
-    let rs = quote! {};
-    run_test("", hdr, rs, &["bar"], &[]);
+    let rs = quote! {
+        let mut squares = ffi::make_squares(4);
+        assert!(!squares.as_ref().unwrap().is_null());
+        assert_eq!(unsafe { *squares.as_ref().unwrap().get_unchecked(3) }, 9);
+        unsafe { *squares.pin_mut().get_unchecked_mut(3).get_mut() = 10 };
+        let slice = unsafe { squares.as_ref().unwrap().as_slice(4) };
+        assert_eq!(slice, &[0, 1, 4, 10]);
+        assert_eq!(slice.get(4), None);
+        let empty = ffi::make_squares_null();
+        assert!(empty.as_ref().unwrap().is_null());
+        assert!(unsafe { empty.as_ref().unwrap().as_slice(4) }.is_empty());
+    };
+    run_test("", hdr, rs, &["make_squares", "make_squares_null"], &[]);
 }
 
 #[test]
-fn test_blocklist_not_overly_broad() {
-    // This is a regression test. We used to block anything that starts with "rust" or "std",
-    // not just items in the "rust" and "std" namespaces. We therefore test that functions starting
-    // with "rust" or "std" get imported.
+fn test_std_array_pod_field_signed() {
     let hdr = indoc! {"
-    inline void rust_func() { }
-    inline void std_func() { }
+        #include <array>
+        #include <cstdint>
+        struct Samples {
+            std::array<int32_t, 4> values;
+        };
+        inline int32_t first(Samples s) {
+            return s.values[0];
+        }
     "};
     let rs = quote! {
-        ffi::rust_func();
-        ffi::std_func();
+        let samples = ffi::Samples {
+            values: [-1, -2, -3, -4],
+        };
+        assert_eq!(ffi::first(samples), -1);
     };
-    run_test("", hdr, rs, &["rust_func", "std_func"], &[]);
+    run_test("", hdr, rs, &["first"], &["Samples"]);
 }
 
 #[test]
-#[ignore] // https://github.com/google/autocxx/issues/837
-fn test_ref_qualified_method() {
+fn test_std_array_pod_field_float() {
     let hdr = indoc! {"
-        struct A {
-            void foo() & {}
+        #include <array>
+        struct Samples {
+            std::array<float, 3> values;
         };
+        inline float first(Samples s) {
+            return s.values[0];
+        }
     "};
     let rs = quote! {
-        A::new().within_unique_ptr().foo();
+        let samples = ffi::Samples {
+            values: [1.5, 2.5, 3.5],
+        };
+        assert_eq!(ffi::first(samples), 1.5);
     };
-    run_test("", hdr, rs, &["A"], &[]);
+    run_test("", hdr, rs, &["first"], &["Samples"]);
 }
 
-#[cfg_attr(skip_windows_msvc_failing_tests, ignore)]
-#[cfg_attr(skip_windows_gnu_failing_tests, ignore)]
 #[test]
-fn test_stringview() {
-    // Test that APIs using std::string_view do not otherwise cause errors.
+fn test_std_array_pod_field_struct() {
     let hdr = indoc! {"
-        #include <string_view>
-        #include <string>
-        void take_string_view(std::string_view) {}
-        std::string_view return_string_view(const std::string& a) { return std::string_view(a); }
+        #include <array>
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        struct Path {
+            std::array<Point, 2> points;
+        };
+        inline uint32_t first_x(Path p) {
+            return p.points[0].x;
+        }
     "};
-    let rs = quote! {};
-    run_test_ex(
-        "",
-        hdr,
-        rs,
-        directives_from_lists(&["take_string_view", "return_string_view"], &[], None),
-        make_cpp17_adder(),
-        None,
-        None,
-    );
+    let rs = quote! {
+        let path = ffi::Path {
+            points: [ffi::Point { x: 1, y: 2 }, ffi::Point { x: 3, y: 4 }],
+        };
+        assert_eq!(ffi::first_x(path), 1);
+    };
+    run_test("", hdr, rs, &["first_x"], &["Path", "Point"]);
 }
 
 #[test]
-fn test_include_cpp_alone() {
+fn test_std_array_by_value() {
     let hdr = indoc! {"
+        #include <array>
         #include <cstdint>
-        inline uint32_t give_int() {
-            return 5;
+        inline std::array<uint32_t, 3> make_array() {
+            return {{1, 2, 3}};
+        }
+        inline uint32_t sum(std::array<uint32_t, 3> a) {
+            return a[0] + a[1] + a[2];
         }
     "};
-    let hexathorpe = Token![#](Span::call_site());
     let rs = quote! {
-        use autocxx::include_cpp;
-        include_cpp! {
-            #hexathorpe include "input.h"
-            safety!(unsafe_ffi)
-            generate!("give_int")
-        }
-        fn main() {
-            assert_eq!(ffi::give_int(), 5);
-        }
+        let a = ffi::make_array();
+        assert_eq!(a, [1, 2, 3]);
+        assert_eq!(ffi::sum([4, 5, 6]), 15);
     };
-    do_run_test_manual("", hdr, rs, None, None).unwrap();
+    run_test("", hdr, rs, &["make_array", "sum"], &[]);
 }
 
 #[test]
-fn test_include_cpp_in_path() {
+fn test_std_array_by_const_ref() {
     let hdr = indoc! {"
+        #include <array>
         #include <cstdint>
-        inline uint32_t give_int() {
-            return 5;
+        inline uint64_t last(const std::array<uint64_t, 2>& a) {
+            return a[1];
         }
     "};
-    let hexathorpe = Token![#](Span::call_site());
     let rs = quote! {
-            autocxx::include_cpp! {
-                #hexathorpe include "input.h"
-                safety!(unsafe_ffi)
-                generate!("give_int")
-            }
-            fn main() {
-                assert_eq!(ffi::give_int(), 5);
-            }
+        assert_eq!(ffi::last(&[7, 8]), 8);
     };
-    do_run_test_manual("", hdr, rs, None, None).unwrap();
+    run_test("", hdr, rs, &["last"], &[]);
 }
 
 #[test]
-fn test_bitset() {
+fn test_std_array_opaque() {
     let hdr = indoc! {"
-        #include <cstddef>
-        template <size_t _N_words, size_t _Size>
-        class __bitset
-        {
-        public:
-            typedef size_t              __storage_type;
-            __storage_type __first_[_N_words];
-            inline bool all() {
-                return false;
-            }
+        #include <array>
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
         };
-
-        template <size_t _Size>
-        class bitset
-            : private __bitset<_Size == 0 ? 0 : (_Size - 1) / (sizeof(size_t) * 8) + 1, _Size>
-        {
-        public:
-            static const unsigned __n_words = _Size == 0 ? 0 : (_Size - 1) / (sizeof(size_t) * 8) + 1;
-            typedef __bitset<__n_words, _Size> base;
-            bool all() const noexcept;
+        struct Path {
+            std::array<Point, 2> points;
+            const std::array<Point, 2>& get_points() const { return points; }
         };
+    "};
+    let rs = quote! {
+        let path = ffi::Path {
+            points: [ffi::Point { x: 1, y: 2 }, ffi::Point { x: 3, y: 4 }],
+        };
+        let points = path.get_points();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points.get(1).unwrap().x, 3);
+        assert!(points.get(2).is_none());
+        assert_eq!(points.iter().map(|p| p.y).sum::<u32>(), 6);
+        assert_eq!(points.as_slice()[0].y, 2);
+    };
+    run_test("", hdr, rs, &[], &["Path", "Point"]);
+}
 
-
-        typedef bitset<1> mybitset;
+#[test]
+fn test_std_array_opaque_non_pod() {
+    let hdr = indoc! {"
+        #include <array>
+        #include <string>
+        struct Names {
+            Names() : names{{\"a\", \"b\"}} {}
+            std::array<std::string, 2> names;
+            const std::array<std::string, 2>& get_names() const { return names; }
+        };
     "};
-    run_generate_all_test(hdr);
+    let rs = quote! {
+        use autocxx::prelude::*;
+        let names = ffi::Names::new().within_unique_ptr();
+        let names = names.get_names();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get(1).unwrap().to_str().unwrap(), "b");
+    };
+    run_test("", hdr, rs, &["Names"], &[]);
 }
 
 #[test]
-fn test_cint_vector() {
+fn test_std_array_non_pod_field_signed() {
     let hdr = indoc! {"
-        #include <vector>
+        #include <array>
         #include <cstdint>
-        inline std::vector<int32_t> give_vec() {
-            return std::vector<int32_t> {1,2};
-        }
+        struct Samples {
+            Samples() : values{{-1, -2}} {}
+            std::array<int32_t, 2> values;
+            int32_t first() const { return values[0]; }
+        };
     "};
-
     let rs = quote! {
-        assert_eq!(ffi::give_vec().as_ref().unwrap().as_slice(), &[1,2]);
+        use autocxx::prelude::*;
+        let samples = ffi::Samples::new().within_unique_ptr();
+        assert_eq!(samples.first(), -1);
     };
-
-    run_test("", hdr, rs, &["give_vec"], &[]);
+    run_test("", hdr, rs, &["Samples"], &[]);
 }
 
 #[test]
@@ -7309,6 +8913,31 @@ fn test_cpp17() {
     );
 }
 
+#[test]
+#[cfg_attr(skip_windows_msvc_failing_tests, ignore)]
+fn test_cpp_standard_check() {
+    let hdr = indoc! {"
+        #include <string>
+        struct A {
+            A() {}
+            std::string b;
+        };
+    "};
+    run_test_ex(
+        "",
+        hdr,
+        quote! {
+            ffi::A::new().within_unique_ptr();
+        },
+        quote! {
+            generate!("A")
+        },
+        make_cpp17_adder(),
+        Some(Box::new(CppMatcher::new(&["201703L"], &[]))),
+        None,
+    );
+}
+
 #[test]
 fn test_box_extern_rust_type() {
     let hdr = indoc! {"
@@ -9720,6 +11349,99 @@ fn test_generate_ns() {
     );
 }
 
+#[test]
+fn test_generate_pattern() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    namespace A {
+        struct BlueWidget { uint32_t a; };
+        struct RedWidget { uint32_t b; };
+        struct WidgetFactory {};
+        inline uint32_t foo_widget(const BlueWidget& w) { return w.a; }
+        inline void bar_widget() {}
+    }
+    "};
+    let rs = quote! {
+        let w = ffi::A::BlueWidget::new().within_unique_ptr();
+        ffi::A::foo_widget(&w);
+        ffi::A::RedWidget::new().within_unique_ptr();
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate!("A::*Widget")
+            generate!("A::*_widget")
+            block!("A::bar_*")
+            safety!(unsafe_ffi)
+        },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_include_config() {
+    let hdr = indoc! {"
+        #include <cstdint>
+        struct Point {
+            uint32_t x;
+            uint32_t y;
+        };
+        inline uint32_t give_int() { return 5; }
+    "};
+    let config_dir = tempfile::tempdir().unwrap();
+    let config_path = config_dir.path().join("bindings.toml");
+    std::fs::write(
+        &config_path,
+        "generate = [\"give_int\"]\npod = [\"Point\"]\n",
+    )
+    .unwrap();
+    let config_path = config_path.to_str().unwrap();
+    let rs = quote! {
+        assert_eq!(ffi::give_int(), 5);
+        let point = ffi::Point { x: 1, y: 2 };
+        assert_eq!(point.x + point.y, 3);
+    };
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        quote! { include_config!(#config_path) },
+        None,
+        None,
+        None,
+    );
+}
+
+#[test]
+fn test_block_pattern_field_by_value() {
+    // A type blocked by a pattern can't be POD, so nor can a type which
+    // contains it by value.
+    let hdr = indoc! {"
+    #include <cstdint>
+    namespace A {
+        struct SecretInner { uint32_t a; };
+        struct Outer { SecretInner inner; };
+    }
+    "};
+    let rs = quote! {};
+    run_test_expect_fail_ex(
+        "",
+        hdr,
+        rs,
+        quote! {
+            generate_pod!("A::Outer")
+            block!("A::Secret*")
+        },
+        None,
+        None,
+        None,
+    );
+}
+
 #[test]
 fn test_no_constructor_make_unique_ns() {
     let hdr = indoc! {"
@@ -9783,6 +11505,61 @@ fn test_suppress_system_includes() {
     );
 }
 
+#[test]
+fn test_cpp_file_shards() {
+    let hdr = indoc! {"
+    #include <cstdint>
+    #include <string>
+    #include <memory>
+    inline uint32_t a() { return 1; }
+    inline uint32_t b(uint32_t x) { return x + 2; }
+    inline std::unique_ptr<std::string> c() { return std::make_unique<std::string>(\"c\"); }
+    struct D {
+        D() : e(4) {}
+        uint32_t get_e() const { return e; }
+        uint32_t e;
+    };
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::a(), 1);
+        assert_eq!(ffi::b(1), 3);
+        assert_eq!(ffi::c().as_ref().unwrap().to_str().unwrap(), "c");
+        let d = ffi::D::new().within_unique_ptr();
+        assert_eq!(d.get_e(), 4);
+    };
+    // One file from cxx, plus three shards of autocxx wrapper functions.
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["a", "b", "c", "D"], &[], None),
+        Some(Box::new(SetCppFileShards(3))),
+        Some(Box::new(CppImplementationFileCounter(4))),
+        None,
+    );
+}
+
+#[test]
+fn test_cpp_file_shards_more_than_functions() {
+    let hdr = indoc! {"
+    #include <string>
+    inline std::string greet() { return \"hello\"; }
+    "};
+    let rs = quote! {
+        assert_eq!(ffi::greet().as_ref().unwrap().to_str().unwrap(), "hello");
+    };
+    // One file from cxx, plus a single file for the only wrapper function.
+    run_test_ex(
+        "",
+        hdr,
+        rs,
+        directives_from_lists(&["greet"], &[], None),
+        Some(Box::new(SetCppFileShards(8))),
+        Some(Box::new(CppImplementationFileCounter(2))),
+        None,
+    );
+}
+
 #[test]
 fn test_no_rvo_move() {
     let hdr = indoc! {"
@@ -11,6 +11,7 @@ use indexmap::set::IndexSet as HashSet;
 use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 
 use itertools::Itertools;
 use proc_macro2::Span;
@@ -20,6 +21,7 @@ use quote::ToTokens;
 use quote::format_ident;
 use syn::{
     parse::{Parse, ParseStream},
+    punctuated::Punctuated,
     Signature, Token, TypePath,
 };
 use syn::{Ident, Result as ParseResult};
@@ -90,11 +92,85 @@ impl UnsafePolicy {
     }
 }
 
+/// How to name the Rust equivalents of C++ functions, methods and types.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Default)]
+pub enum NamingPolicy {
+    /// Keep the C++ spelling.
+    #[default]
+    Cpp,
+    /// Use snake_case for functions and methods, and UpperCamelCase for types.
+    Rust,
+}
+
+impl Parse for NamingPolicy {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let id: syn::Ident = input.parse()?;
+        if id == "cpp" {
+            Ok(NamingPolicy::Cpp)
+        } else if id == "rust" {
+            Ok(NamingPolicy::Rust)
+        } else {
+            Err(syn::Error::new(id.span(), "expected cpp or rust"))
+        }
+    }
+}
+
+impl ToTokens for NamingPolicy {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            NamingPolicy::Cpp => tokens.extend(quote! { cpp }),
+            NamingPolicy::Rust => tokens.extend(quote! { rust }),
+        }
+    }
+}
+
+/// How to name the Rust equivalents of overloaded C++ functions and methods.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Default)]
+pub enum OverloadNaming {
+    /// `func`, `func1`, `func2` etc. in the order in which they're found.
+    #[default]
+    Sequential,
+    /// Suffixes derived from the parameter types, e.g. `set_i32`, `set_str`.
+    Types,
+    /// Suffixes derived from the number of parameters, e.g. `set_1`, `set_2`.
+    Count,
+}
+
+impl Parse for OverloadNaming {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let id: syn::Ident = input.parse()?;
+        if id == "sequential" {
+            Ok(OverloadNaming::Sequential)
+        } else if id == "types" {
+            Ok(OverloadNaming::Types)
+        } else if id == "count" {
+            Ok(OverloadNaming::Count)
+        } else {
+            Err(syn::Error::new(
+                id.span(),
+                "expected sequential, types or count",
+            ))
+        }
+    }
+}
+
+impl ToTokens for OverloadNaming {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            OverloadNaming::Sequential => tokens.extend(quote! { sequential }),
+            OverloadNaming::Types => tokens.extend(quote! { types }),
+            OverloadNaming::Count => tokens.extend(quote! { count }),
+        }
+    }
+}
+
 /// An entry in the allowlist.
 #[derive(Hash, Debug)]
 pub enum AllowlistEntry {
     Item(String),
     Namespace(String),
+    /// A glob pattern such as `mylib::widgets::*Widget`.
+    Pattern(String),
 }
 
 impl AllowlistEntry {
@@ -102,10 +178,64 @@ impl AllowlistEntry {
         match self {
             AllowlistEntry::Item(i) => i.clone(),
             AllowlistEntry::Namespace(ns) => format!("{ns}::.*"),
+            AllowlistEntry::Pattern(pattern) => glob_to_regex(pattern),
         }
     }
 }
 
+/// Whether this name contains glob wildcards, i.e. `*` (matching any
+/// sequence of characters, including `::`) or `?` (matching any single
+/// character).
+pub(crate) fn is_glob(name: &str) -> bool {
+    name.contains(['*', '?'])
+}
+
+/// Whether a C++ name matches a glob pattern. Patterns without wildcards
+/// must match exactly.
+fn glob_matches(pattern: &str, cpp_name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let cpp_name: Vec<char> = cpp_name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where to resume if the characters after the most recent '*' stop matching.
+    let mut backtrack = None;
+    while n < cpp_name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == cpp_name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Converts a glob pattern into the equivalent regex for bindgen.
+fn glob_to_regex(pattern: &str) -> String {
+    pattern
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            '\\' | '.' | '+' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' => {
+                format!("\\{c}")
+            }
+            c => c.to_string(),
+        })
+        .collect()
+}
+
 /// Allowlist configuration.
 #[derive(Hash, Debug)]
 pub enum Allowlist {
@@ -209,24 +339,263 @@ impl std::hash::Hash for ConcretesMap {
     }
 }
 
-#[derive(Debug, Default, Hash)]
+/// Hashes the entries of a map in key order, such that the hash doesn't
+/// depend upon the order in which the corresponding directives were written.
+fn hash_in_key_order<V: Hash, H: Hasher>(map: &HashMap<String, V>, state: &mut H) {
+    for (k, v) in map.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+        k.hash(state);
+        v.hash(state);
+    }
+}
+
+/// Which auto traits the user has asserted, using `thread_safe!`, are
+/// sound for an opaque C++ type.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ThreadSafety {
+    pub send: bool,
+    pub sync: bool,
+}
+
+impl Parse for ThreadSafety {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let mut thread_safety = ThreadSafety::default();
+        let traits = Punctuated::<syn::Ident, syn::token::Comma>::parse_separated_nonempty(input)?;
+        for id in traits {
+            if id == "Send" {
+                thread_safety.send = true;
+            } else if id == "Sync" {
+                thread_safety.sync = true;
+            } else {
+                return Err(syn::Error::new(id.span(), "expected Send or Sync"));
+            }
+        }
+        Ok(thread_safety)
+    }
+}
+
+impl ToTokens for ThreadSafety {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let mut traits = Vec::new();
+        if self.send {
+            traits.push(quote! { Send });
+        }
+        if self.sync {
+            traits.push(quote! { Sync });
+        }
+        tokens.extend(quote! { #(#traits),* })
+    }
+}
+
+/// Newtype wrapper so we can implement Hash.
+#[derive(Debug, Default)]
+pub struct ThreadSafetyMap(pub HashMap<String, ThreadSafety>);
+
+impl std::hash::Hash for ThreadSafetyMap {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_in_key_order(&self.0, state)
+    }
+}
+
+/// A trait which may be derived for a POD type using `pod_derive!`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum PodDerive {
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+}
+
+impl std::fmt::Display for PodDerive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_token_stream().to_string().replace(' ', ""))
+    }
+}
+
+impl Parse for PodDerive {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let path: syn::Path = input.parse()?;
+        let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let segments: Vec<_> = segments.iter().map(String::as_str).collect();
+        match segments.as_slice() {
+            ["Clone"] => Ok(PodDerive::Clone),
+            ["Copy"] => Ok(PodDerive::Copy),
+            ["Debug"] => Ok(PodDerive::Debug),
+            ["PartialEq"] => Ok(PodDerive::PartialEq),
+            ["Eq"] => Ok(PodDerive::Eq),
+            ["Hash"] => Ok(PodDerive::Hash),
+            ["Default"] => Ok(PodDerive::Default),
+            ["serde", "Serialize"] => Ok(PodDerive::Serialize),
+            ["serde", "Deserialize"] => Ok(PodDerive::Deserialize),
+            _ => Err(syn::Error::new_spanned(
+                path,
+                "expected Clone, Copy, Debug, PartialEq, Eq, Hash, Default, serde::Serialize or serde::Deserialize",
+            )),
+        }
+    }
+}
+
+impl ToTokens for PodDerive {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            PodDerive::Clone => tokens.extend(quote! { Clone }),
+            PodDerive::Copy => tokens.extend(quote! { Copy }),
+            PodDerive::Debug => tokens.extend(quote! { Debug }),
+            PodDerive::PartialEq => tokens.extend(quote! { PartialEq }),
+            PodDerive::Eq => tokens.extend(quote! { Eq }),
+            PodDerive::Hash => tokens.extend(quote! { Hash }),
+            PodDerive::Default => tokens.extend(quote! { Default }),
+            PodDerive::Serialize => tokens.extend(quote! { serde::Serialize }),
+            PodDerive::Deserialize => tokens.extend(quote! { serde::Deserialize }),
+        }
+    }
+}
+
+/// Newtype wrapper so we can implement Hash.
+#[derive(Debug, Default)]
+pub struct PodDerivesMap(pub HashMap<String, Vec<PodDerive>>);
+
+impl std::hash::Hash for PodDerivesMap {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_in_key_order(&self.0, state)
+    }
+}
+
+/// Newtype wrapper so we can implement Hash.
+/// Keys are C++ names or signatures, as normalized by
+/// [`normalize_cpp_signature`].
+#[derive(Debug, Default)]
+pub struct RenamesMap(pub HashMap<String, Ident>);
+
+impl std::hash::Hash for RenamesMap {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_in_key_order(&self.0, state)
+    }
+}
+
+/// Removes insignificant whitespace from a C++ name or function signature,
+/// so that `ns::A::foo(const std::string &)` and `ns::A::foo(const std::string&)`
+/// are considered the same.
+pub(crate) fn normalize_cpp_signature(sig: &str) -> String {
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+    let mut output = String::new();
+    let mut whitespace_pending = false;
+    for c in sig.chars() {
+        if c.is_whitespace() {
+            whitespace_pending = true;
+            continue;
+        }
+        if whitespace_pending && is_word_char(c) && output.ends_with(is_word_char) {
+            output.push(' ');
+        }
+        whitespace_pending = false;
+        output.push(c);
+    }
+    output
+}
+
+#[derive(Debug, Default)]
 pub struct IncludeCppConfig {
     pub inclusions: Vec<String>,
     pub unsafe_policy: UnsafePolicy,
+    pub naming_policy: NamingPolicy,
+    pub overload_naming: OverloadNaming,
     pub parse_only: bool,
     pub exclude_impls: bool,
     pub(crate) pod_requests: Vec<String>,
     pub allowlist: Allowlist,
     pub(crate) blocklist: Vec<String>,
     pub(crate) constructor_blocklist: Vec<String>,
+    pub(crate) safe_functions: Vec<String>,
+    pub(crate) unsafe_functions: Vec<String>,
     pub instantiable: Vec<String>,
+    pub(crate) iterables: Vec<String>,
     pub(crate) exclude_utilities: bool,
     pub(crate) mod_name: Option<Ident>,
     pub rust_types: Vec<RustPath>,
     pub subclasses: Vec<Subclass>,
     pub extern_rust_funs: Vec<RustFun>,
     pub concretes: ConcretesMap,
+    pub(crate) renames: RenamesMap,
+    pub(crate) thread_safe: ThreadSafetyMap,
+    pub(crate) pod_derives: PodDerivesMap,
     pub externs: ExternCppTypeMap,
+    pub(crate) defines: Vec<(String, Option<String>)>,
+    pub(crate) clang_args: Vec<String>,
+    pub(crate) config_files: Vec<PathBuf>,
+    pub(crate) hash_override: Option<u64>,
+}
+
+impl Hash for IncludeCppConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Destructure so that we have to decide about any new fields.
+        let Self {
+            inclusions,
+            unsafe_policy,
+            naming_policy,
+            overload_naming,
+            parse_only,
+            exclude_impls,
+            pod_requests,
+            allowlist,
+            blocklist,
+            constructor_blocklist,
+            safe_functions,
+            unsafe_functions,
+            instantiable,
+            iterables,
+            exclude_utilities,
+            mod_name,
+            rust_types,
+            subclasses,
+            extern_rust_funs,
+            concretes,
+            renames,
+            thread_safe,
+            pod_derives,
+            externs,
+            defines,
+            clang_args,
+            // The contents of configuration files have been merged into
+            // the fields above, so we've already hashed them. Their paths
+            // are absolute, so depend on where the crate is checked out.
+            config_files: _,
+            // This replaces the hash, so isn't part of it.
+            hash_override: _,
+        } = self;
+        inclusions.hash(state);
+        unsafe_policy.hash(state);
+        naming_policy.hash(state);
+        overload_naming.hash(state);
+        parse_only.hash(state);
+        exclude_impls.hash(state);
+        pod_requests.hash(state);
+        allowlist.hash(state);
+        blocklist.hash(state);
+        constructor_blocklist.hash(state);
+        safe_functions.hash(state);
+        unsafe_functions.hash(state);
+        instantiable.hash(state);
+        iterables.hash(state);
+        exclude_utilities.hash(state);
+        mod_name.hash(state);
+        rust_types.hash(state);
+        subclasses.hash(state);
+        extern_rust_funs.hash(state);
+        concretes.hash(state);
+        renames.hash(state);
+        thread_safe.hash(state);
+        pod_derives.hash(state);
+        externs.hash(state);
+        defines.hash(state);
+        clang_args.hash(state);
+    }
 }
 
 impl Parse for IncludeCppConfig {
@@ -287,22 +656,24 @@ impl IncludeCppConfig {
         self.exclude_utilities
     }
 
-    /// Items which the user has explicitly asked us to generate;
-    /// we should raise an error if we weren't able to do so.
+    /// Items which the user has explicitly asked us to generate, or to
+    /// derive traits for; we should raise an error if we weren't able to
+    /// do so.
     pub fn must_generate_list(&self) -> Box<dyn Iterator<Item = String> + '_> {
+        let pod_items = self.pod_requests.iter().chain(self.pod_derives.0.keys());
         if let Allowlist::Specific(items) = &self.allowlist {
             Box::new(
                 items
                     .iter()
                     .filter_map(|i| match i {
                         AllowlistEntry::Item(i) => Some(i),
-                        AllowlistEntry::Namespace(_) => None,
+                        AllowlistEntry::Namespace(_) | AllowlistEntry::Pattern(_) => None,
                     })
-                    .chain(self.pod_requests.iter())
+                    .chain(pod_items)
                     .cloned(),
             )
         } else {
-            Box::new(self.pod_requests.iter().cloned())
+            Box::new(pod_items.cloned())
         }
     }
 
@@ -370,31 +741,124 @@ impl IncludeCppConfig {
                 Allowlist::Specific(items) => items.iter().any(|entry| match entry {
                     AllowlistEntry::Item(i) => i == cpp_name,
                     AllowlistEntry::Namespace(ns) => cpp_name.starts_with(ns),
+                    AllowlistEntry::Pattern(pattern) => glob_matches(pattern, cpp_name),
                 }),
             }
     }
 
     pub fn is_on_blocklist(&self, cpp_name: &str) -> bool {
-        self.blocklist.contains(&cpp_name.to_string())
+        self.blocklist
+            .iter()
+            .any(|item| glob_matches(item, cpp_name))
+    }
+
+    /// Whether the user has used `safe!` or `unsafe_fn!` to override the
+    /// overall safety policy for a function. `type_cpp_name` should be
+    /// given for methods, since these directives may name a whole type.
+    /// A directive naming the function itself takes precedence.
+    pub fn get_safety_override(
+        &self,
+        fn_cpp_name: &str,
+        type_cpp_name: Option<&str>,
+    ) -> Option<UnsafePolicy> {
+        let find = |cpp_name: &str| {
+            if self
+                .unsafe_functions
+                .iter()
+                .any(|item| glob_matches(item, cpp_name))
+            {
+                Some(UnsafePolicy::AllFunctionsUnsafe)
+            } else if self
+                .safe_functions
+                .iter()
+                .any(|item| glob_matches(item, cpp_name))
+            {
+                Some(UnsafePolicy::AllFunctionsSafe)
+            } else {
+                None
+            }
+        };
+        find(fn_cpp_name).or_else(|| type_cpp_name.and_then(find))
     }
 
     pub fn is_on_constructor_blocklist(&self, cpp_name: &str) -> bool {
         self.constructor_blocklist.contains(&cpp_name.to_string())
     }
 
+    /// Whether the user has used `iterable!` to promise that this type's
+    /// iterators dereference to references to its elements.
+    pub fn is_iterable(&self, cpp_name: &str) -> bool {
+        self.iterables.iter().any(|item| item == cpp_name)
+    }
+
+    /// The exact names on the blocklist. Use [`Self::is_on_blocklist`]
+    /// to take account of patterns too.
     pub fn get_blocklist(&self) -> impl Iterator<Item = &String> {
-        self.blocklist.iter()
+        self.blocklist.iter().filter(|item| !is_glob(item))
+    }
+
+    /// Any Rust name requested for the given C++ item using `rename!`.
+    /// For functions, pass the C++ spelling of each parameter type,
+    /// so that we can find renames which target a specific overload.
+    pub fn get_rename(&self, cpp_name: &str, param_types: Option<&[String]>) -> Option<&Ident> {
+        param_types
+            .and_then(|param_types| self.get_signature_rename(cpp_name, param_types))
+            .or_else(|| self.renames.0.get(&normalize_cpp_signature(cpp_name)))
+    }
+
+    /// Any Rust name requested using `rename!` for the specific overload
+    /// of the given C++ function which takes these parameter types,
+    /// ignoring any `rename!` of the function by name alone.
+    pub fn get_signature_rename(&self, cpp_name: &str, param_types: &[String]) -> Option<&Ident> {
+        let sig = format!("{cpp_name}({})", param_types.join(","));
+        self.renames.0.get(&normalize_cpp_signature(&sig))
+    }
+
+    /// Whether the user has used `thread_safe!` to declare that the
+    /// given opaque type is `Send` and/or `Sync`.
+    pub fn get_thread_safety(&self, cpp_name: &str) -> ThreadSafety {
+        self.thread_safe
+            .0
+            .get(cpp_name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Any extra traits which the user has asked us to derive for
+    /// the given POD type using `pod_derive!`.
+    pub fn get_pod_derives(&self, cpp_name: &str) -> &[PodDerive] {
+        self.pod_derives
+            .0
+            .get(cpp_name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 
     fn is_concrete_type(&self, cpp_name: &str) -> bool {
         self.concretes.0.values().any(|val| *val == cpp_name)
     }
 
+    /// Extra arguments to pass to clang when parsing the headers for this
+    /// `include_cpp!` block, as specified by `define!` and `clang_arg!`
+    /// directives. These should be applied after any global arguments so
+    /// that they take precedence.
+    pub fn extra_clang_args(&self) -> impl Iterator<Item = String> + '_ {
+        self.defines
+            .iter()
+            .map(|(name, value)| match value {
+                None => format!("-D{name}"),
+                Some(value) => format!("-D{name}={value}"),
+            })
+            .chain(self.clang_args.iter().cloned())
+    }
+
     /// Get a hash of the contents of this `include_cpp!` block.
     pub fn get_hash(&self) -> u64 {
-        let mut s = DefaultHasher::new();
-        self.hash(&mut s);
-        s.finish()
+        self.hash_override.unwrap_or_else(|| {
+            let mut s = DefaultHasher::new();
+            self.hash(&mut s);
+            s.finish()
+        })
     }
 
     /// In case there are multiple sets of ffi mods in a single binary,
@@ -495,7 +959,11 @@ impl ToTokens for IncludeCppConfig {
 
 #[cfg(test)]
 mod parse_tests {
-    use crate::config::UnsafePolicy;
+    use crate::config::{NamingPolicy, OverloadNaming, PodDerive, ThreadSafety, UnsafePolicy};
+    use crate::IncludeCppConfig;
+    use itertools::Itertools;
+    use proc_macro2::Span;
+    use quote::quote;
     use syn::parse_quote;
     #[test]
     fn test_safety_unsafe() {
@@ -518,4 +986,215 @@ mod parse_tests {
         let us: UnsafePolicy = parse_quote! {};
         assert_eq!(us, UnsafePolicy::AllFunctionsUnsafe)
     }
+
+    #[test]
+    fn test_naming_policy() {
+        let config: IncludeCppConfig = parse_quote! {
+            naming!(rust)
+            generate!("Foo")
+        };
+        assert_eq!(config.naming_policy, NamingPolicy::Rust);
+        let config: IncludeCppConfig = parse_quote! {
+            generate!("Foo")
+        };
+        assert_eq!(config.naming_policy, NamingPolicy::Cpp);
+    }
+
+    #[test]
+    fn test_allowlist_patterns() {
+        let config: IncludeCppConfig = parse_quote! {
+            generate!("mylib::widgets::*Widget")
+            generate!("mylib::Thing")
+            block!("mylib::widgets::detail::*")
+        };
+        assert!(config.is_on_allowlist("mylib::widgets::BlueWidget"));
+        assert!(config.is_on_allowlist("mylib::widgets::detail::SecretWidget"));
+        assert!(!config.is_on_allowlist("mylib::widgets::WidgetFactory"));
+        assert!(config.is_on_allowlist("mylib::Thing"));
+        assert!(!config.is_on_allowlist("mylib::Things"));
+        assert!(config.is_on_blocklist("mylib::widgets::detail::SecretWidget"));
+        assert!(!config.is_on_blocklist("mylib::widgets::BlueWidget"));
+        assert_eq!(config.get_blocklist().count(), 0);
+        assert_eq!(
+            config.bindgen_allowlist().unwrap().take(2).collect_vec(),
+            vec!["mylib::widgets::.*Widget", "mylib::Thing"]
+        );
+        assert!(config
+            .must_generate_list()
+            .all(|item| item == "mylib::Thing"));
+    }
+
+    #[test]
+    fn test_safety_overrides() {
+        let config: IncludeCppConfig = parse_quote! {
+            safe!("ns::A")
+            safe!("ns::bar")
+            unsafe_fn!("ns::A::foo")
+            unsafe_fn!("ns::raw_*")
+            generate_ns!("ns")
+        };
+        assert_eq!(
+            config.get_safety_override("ns::A::foo", Some("ns::A")),
+            Some(UnsafePolicy::AllFunctionsUnsafe)
+        );
+        assert_eq!(
+            config.get_safety_override("ns::A::baz", Some("ns::A")),
+            Some(UnsafePolicy::AllFunctionsSafe)
+        );
+        assert_eq!(
+            config.get_safety_override("ns::bar", None),
+            Some(UnsafePolicy::AllFunctionsSafe)
+        );
+        assert_eq!(
+            config.get_safety_override("ns::raw_ptr", None),
+            Some(UnsafePolicy::AllFunctionsUnsafe)
+        );
+        assert_eq!(config.get_safety_override("ns::qux", None), None);
+    }
+
+    #[test]
+    fn test_hash_independent_of_directive_order() {
+        let config1: IncludeCppConfig = parse_quote! {
+            rename!("ns::foo", bar)
+            rename!("ns::baz", qux)
+            thread_safe!("ns::Cache", Send, Sync)
+            thread_safe!("ns::Handle", Send)
+            pod_derive!("ns::Point", Debug, PartialEq)
+            pod_derive!("ns::Size", Hash)
+            generate_ns!("ns")
+        };
+        let config2: IncludeCppConfig = parse_quote! {
+            pod_derive!("ns::Size", Hash)
+            pod_derive!("ns::Point", Debug, PartialEq)
+            thread_safe!("ns::Handle", Send)
+            thread_safe!("ns::Cache", Send, Sync)
+            rename!("ns::baz", qux)
+            rename!("ns::foo", bar)
+            generate_ns!("ns")
+        };
+        assert_eq!(config1.get_hash(), config2.get_hash());
+        let config3: IncludeCppConfig = parse_quote! {
+            rename!("ns::foo", bar)
+            rename!("ns::baz", qux)
+            thread_safe!("ns::Cache", Send)
+            thread_safe!("ns::Handle", Send, Sync)
+            pod_derive!("ns::Point", Debug, PartialEq)
+            pod_derive!("ns::Size", Hash)
+            generate_ns!("ns")
+        };
+        assert_ne!(config1.get_hash(), config3.get_hash());
+        let config4: IncludeCppConfig = parse_quote! {
+            rename!("ns::foo", qux)
+            rename!("ns::baz", bar)
+            thread_safe!("ns::Cache", Send, Sync)
+            thread_safe!("ns::Handle", Send)
+            pod_derive!("ns::Point", Debug, PartialEq)
+            pod_derive!("ns::Size", Hash)
+            generate_ns!("ns")
+        };
+        assert_ne!(config1.get_hash(), config4.get_hash());
+    }
+
+    #[test]
+    fn test_thread_safe() {
+        let config: IncludeCppConfig = parse_quote! {
+            thread_safe!("ns::Cache", Send, Sync)
+            thread_safe!("ns::Handle", Send)
+            generate_ns!("ns")
+        };
+        assert_eq!(
+            config.get_thread_safety("ns::Cache"),
+            ThreadSafety {
+                send: true,
+                sync: true
+            }
+        );
+        assert_eq!(
+            config.get_thread_safety("ns::Handle"),
+            ThreadSafety {
+                send: true,
+                sync: false
+            }
+        );
+        assert_eq!(
+            config.get_thread_safety("ns::Other"),
+            ThreadSafety::default()
+        );
+        assert!(syn::parse2::<IncludeCppConfig>(quote! {
+            thread_safe!("ns::Cache", Unpin)
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_pod_derive() {
+        let config: IncludeCppConfig = parse_quote! {
+            generate_pod!("Point")
+            pod_derive!("Point", Debug, PartialEq)
+            pod_derive!("Point", serde::Serialize)
+        };
+        assert_eq!(
+            config.get_pod_derives("Point"),
+            [PodDerive::Debug, PodDerive::PartialEq, PodDerive::Serialize]
+        );
+        assert!(config.get_pod_derives("Other").is_empty());
+        assert!(config.must_generate_list().any(|item| item == "Point"));
+        assert_eq!(PodDerive::Serialize.to_string(), "serde::Serialize");
+        assert!(syn::parse2::<IncludeCppConfig>(quote! {
+            pod_derive!("Point", PartialOrd)
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_overload_naming() {
+        let config: IncludeCppConfig = parse_quote! {
+            overload_naming!(types)
+            generate!("Foo")
+        };
+        assert_eq!(config.overload_naming, OverloadNaming::Types);
+        let config: IncludeCppConfig = parse_quote! {
+            generate!("Foo")
+        };
+        assert_eq!(config.overload_naming, OverloadNaming::Sequential);
+    }
+
+    #[test]
+    fn test_rename() {
+        let config: IncludeCppConfig = parse_quote! {
+            rename!("ns::A::foo(int, const std::string &)", foo_with_str)
+            rename!("ns::A::bar", baz)
+            generate!("ns::A")
+        };
+        let params = ["int".to_string(), "const std::string&".to_string()];
+        assert_eq!(
+            config.get_rename("ns::A::foo", Some(&params)).unwrap(),
+            "foo_with_str"
+        );
+        assert!(config
+            .get_rename("ns::A::foo", Some(&params[..1]))
+            .is_none());
+        assert_eq!(
+            config.get_rename("ns::A::bar", Some(&params)).unwrap(),
+            "baz"
+        );
+        assert_eq!(config.get_rename("ns::A::bar", None).unwrap(), "baz");
+        assert!(config.get_signature_rename("ns::A::bar", &params).is_none());
+    }
+
+    #[test]
+    fn test_extra_clang_args() {
+        let hexathorpe = syn::token::Pound(Span::call_site());
+        let config: IncludeCppConfig = parse_quote! {
+            #hexathorpe include "a.h"
+            define!("FOO", "1")
+            define!("BAR")
+            clang_arg!("-std=c++20")
+            generate!("Foo")
+        };
+        assert_eq!(
+            config.extra_clang_args().collect_vec(),
+            ["-DFOO=1", "-DBAR", "-std=c++20"]
+        );
+    }
 }
@@ -0,0 +1,228 @@
+// Copyright 2023 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for directives stored in a TOML file, which may be named
+//! by `include_config!` or passed to `autocxx-gen --config`. For example:
+//!
+//! ```toml
+//! safety = "unsafe_ffi"
+//! generate = ["mylib::Widget", "mylib::widgets::*Widget"]
+//! pod = ["mylib::Point"]
+//! block = ["mylib::detail::*"]
+//!
+//! [rename]
+//! "mylib::Widget::Set(const std::string&)" = "set_str"
+//!
+//! [extern_cpp_type]
+//! "base::Value" = "crate::ffi2::base::Value"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use indexmap::map::IndexMap as HashMap;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::{
+    is_glob, normalize_cpp_signature, AllowlistEntry, AllowlistErr, ExternCppType,
+    IncludeCppConfig, UnsafePolicy,
+};
+
+#[derive(Error, Debug)]
+pub enum ConfigFileErr {
+    #[error("unable to read the autocxx configuration file {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("unable to parse the autocxx configuration file {0}: {1}")]
+    Toml(PathBuf, toml::de::Error),
+    #[error("{0}: safety must be \"unsafe\", \"unsafe_ffi\" or \"unsafe_references_wrapped\", not \"{1}\"")]
+    InvalidSafety(PathBuf, String),
+    #[error("{0}: {1} is not a valid Rust identifier or path")]
+    InvalidRustName(PathBuf, String),
+    #[error("{0}: pod requires exact type names, not patterns such as {1}")]
+    PodPattern(PathBuf, String),
+    #[error("{0}: {1}")]
+    Allowlist(PathBuf, AllowlistErr),
+}
+
+/// The contents of a configuration file. Each field corresponds to
+/// the directive of the same name.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    safety: Option<String>,
+    #[serde(default)]
+    generate: Vec<String>,
+    #[serde(default)]
+    pod: Vec<String>,
+    #[serde(default)]
+    block: Vec<String>,
+    #[serde(default)]
+    rename: HashMap<String, String>,
+    #[serde(default)]
+    extern_cpp_type: HashMap<String, String>,
+}
+
+impl IncludeCppConfig {
+    /// Merges in the directives from a TOML configuration file, as if
+    /// they had been written in the `include_cpp!` block.
+    pub fn load_config_file(&mut self, path: &Path) -> Result<(), ConfigFileErr> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| ConfigFileErr::Io(path.to_path_buf(), err))?;
+        let file: ConfigFile = toml::from_str(&contents)
+            .map_err(|err| ConfigFileErr::Toml(path.to_path_buf(), err))?;
+        self.merge_config_file(path, file)?;
+        self.config_files.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Like [`Self::load_config_file`], but for a configuration file passed
+    /// to the code generator rather than named by `include_config!`. The
+    /// `include_cpp!` macro can't see such files, so we keep the hash by
+    /// which it finds the generated bindings unchanged.
+    pub fn load_external_config_file(&mut self, path: &Path) -> Result<(), ConfigFileErr> {
+        let hash = self.get_hash();
+        self.load_config_file(path)?;
+        self.hash_override.get_or_insert(hash);
+        Ok(())
+    }
+
+    /// Configuration files which have been merged into this config,
+    /// and which should therefore be treated as build dependencies.
+    pub fn config_files(&self) -> impl Iterator<Item = &Path> {
+        self.config_files.iter().map(PathBuf::as_path)
+    }
+
+    fn merge_config_file(&mut self, path: &Path, file: ConfigFile) -> Result<(), ConfigFileErr> {
+        if let Some(safety) = file.safety {
+            self.unsafe_policy = syn::parse_str::<UnsafePolicy>(&safety)
+                .map_err(|_| ConfigFileErr::InvalidSafety(path.to_path_buf(), safety))?;
+        }
+        for item in file.generate {
+            let entry = if is_glob(&item) {
+                AllowlistEntry::Pattern(item)
+            } else {
+                AllowlistEntry::Item(item)
+            };
+            self.allowlist
+                .push(entry)
+                .map_err(|err| ConfigFileErr::Allowlist(path.to_path_buf(), err))?;
+        }
+        for item in file.pod {
+            if is_glob(&item) {
+                return Err(ConfigFileErr::PodPattern(path.to_path_buf(), item));
+            }
+            self.allowlist
+                .push(AllowlistEntry::Item(item.clone()))
+                .map_err(|err| ConfigFileErr::Allowlist(path.to_path_buf(), err))?;
+            self.pod_requests.push(item);
+        }
+        self.blocklist.extend(file.block);
+        for (cpp_name, rust_name) in file.rename {
+            let rust_id = syn::parse_str(&rust_name)
+                .map_err(|_| ConfigFileErr::InvalidRustName(path.to_path_buf(), rust_name))?;
+            self.renames
+                .0
+                .insert(normalize_cpp_signature(&cpp_name), rust_id);
+        }
+        for (cpp_name, rust_path) in file.extern_cpp_type {
+            let rust_path = syn::parse_str(&rust_path)
+                .map_err(|_| ConfigFileErr::InvalidRustName(path.to_path_buf(), rust_path))?;
+            self.externs.0.insert(
+                cpp_name,
+                ExternCppType {
+                    rust_path,
+                    opaque: false,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{IncludeCppConfig, UnsafePolicy};
+
+    use super::{ConfigFile, ConfigFileErr};
+
+    fn merge(toml: &str) -> Result<IncludeCppConfig, ConfigFileErr> {
+        let file: ConfigFile = toml::from_str(toml).unwrap();
+        let mut config = IncludeCppConfig::default();
+        config.merge_config_file(Path::new("bindings.toml"), file)?;
+        Ok(config)
+    }
+
+    #[test]
+    fn test_config_file() {
+        let config = merge(
+            r#"
+            safety = "unsafe_ffi"
+            generate = ["A", "ns::*Widget"]
+            pod = ["B"]
+            block = ["ns::detail::*"]
+
+            [rename]
+            "A::foo(int)" = "foo_int"
+
+            [extern_cpp_type]
+            "base::Value" = "crate::ffi2::base::Value"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.unsafe_policy, UnsafePolicy::AllFunctionsSafe);
+        assert!(config.is_on_allowlist("A"));
+        assert!(config.is_on_allowlist("ns::BlueWidget"));
+        assert!(config.is_on_allowlist("B"));
+        assert_eq!(config.get_pod_requests(), ["B"]);
+        assert!(config.is_on_blocklist("ns::detail::Secret"));
+        assert_eq!(
+            config
+                .get_rename("A::foo", Some(&["int".to_string()]))
+                .unwrap(),
+            "foo_int"
+        );
+        assert!(config.externs.0.contains_key("base::Value"));
+    }
+
+    #[test]
+    fn test_config_file_errors() {
+        assert!(matches!(
+            merge(r#"safety = "very_unsafe""#),
+            Err(ConfigFileErr::InvalidSafety(..))
+        ));
+        assert!(matches!(
+            merge(r#"pod = ["ns::*"]"#),
+            Err(ConfigFileErr::PodPattern(..))
+        ));
+        assert!(matches!(
+            merge("[rename]\n\"A\" = \"not an ident\""),
+            Err(ConfigFileErr::InvalidRustName(..))
+        ));
+        assert!(toml::from_str::<ConfigFile>("generate_all = true").is_err());
+    }
+
+    #[test]
+    fn test_config_file_hash_independent_of_location() {
+        let hash_from = |dir: &str| {
+            let dir = std::env::temp_dir().join(format!(
+                "autocxx-config-file-test-{}-{dir}",
+                std::process::id()
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("bindings.toml");
+            std::fs::write(&path, "generate = [\"A\"]").unwrap();
+            let mut config = IncludeCppConfig::default();
+            config.load_config_file(&path).unwrap();
+            std::fs::remove_dir_all(&dir).unwrap();
+            config.get_hash()
+        };
+        assert_eq!(hash_from("a"), hash_from("b"));
+    }
+}
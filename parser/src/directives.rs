@@ -14,7 +14,7 @@ use proc_macro2::{Ident, TokenStream};
 use quote::{quote, ToTokens};
 use syn::parse::ParseStream;
 
-use crate::config::{Allowlist, AllowlistErr};
+use crate::config::{is_glob, normalize_cpp_signature, Allowlist, AllowlistErr};
 use crate::directive_names::{EXTERN_RUST_FUN, EXTERN_RUST_TYPE, SUBCLASS};
 use crate::{AllowlistEntry, IncludeCppConfig};
 use crate::{ParseResult, RustFun, RustPath};
@@ -36,6 +36,8 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
         need_exclamation.insert("generate_ns".into(), Box::new(GenerateNs));
         need_exclamation.insert("generate_all".into(), Box::new(GenerateAll));
         need_exclamation.insert("safety".into(), Box::new(Safety));
+        need_exclamation.insert("naming".into(), Box::new(Naming));
+        need_exclamation.insert("overload_naming".into(), Box::new(OverloadNaming));
         need_exclamation.insert(
             "pod".into(),
             Box::new(StringList(
@@ -50,6 +52,20 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
                 |config| &config.blocklist,
             )),
         );
+        need_exclamation.insert(
+            "safe".into(),
+            Box::new(StringList(
+                |config| &mut config.safe_functions,
+                |config| &config.safe_functions,
+            )),
+        );
+        need_exclamation.insert(
+            "unsafe_fn".into(),
+            Box::new(StringList(
+                |config| &mut config.unsafe_functions,
+                |config| &config.unsafe_functions,
+            )),
+        );
         need_exclamation.insert(
             "block_constructors".into(),
             Box::new(StringList(
@@ -64,6 +80,21 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
                 |config| &config.instantiable,
             )),
         );
+        need_exclamation.insert(
+            "iterable".into(),
+            Box::new(StringList(
+                |config| &mut config.iterables,
+                |config| &config.iterables,
+            )),
+        );
+        need_exclamation.insert(
+            "clang_arg".into(),
+            Box::new(StringList(
+                |config| &mut config.clang_args,
+                |config| &config.clang_args,
+            )),
+        );
+        need_exclamation.insert("define".into(), Box::new(Define));
         need_exclamation.insert(
             "parse_only".into(),
             Box::new(BoolFlag(
@@ -87,6 +118,10 @@ pub(crate) fn get_directives() -> &'static DirectivesMap {
         );
         need_exclamation.insert("name".into(), Box::new(ModName));
         need_exclamation.insert("concrete".into(), Box::new(Concrete));
+        need_exclamation.insert("rename".into(), Box::new(Rename));
+        need_exclamation.insert("thread_safe".into(), Box::new(ThreadSafe));
+        need_exclamation.insert("pod_derive".into(), Box::new(PodDerive));
+        need_exclamation.insert("include_config".into(), Box::new(IncludeConfig));
         need_exclamation.insert("rust_type".into(), Box::new(RustType { output: false }));
         need_exclamation.insert(EXTERN_RUST_TYPE.into(), Box::new(RustType { output: true }));
         need_exclamation.insert(SUBCLASS.into(), Box::new(Subclass));
@@ -154,9 +189,20 @@ impl Directive for Generate {
         span: &Span,
     ) -> ParseResult<()> {
         let generate: syn::LitStr = args.parse()?;
+        let entry = if is_glob(&generate.value()) {
+            if self.0 {
+                return Err(syn::Error::new(
+                    generate.span(),
+                    "generate_pod! requires an exact type name, not a pattern",
+                ));
+            }
+            AllowlistEntry::Pattern(generate.value())
+        } else {
+            AllowlistEntry::Item(generate.value())
+        };
         config
             .allowlist
-            .push(AllowlistEntry::Item(generate.value()))
+            .push(entry)
             .map_err(|e| allowlist_err_to_syn_err(e, span))?;
         if self.0 {
             config.pod_requests.push(generate.value());
@@ -173,7 +219,7 @@ impl Directive for Generate {
                 items
                     .iter()
                     .flat_map(|i| match i {
-                        AllowlistEntry::Item(s) => Some(s),
+                        AllowlistEntry::Item(s) | AllowlistEntry::Pattern(s) => Some(s),
                         _ => None,
                     })
                     .map(|s| quote! { #s }),
@@ -274,6 +320,54 @@ impl Directive for Safety {
     }
 }
 
+struct Naming;
+
+impl Directive for Naming {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        config.naming_policy = args.parse()?;
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        match config.naming_policy {
+            crate::NamingPolicy::Cpp => Box::new(std::iter::empty()),
+            policy => Box::new(std::iter::once(policy.to_token_stream())),
+        }
+    }
+}
+
+struct OverloadNaming;
+
+impl Directive for OverloadNaming {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        config.overload_naming = args.parse()?;
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        match config.overload_naming {
+            crate::OverloadNaming::Sequential => Box::new(std::iter::empty()),
+            overload_naming => Box::new(std::iter::once(overload_naming.to_token_stream())),
+        }
+    }
+}
+
 fn allowlist_err_to_syn_err(err: AllowlistErr, span: &Span) -> syn::Error {
     syn::Error::new(*span, format!("{err}"))
 }
@@ -396,6 +490,161 @@ impl Directive for Concrete {
     }
 }
 
+struct Rename;
+
+impl Directive for Rename {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let cpp_name: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let rust_id: syn::Ident = args.parse()?;
+        config
+            .renames
+            .0
+            .insert(normalize_cpp_signature(&cpp_name.value()), rust_id);
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.renames.0.iter().map(|(k, v)| {
+            quote! {
+                #k,#v
+            }
+        }))
+    }
+}
+
+struct ThreadSafe;
+
+impl Directive for ThreadSafe {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let cpp_name: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let thread_safety: crate::ThreadSafety = args.parse()?;
+        config.thread_safe.0.insert(cpp_name.value(), thread_safety);
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.thread_safe.0.iter().map(|(k, v)| {
+            quote! {
+                #k,#v
+            }
+        }))
+    }
+}
+
+struct PodDerive;
+
+impl Directive for PodDerive {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let cpp_name: syn::LitStr = args.parse()?;
+        args.parse::<syn::token::Comma>()?;
+        let derives =
+            syn::punctuated::Punctuated::<crate::PodDerive, syn::token::Comma>::parse_separated_nonempty(
+                args,
+            )?;
+        let existing = config.pod_derives.0.entry(cpp_name.value()).or_default();
+        for derive in derives {
+            if !existing.contains(&derive) {
+                existing.push(derive);
+            }
+        }
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.pod_derives.0.iter().map(|(k, v)| {
+            quote! {
+                #k,#(#v),*
+            }
+        }))
+    }
+}
+
+struct IncludeConfig;
+
+impl Directive for IncludeConfig {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let path: syn::LitStr = args.parse()?;
+        // Relative paths are relative to the crate, whether we're being
+        // run from a build script, a proc macro or autocxx-gen.
+        let mut full_path = std::path::PathBuf::from(path.value());
+        if let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+            full_path = std::path::PathBuf::from(manifest_dir).join(full_path);
+        }
+        config
+            .load_config_file(&full_path)
+            .map_err(|err| syn::Error::new(path.span(), err.to_string()))
+    }
+
+    fn output<'a>(
+        &self,
+        _config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        // The other directives output whatever we loaded from the file.
+        Box::new(std::iter::empty())
+    }
+}
+
+struct Define;
+
+impl Directive for Define {
+    fn parse(
+        &self,
+        args: ParseStream,
+        config: &mut IncludeCppConfig,
+        _ident_span: &Span,
+    ) -> ParseResult<()> {
+        let name: syn::LitStr = args.parse()?;
+        let value = if args.parse::<Option<syn::token::Comma>>()?.is_some() {
+            Some(args.parse::<syn::LitStr>()?.value())
+        } else {
+            None
+        };
+        config.defines.push((name.value(), value));
+        Ok(())
+    }
+
+    fn output<'a>(
+        &self,
+        config: &'a IncludeCppConfig,
+    ) -> Box<dyn Iterator<Item = TokenStream> + 'a> {
+        Box::new(config.defines.iter().map(|(name, value)| match value {
+            None => quote! { #name },
+            Some(value) => quote! { #name, #value },
+        }))
+    }
+}
+
 struct RustType {
     output: bool,
 }
@@ -9,6 +9,7 @@
 #![forbid(unsafe_code)]
 
 mod config;
+mod config_file;
 mod directives;
 pub mod file_locations;
 mod multi_bindings;
@@ -16,8 +17,10 @@ mod path;
 mod subclass_attrs;
 
 pub use config::{
-    AllowlistEntry, ExternCppType, IncludeCppConfig, RustFun, Subclass, UnsafePolicy,
+    AllowlistEntry, ExternCppType, IncludeCppConfig, NamingPolicy, OverloadNaming, PodDerive,
+    RustFun, Subclass, ThreadSafety, UnsafePolicy,
 };
+pub use config_file::ConfigFileErr;
 use file_locations::FileLocationStrategy;
 pub use multi_bindings::{MultiBindings, MultiBindingsErr};
 pub use path::RustPath;
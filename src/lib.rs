@@ -20,8 +20,14 @@ mod reference_wrapper;
 mod rvalue_param;
 pub mod subclass;
 mod value_param;
+mod vector_builder;
+mod wide_string;
 
 pub use reference_wrapper::{AsCppMutRef, AsCppRef, CppMutRef, CppPin, CppRef, CppUniquePtrPin};
+pub use vector_builder::{collect_vector, VectorBuilder};
+pub use wide_string::WideStringError;
+#[doc(hidden)]
+pub use wide_string::{Utf16, Utf32, Wide, WideStringEncoding};
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// Include some C++ headers in your Rust project.
@@ -122,6 +128,9 @@ macro_rules! include {
 }
 
 /// Generate Rust bindings for the given C++ type or function.
+/// The name may be a glob pattern, in which `*` matches any sequence
+/// of characters and `?` any single character, for example
+/// `generate!("mylib::widgets::*Widget")`.
 /// A directive to be included inside
 /// [include_cpp] - see [include_cpp] for general information.
 /// See also [generate_pod].
@@ -186,6 +195,8 @@ macro_rules! exclude_utilities {
 /// otherwise generated.
 /// This is 'greedy' in the sense that any functions/methods
 /// which take or return such a type will _also_ be blocked.
+/// As with [generate], the name may be a glob pattern such as
+/// `block!("mylib::detail::*")`.
 ///
 /// A directive to be included inside
 /// [include_cpp] - see [include_cpp] for general information.
@@ -278,6 +289,74 @@ macro_rules! safety {
     ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
 }
 
+/// Marks a function, or all the methods of a type, as safe to call
+/// regardless of the overall [safety] policy, as if
+/// `safety!(unsafe_ffi)` applied just to them.
+/// ```ignore
+/// safe!("ns::Widget")
+/// safe!("ns::compute_total")
+/// ```
+/// Functions which take raw pointers remain `unsafe`.
+/// As with [generate], the name may be a glob pattern.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! safe {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Marks a function, or all the methods of a type, as `unsafe`
+/// regardless of the overall [safety] policy. Use this for APIs
+/// with preconditions which the caller must uphold.
+/// ```ignore
+/// unsafe_fn!("ns::Widget::set_len")
+/// ```
+/// If both this and [safe] apply to a function, the directive naming
+/// the function itself wins; failing that, `unsafe_fn!` wins.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! unsafe_fn {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Derives extra traits for a type generated using [generate_pod].
+/// ```ignore
+/// pod_derive!("ns::Point", Debug, PartialEq, Hash, serde::Serialize)
+/// ```
+/// The supported traits are `Clone`, `Copy`, `Debug`, `PartialEq`, `Eq`,
+/// `Hash`, `Default`, `serde::Serialize` and `serde::Deserialize`; the
+/// latter two require your crate to depend upon `serde` with its `derive`
+/// feature. autocxx will report an error if the type isn't generated, or
+/// if any field of the type doesn't implement the requested trait. `Clone` and `Copy` copy the object
+/// bitwise, so autocxx also checks in C++ that the type is trivially
+/// copyable.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! pod_derive {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Declares that an opaque C++ type may be sent between threads (`Send`)
+/// and/or shared between threads (`Sync`).
+/// ```ignore
+/// thread_safe!("ns::Cache", Send, Sync)
+/// ```
+/// autocxx will then emit the corresponding `unsafe impl`s, which also
+/// make `UniquePtr` and `SharedPtr` of that type `Send` or `Sync`.
+/// It is your responsibility to ensure the C++ type really is thread-safe.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! thread_safe {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
 /// Whether to avoid generating [`cxx::UniquePtr`] and [`cxx::Vector`]
 /// implementations. This is primarily useful for reducing test cases and
 /// shouldn't be used in normal operation.
@@ -392,6 +471,126 @@ macro_rules! instantiable {
     ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
 }
 
+/// Asks autocxx to generate an `iter()` method for a C++ type with
+/// `begin() const` and `end() const` methods and a nested `value_type`,
+/// promising that dereferencing its iterators gives a reference to an
+/// element. (Iterators which instead give a proxy or a value, as for
+/// `std::vector<bool>`, can't be used from Rust.) autocxx does this
+/// without being asked if `begin()` returns a plain pointer.
+/// ```ignore
+/// iterable!("ns::WidgetList")
+/// ```
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! iterable {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Defines a preprocessor macro while autocxx parses the headers
+/// in this [include_cpp] block. The syntax is
+/// `define!("NAME")` or `define!("NAME", "VALUE")`.
+///
+/// This affects only autocxx's own parsing of the headers. You must also
+/// pass the same definition to the C++ compiler when building the
+/// generated C++ code, or the two may disagree about the C++ API.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! define {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Passes an extra argument to clang while autocxx parses the headers
+/// in this [include_cpp] block, for example
+/// `clang_arg!("-fno-exceptions")`. These arguments are applied after any
+/// passed in by the build system (for instance using
+/// `Builder::extra_clang_args`) so take precedence over them.
+///
+/// As with [define], you must also pass equivalent flags when compiling
+/// the generated C++ code.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! clang_arg {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Chooses how to name the Rust equivalents of C++ APIs.
+/// `naming!(rust)` renames functions and methods to snake_case
+/// (so `GetWidth` becomes `get_width`) and types to UpperCamelCase
+/// (so `http_response` becomes `HttpResponse`). The C++ names are
+/// unaffected, so linkage still works. `naming!(cpp)`, the default,
+/// keeps the C++ spelling.
+///
+/// If renaming a function or method would make it clash with another
+/// one, autocxx doesn't generate it, and explains why in the
+/// generated documentation. Types whose new names would clash keep
+/// their C++ names.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! naming {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Loads further directives from a TOML file, so that long allowlists
+/// can be kept outside the `include_cpp!` block and shared between
+/// crates. Relative paths are relative to the crate's `Cargo.toml`.
+/// ```ignore
+/// include_config!("bindings.toml")
+/// ```
+/// The file may contain `safety`, `generate`, `pod`, `block`, `rename`
+/// and `extern_cpp_type` settings. See the book for the format.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! include_config {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Chooses how to name overloaded functions and methods.
+/// By default (`overload_naming!(sequential)`) they're called `func`,
+/// `func1`, `func2` etc. in the order they appear in the header, so adding
+/// an overload can change which function `func1` refers to.
+/// `overload_naming!(types)` instead names each overload after its
+/// parameter types (`set_i32`, `set_str`) and `overload_naming!(count)`
+/// after its number of parameters (`set_1`, `set_2`). An overload with
+/// no parameters keeps the plain name. If two overloads would get the
+/// same name, autocxx falls back to numbering them.
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! overload_naming {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
+/// Chooses the Rust name for a specific C++ item. The first argument is
+/// the fully-qualified C++ name; the second is the Rust identifier to use.
+/// ```ignore
+/// rename!("ns::Type::method", method_renamed)
+/// ```
+/// To pick out one overload of a function or method, list the C++
+/// parameter types after its name:
+/// ```ignore
+/// rename!("ns::Type::method(int, const std::string&)", method_with_str)
+/// ```
+/// Overloads which aren't renamed keep the usual `method`, `method1` etc.
+/// names. `rename!` takes precedence over [naming].
+///
+/// A directive to be included inside
+/// [include_cpp] - see [include_cpp] for general information.
+#[macro_export]
+macro_rules! rename {
+    ($($tt:tt)*) => { $crate::usage!{$($tt)*} };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! usage {
@@ -417,7 +616,7 @@ pub use autocxx_macro::cpp_semantics;
 macro_rules! ctype_wrapper {
     ($r:ident, $c:expr, $d:expr) => {
         #[doc=$d]
-        #[derive(Debug, Eq, Copy, Clone, PartialEq, Hash)]
+        #[derive(Debug, Eq, Copy, Clone, PartialEq, Hash, Default)]
         #[allow(non_camel_case_types)]
         #[repr(transparent)]
         pub struct $r(pub ::std::os::raw::$r);
@@ -628,6 +827,47 @@ where
     }
 }
 
+/// A C++ type which can be moved into a newly allocated `std::shared_ptr`,
+/// using `std::make_shared`. `autocxx` implements this for each type it
+/// generates which has an accessible move constructor and destructor.
+///
+/// It is not recommended that you implement this trait, nor that you directly
+/// use its methods, which are for use by `autocxx` generated code only.
+/// Use [`WithinSharedPtr`] or [`WithinSharedPtrTrivial`].
+pub trait MakeShared: SharedPtrTarget + Sized {
+    /// Move the contents of `obj` into a new `std::shared_ptr`, leaving
+    /// `obj` in its moved-from state.
+    fn make_shared(obj: Pin<&mut Self>) -> cxx::SharedPtr<Self>;
+}
+
+/// Provides utility functions to emplace any [`moveit::New`] into a
+/// [`cxx::SharedPtr`]. Automatically imported by the autocxx prelude
+/// and implemented by any (autocxx-related) [`moveit::New`].
+///
+/// The object is first constructed on the Rust stack, and then moved into
+/// the `std::shared_ptr` using its C++ move constructor. Types which are
+/// used within a `std::shared_ptr` also get `make_shared` associated
+/// functions, which construct the object in place instead. Use
+/// [`cxx::SharedPtr::downgrade`] and [`cxx::WeakPtr::upgrade`] to convert
+/// to and from a [`cxx::WeakPtr`].
+pub trait WithinSharedPtr {
+    type Inner: MakeShared;
+    /// Create this item within a [`cxx::SharedPtr`].
+    fn within_shared_ptr(self) -> cxx::SharedPtr<Self::Inner>;
+}
+
+impl<N, T> WithinSharedPtr for N
+where
+    N: New<Output = T>,
+    T: MakeShared,
+{
+    type Inner = T;
+    fn within_shared_ptr(self) -> cxx::SharedPtr<T> {
+        moveit::moveit! { let mut obj = self; }
+        T::make_shared(obj.as_mut())
+    }
+}
+
 /// Emulates the [`WithinUniquePtr`] trait, but for trivial (plain old data) types.
 /// This allows such types to behave identically if a type is changed from
 /// `generate!` to `generate_pod!`.
@@ -668,6 +908,23 @@ where
     }
 }
 
+/// Emulates the [`WithinSharedPtr`] trait, but for trivial (plain old data) types.
+/// This allows such types to behave identically if a type is changed from
+/// `generate!` to `generate_pod!`.
+pub trait WithinSharedPtrTrivial: MakeShared + Unpin {
+    fn within_shared_ptr(self) -> cxx::SharedPtr<Self>;
+}
+
+impl<T> WithinSharedPtrTrivial for T
+where
+    T: MakeShared + ExternType<Kind = Trivial> + Unpin,
+{
+    fn within_shared_ptr(mut self) -> cxx::SharedPtr<T> {
+        T::make_shared(Pin::new(&mut self))
+    }
+}
+
+use cxx::memory::SharedPtrTarget;
 use cxx::memory::UniquePtrTarget;
 use cxx::UniquePtr;
 use moveit::New;
@@ -707,6 +964,8 @@ pub mod prelude {
     pub use crate::ValueParam;
     pub use crate::WithinBox;
     pub use crate::WithinBoxTrivial;
+    pub use crate::WithinSharedPtr;
+    pub use crate::WithinSharedPtrTrivial;
     pub use crate::WithinUniquePtr;
     pub use crate::WithinUniquePtrTrivial;
     pub use cxx::UniquePtr;
@@ -0,0 +1,76 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use cxx::{vector::VectorElement, CxxVector, UniquePtr};
+use std::pin::Pin;
+
+/// A C++ type which can be appended to a `std::vector` from Rust, given
+/// an `Item`. `autocxx` implements this for each movable type it generates
+/// which is used within a `std::vector` by the APIs it generates:
+///
+/// * For POD types, `Item` is `T`, which is moved into the vector. If the
+///   type is also `Copy` (for instance because you asked for it using
+///   `pod_derive!`) `Item` may also be `&T`.
+/// * For non-POD types, `Item` is `UniquePtr<T>`; the object is moved
+///   into the vector, using its C++ move constructor, and the
+///   moved-from husk is then dropped.
+///
+/// It is not recommended that you implement this trait, nor that you directly
+/// use its methods, which are for use by `autocxx` generated code only.
+/// Use [`collect_vector`] or, for POD types,
+/// `iter.collect::<UniquePtr<CxxVector<T>>>()`.
+pub trait VectorBuilder<Item>: VectorElement {
+    /// Make a new, empty vector.
+    fn new_vector() -> UniquePtr<CxxVector<Self>>;
+    /// Make space for at least `additional` more elements.
+    fn reserve(vec: Pin<&mut CxxVector<Self>>, additional: usize);
+    /// Append `item` to the end of the vector.
+    fn push(vec: Pin<&mut CxxVector<Self>>, item: Item);
+}
+
+/// Builds a new C++ `std::vector` from the items yielded by an iterator.
+///
+/// For POD types you can simply `collect` into a
+/// `UniquePtr<CxxVector<T>>`, but Rust's orphan rules don't allow `autocxx`
+/// to offer that for non-POD types, which are collected from
+/// `UniquePtr<T>`s:
+///
+/// ```ignore
+/// let vec: UniquePtr<CxxVector<ffi::Goat>> =
+///     autocxx::collect_vector((0..3).map(|_| ffi::Goat::new().within_unique_ptr()));
+/// ```
+pub fn collect_vector<T, I>(iter: I) -> UniquePtr<CxxVector<T>>
+where
+    I: IntoIterator,
+    T: VectorBuilder<I::Item>,
+{
+    let iter = iter.into_iter();
+    let mut vec = T::new_vector();
+    T::reserve(vec.pin_mut(), iter.size_hint().0);
+    for item in iter {
+        T::push(vec.pin_mut(), item);
+    }
+    vec
+}
+
+impl<'a, T> VectorBuilder<&'a T> for T
+where
+    T: VectorBuilder<T> + Copy,
+{
+    fn new_vector() -> UniquePtr<CxxVector<Self>> {
+        <T as VectorBuilder<T>>::new_vector()
+    }
+
+    fn reserve(vec: Pin<&mut CxxVector<Self>>, additional: usize) {
+        <T as VectorBuilder<T>>::reserve(vec, additional)
+    }
+
+    fn push(vec: Pin<&mut CxxVector<Self>>, item: &'a T) {
+        <T as VectorBuilder<T>>::push(vec, *item)
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for C++ strings of characters wider than a byte. The string
+//! types themselves, and the functions to convert them, are generated
+//! alongside the rest of the bindings, since they need some C++ help.
+
+/// Error returned when a C++ wide string isn't valid UTF-16 or UTF-32, and so
+/// can't be converted to a Rust string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WideStringError;
+
+impl std::fmt::Display for WideStringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("C++ string contains invalid UTF-16 or UTF-32")
+    }
+}
+
+impl std::error::Error for WideStringError {}
+
+/// How the code units of a wide string, as exchanged with C++, relate to
+/// Unicode. Used by generated code, with the marker types below.
+#[doc(hidden)]
+pub trait WideStringEncoding {
+    type CodeUnit;
+
+    fn decode(units: &[Self::CodeUnit]) -> Result<String, WideStringError>;
+    fn decode_lossy(units: &[Self::CodeUnit]) -> String;
+    fn encode(s: &str) -> Vec<Self::CodeUnit>;
+}
+
+/// `std::u16string`.
+#[doc(hidden)]
+pub enum Utf16 {}
+
+/// `std::u32string`.
+#[doc(hidden)]
+pub enum Utf32 {}
+
+/// `std::wstring`.
+#[doc(hidden)]
+pub enum Wide {}
+
+fn decode_utf32(units: &[u32]) -> Result<String, WideStringError> {
+    units
+        .iter()
+        .map(|&unit| char::from_u32(unit).ok_or(WideStringError))
+        .collect()
+}
+
+fn decode_utf32_lossy(units: &[u32]) -> String {
+    units
+        .iter()
+        .map(|&unit| char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+impl WideStringEncoding for Utf16 {
+    type CodeUnit = u16;
+
+    fn decode(units: &[u16]) -> Result<String, WideStringError> {
+        String::from_utf16(units).map_err(|_| WideStringError)
+    }
+
+    fn decode_lossy(units: &[u16]) -> String {
+        String::from_utf16_lossy(units)
+    }
+
+    fn encode(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+}
+
+impl WideStringEncoding for Utf32 {
+    type CodeUnit = u32;
+
+    fn decode(units: &[u32]) -> Result<String, WideStringError> {
+        decode_utf32(units)
+    }
+
+    fn decode_lossy(units: &[u32]) -> String {
+        decode_utf32_lossy(units)
+    }
+
+    fn encode(s: &str) -> Vec<u32> {
+        s.chars().map(u32::from).collect()
+    }
+}
+
+/// `wchar_t` is UTF-16 on Windows and UTF-32 elsewhere. We always exchange
+/// 32-bit code units with C++, which can represent either.
+impl WideStringEncoding for Wide {
+    type CodeUnit = u32;
+
+    fn decode(units: &[u32]) -> Result<String, WideStringError> {
+        if cfg!(windows) {
+            let units: Vec<u16> = units.iter().map(|&unit| unit as u16).collect();
+            Utf16::decode(&units)
+        } else {
+            decode_utf32(units)
+        }
+    }
+
+    fn decode_lossy(units: &[u32]) -> String {
+        if cfg!(windows) {
+            let units: Vec<u16> = units.iter().map(|&unit| unit as u16).collect();
+            Utf16::decode_lossy(&units)
+        } else {
+            decode_utf32_lossy(units)
+        }
+    }
+
+    fn encode(s: &str) -> Vec<u32> {
+        if cfg!(windows) {
+            s.encode_utf16().map(u32::from).collect()
+        } else {
+            Utf32::encode(s)
+        }
+    }
+}
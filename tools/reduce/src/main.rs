@@ -16,7 +16,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use autocxx_engine::{get_clang_path, make_clang_args, preprocess};
+use autocxx_engine::{get_clang_path, make_clang_args, preprocess, CppStandard};
 use autocxx_parser::IncludeCppConfig;
 use clap::{crate_authors, crate_version, Arg, ArgMatches, Command};
 use indexmap::IndexSet;
@@ -179,6 +179,15 @@ fn main() {
                 .value_name("CLANG_ARG")
                 .help("Extra arguments to pass to Clang"),
         )
+        .arg(
+            Arg::new("std")
+                .long("std")
+                .value_name("STANDARD")
+                .help("version of the C++ standard to use when preprocessing, generating bindings and compiling")
+                .possible_values(["c++14", "c++17", "c++20"])
+                .default_value("c++14")
+                .takes_value(true),
+        )
         .arg(
             Arg::new("creduce-args")
                 .long("creduce-arg")
@@ -238,6 +247,7 @@ struct ReproCase {
 fn do_run(matches: ArgMatches, tmp_dir: &TempDir) -> Result<(), std::io::Error> {
     let rs_path = tmp_dir.path().join("input.rs");
     let concat_path = tmp_dir.path().join("concat.h");
+    let cpp_standard = get_cpp_standard(&matches);
     match matches.subcommand_matches("repro") {
         None => {
             let submatches = matches.subcommand_matches("file").unwrap();
@@ -254,7 +264,7 @@ fn do_run(matches: ArgMatches, tmp_dir: &TempDir) -> Result<(), std::io::Error>
             announce_progress(&format!(
                 "Preprocessing {listing_path:?} to {concat_path:?}"
             ));
-            preprocess(&listing_path, &concat_path, &incs, &defs)?;
+            preprocess(&listing_path, &concat_path, &incs, cpp_standard, &defs)?;
             let directives: Vec<_> = std::iter::once("#include \"concat.h\"\n".to_string())
                 .chain(
                     submatches
@@ -313,7 +323,13 @@ fn do_run(matches: ArgMatches, tmp_dir: &TempDir) -> Result<(), std::io::Error>
         );
     }
 
-    run_sample_gen_cmd(gen_cmd, &rs_path, tmp_dir.path(), &extra_clang_args)?;
+    run_sample_gen_cmd(
+        gen_cmd,
+        &rs_path,
+        tmp_dir.path(),
+        cpp_standard,
+        &extra_clang_args,
+    )?;
     // Create and run an interestingness test which does not filter its output through grep.
     let demo_interestingness_test_dir = tmp_dir.path().join("demo-interestingness-test");
     std::fs::create_dir(&demo_interestingness_test_dir).unwrap();
@@ -428,9 +444,15 @@ fn run_sample_gen_cmd(
     gen_cmd: &str,
     rs_file: &Path,
     tmp_dir: &Path,
+    cpp_standard: CppStandard,
     extra_clang_args: &[&str],
 ) -> Result<(), std::io::Error> {
-    let args = format_gen_cmd(rs_file, tmp_dir.to_str().unwrap(), extra_clang_args);
+    let args = format_gen_cmd(
+        rs_file,
+        tmp_dir.to_str().unwrap(),
+        cpp_standard,
+        extra_clang_args,
+    );
     let args = args.collect::<Vec<_>>();
     let args_str = args.join(" ");
     announce_progress(&format!("Running sample gen cmd: {gen_cmd} {args_str}"));
@@ -452,6 +474,7 @@ fn run_demo_interestingness_test(demo_dir: &Path, test: &Path) -> Result<(), std
 fn format_gen_cmd<'a>(
     rs_file: &Path,
     dir: &str,
+    cpp_standard: CppStandard,
     extra_clang_args: &'a [&str],
 ) -> impl Iterator<Item = String> + 'a {
     let args = [
@@ -463,6 +486,8 @@ fn format_gen_cmd<'a>(
         "--gen-rs-include".to_string(),
         "--gen-cpp".to_string(),
         "--suppress-system-headers".to_string(),
+        "--std".to_string(),
+        cpp_standard.to_string().to_ascii_lowercase(),
         "--".to_string(),
     ]
     .to_vec();
@@ -482,6 +507,7 @@ fn create_interestingness_test(
     let precompile = !matches.is_present("no-precompile");
     let postcompile = !matches.is_present("no-postcompile");
     let rustc = !matches.is_present("no-rustc");
+    let cpp_standard = get_cpp_standard(matches);
 
     let rustc_path = matches.value_of("rustc").unwrap();
 
@@ -494,12 +520,13 @@ fn create_interestingness_test(
     // Ensure we refer to the input header by relative path
     // because creduce will invoke us in some other directory with
     // a copy thereof.
-    let mut args = format_gen_cmd(rs_file, "$(pwd)", extra_clang_args);
+    let mut args = format_gen_cmd(rs_file, "$(pwd)", cpp_standard, extra_clang_args);
     let args = args.join(" ");
-    let precompile_step = make_compile_step(precompile, "concat.h", extra_clang_args);
+    let precompile_step = make_compile_step(precompile, "concat.h", cpp_standard, extra_clang_args);
     // For the compile afterwards, we have to avoid including any system headers.
     // We rely on equivalent content being hermetically inside concat.h.
-    let postcompile_step = make_compile_step(postcompile, "gen0.cc", extra_clang_args);
+    let postcompile_step =
+        make_compile_step(postcompile, "gen0.cc", cpp_standard, extra_clang_args);
     let rustc_step = if rustc {
         let rust_libs_path = rust_libs_path.iter().map(|p| format!(" -L{p}")).join(" ");
         format!("{rustc_path} --extern cxx --extern autocxx {rust_libs_path} --crate-type rlib --emit=metadata --edition=2021 autocxx-ffi-default-gen.rs 2>&1")
@@ -544,12 +571,17 @@ fn create_interestingness_test(
     Ok(())
 }
 
-fn make_compile_step(enabled: bool, file: &str, extra_clang_args: &[&str]) -> String {
+fn make_compile_step(
+    enabled: bool,
+    file: &str,
+    cpp_standard: CppStandard,
+    extra_clang_args: &[&str],
+) -> String {
     if enabled {
         format!(
             "{} {} -c {}",
             get_clang_path(),
-            make_clang_args(&[PathBuf::from(".")], extra_clang_args).join(" "),
+            make_clang_args(&[PathBuf::from(".")], cpp_standard, extra_clang_args).join(" "),
             file,
         )
     } else {
@@ -557,6 +589,11 @@ fn make_compile_step(enabled: bool, file: &str, extra_clang_args: &[&str]) -> St
     }
 }
 
+fn get_cpp_standard(matches: &ArgMatches) -> CppStandard {
+    // Already validated by clap.
+    matches.value_of("std").unwrap().parse().unwrap()
+}
+
 fn create_rs_file(rs_path: &Path, directives: &[String]) -> Result<(), std::io::Error> {
     announce_progress("Creating Rust input file");
     let mut file = File::create(rs_path)?;